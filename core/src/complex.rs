@@ -0,0 +1,163 @@
+use crate::float;
+
+use std::fmt;
+use std::ops::{Add, Div, Mul, Sub};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Complex {
+    pub re: f64,
+    pub im: f64,
+}
+
+impl Complex {
+    pub fn new(re: f64, im: f64) -> Complex {
+        Complex { re, im }
+    }
+
+    pub fn from_polar(norm: f64, angle: f64) -> Complex {
+        Complex::new(norm * angle.cos(), norm * angle.sin())
+    }
+
+    pub fn norm(&self) -> f64 {
+        self.re.hypot(self.im)
+    }
+
+    /// `a ± bi`, the root and its conjugate at once.
+    pub fn plus_minus(&self) -> String {
+        let im = float::shortest(self.im.abs() as f32);
+        match self.re.abs() <= 1e-9 * self.norm().max(1.0) {
+            true => format!("±{}i", im),
+            false => format!("{} ± {}i", float::shortest(self.re as f32), im),
+        }
+    }
+
+    /// `plus_minus` in LaTeX, `a \pm bi`.
+    pub fn plus_minus_latex(&self) -> String {
+        match self.plus_minus().split_once('±') {
+            Some((re, im)) => format!("{}\\pm {}", re, im.trim_start()),
+            None => self.plus_minus(),
+        }
+    }
+}
+
+/// One root of each conjugate pair, the one with a positive imaginary part,
+/// by increasing real part. The roots off the real axis of a polynomial with
+/// real coefficients come in such pairs: a root within `tolerance` of the
+/// axis, relative to its magnitude, is real, and one without a conjugate
+/// that close is left out as an artifact of the solver.
+pub fn conjugate_pairs(roots: &[Complex], tolerance: f64) -> Vec<Complex> {
+    let near = |a: Complex, b: Complex| (a - b).norm() <= tolerance * a.norm().max(1.0);
+    let off_axis = |z: &&Complex| z.im.abs() > tolerance * z.norm().max(1.0);
+    let mut lower: Vec<Complex> = roots
+        .iter()
+        .filter(off_axis)
+        .filter(|z| z.im < 0.0)
+        .copied()
+        .collect();
+    let mut upper = vec![];
+    for &z in roots.iter().filter(off_axis).filter(|z| z.im > 0.0) {
+        let conjugate = Complex::new(z.re, -z.im);
+        if let Some(i) = lower.iter().position(|&w| near(w, conjugate)) {
+            lower.swap_remove(i);
+            upper.push(z);
+        }
+    }
+    upper.sort_by(|a, b| a.re.total_cmp(&b.re).then(a.im.total_cmp(&b.im)));
+    upper
+}
+
+impl From<f64> for Complex {
+    fn from(re: f64) -> Complex {
+        Complex::new(re, 0.0)
+    }
+}
+
+impl Add for Complex {
+    type Output = Complex;
+
+    fn add(self, other: Complex) -> Complex {
+        Complex::new(self.re + other.re, self.im + other.im)
+    }
+}
+
+impl Sub for Complex {
+    type Output = Complex;
+
+    fn sub(self, other: Complex) -> Complex {
+        Complex::new(self.re - other.re, self.im - other.im)
+    }
+}
+
+impl Mul for Complex {
+    type Output = Complex;
+
+    fn mul(self, other: Complex) -> Complex {
+        Complex::new(
+            self.re * other.re - self.im * other.im,
+            self.re * other.im + self.im * other.re,
+        )
+    }
+}
+
+impl Div for Complex {
+    type Output = Complex;
+
+    fn div(self, other: Complex) -> Complex {
+        let denominator = other.re * other.re + other.im * other.im;
+        Complex::new(
+            (self.re * other.re + self.im * other.im) / denominator,
+            (self.im * other.re - self.re * other.im) / denominator,
+        )
+    }
+}
+
+impl fmt::Display for Complex {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let epsilon = 1e-9 * self.norm().max(1.0);
+        if self.im.abs() <= epsilon {
+            write!(f, "{}", float::shortest(self.re as f32))
+        } else if self.re.abs() <= epsilon {
+            write!(f, "{}i", float::shortest(self.im as f32))
+        } else {
+            let re = float::shortest(self.re as f32);
+            let im = float::shortest(self.im.abs() as f32);
+            let sign = if self.im < 0.0 { '-' } else { '+' };
+            write!(f, "{} {} {}i", re, sign, im)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plus_minus_forms() {
+        let roots = [
+            Complex::new(0.0, -1.0),
+            Complex::new(-1.0, 2.0),
+            Complex::new(0.0, 1.0),
+            Complex::new(-1.0, -2.0),
+        ];
+        let pairs = conjugate_pairs(&roots, 0.0);
+        assert_eq!(pairs, [Complex::new(-1.0, 2.0), Complex::new(0.0, 1.0)]);
+        assert_eq!(pairs[0].plus_minus(), "-1 ± 2i");
+        assert_eq!(pairs[1].plus_minus(), "±1i");
+        assert_eq!(pairs[0].plus_minus_latex(), "-1 \\pm 2i");
+        assert_eq!(pairs[1].plus_minus_latex(), "\\pm 1i");
+    }
+
+    #[test]
+    fn near_real_and_unpaired_roots_are_left_out() {
+        // around a triple root, without a conjugate for the last one
+        let roots = [
+            Complex::new(0.99999845, 5.5e-7),
+            Complex::new(0.99999845, -5.5e-7),
+            Complex::new(1.0000031, 1e-3),
+            Complex::new(2.0, 3.0),
+            Complex::new(2.0, -3.0 + 1e-12),
+        ];
+        assert_eq!(conjugate_pairs(&roots, 1e-6), [Complex::new(2.0, 3.0)]);
+        assert_eq!(conjugate_pairs(&roots, 0.0).len(), 1);
+    }
+}
@@ -0,0 +1,205 @@
+use crate::decimal;
+use std::fmt;
+
+/// Past this degree cyclotomic polynomials are not looked for.
+const MAX_DEGREE: usize = 64;
+
+/// A polynomial known to be a product of cyclotomic polynomials, up to a
+/// constant factor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub enum Family {
+    /// Φn, whose roots are the primitive n-th roots of unity.
+    Cyclotomic(usize),
+    /// X^n - 1, the product of Φd for every d dividing n.
+    MinusOne(usize),
+    /// X^n + 1, the product of Φd for every d dividing 2n but not n.
+    PlusOne(usize),
+}
+
+impl fmt::Display for Family {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Family::Cyclotomic(n) => write!(f, "Φ{}", n),
+            Family::MinusOne(n) => write!(f, "X^{} - 1", n),
+            Family::PlusOne(n) => write!(f, "X^{} + 1", n),
+        }
+    }
+}
+
+impl Family {
+    /// The `d` of the Φd whose product it is, increasing.
+    pub fn indices(self) -> Vec<usize> {
+        match self {
+            Family::Cyclotomic(n) => vec![n],
+            Family::MinusOne(n) => (1..=n).filter(|&d| n.is_multiple_of(d)).collect(),
+            Family::PlusOne(n) => (1..=2 * n)
+                .filter(|&d| (2 * n).is_multiple_of(d) && !n.is_multiple_of(d))
+                .collect(),
+        }
+    }
+
+    /// Every complex root, as an exact root of unity.
+    pub fn roots(self) -> String {
+        match self {
+            Family::Cyclotomic(1) => String::from("X = 1"),
+            Family::Cyclotomic(2) => String::from("X = -1"),
+            Family::Cyclotomic(n) => format!(
+                "the primitive {} roots of unity, X = e^(2πik/{}) for 0 < k < {} coprime to {}",
+                ordinal(n),
+                n,
+                n,
+                n
+            ),
+            Family::MinusOne(n) => format!(
+                "the {} roots of unity, X = e^(2πik/{}) for k = 0..{}",
+                ordinal(n),
+                n,
+                n - 1
+            ),
+            Family::PlusOne(n) => format!(
+                "the {} roots of -1, X = e^(iπ(2k + 1)/{}) for k = 0..{}",
+                ordinal(n),
+                n,
+                n - 1
+            ),
+        }
+    }
+
+    /// The real roots, increasing: 1 from Φ1 and -1 from Φ2.
+    pub fn real_roots(self) -> Vec<f32> {
+        let indices = self.indices();
+        [(2, -1.0), (1, 1.0)]
+            .iter()
+            .filter(|(d, _)| indices.contains(d))
+            .map(|&(_, root)| root)
+            .collect()
+    }
+}
+
+fn ordinal(n: usize) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{}{}", n, suffix)
+}
+
+/// Euler's φ, from the prime factors.
+fn totient(mut n: usize) -> usize {
+    let mut result = n;
+    let mut p = 2;
+    while p * p <= n {
+        if n.is_multiple_of(p) {
+            while n.is_multiple_of(p) {
+                n /= p;
+            }
+            result -= result / p;
+        }
+        p += 1;
+    }
+    if n > 1 {
+        result -= result / n;
+    }
+    result
+}
+
+/// Φn, coefficients by ascending degree, from X^n - 1 divided by every Φd
+/// with `d` a proper divisor of `n`.
+pub fn polynomial(n: usize) -> Vec<i128> {
+    let mut p = vec![0; n + 1];
+    p[0] = -1;
+    p[n] = 1;
+    for d in (1..n).filter(|&d| n.is_multiple_of(d)) {
+        let divisor = polynomial(d);
+        // monic, the division is exact
+        let mut quotient = vec![0; p.len() - divisor.len() + 1];
+        while p.len() >= divisor.len() {
+            let factor = p[p.len() - 1];
+            let shift = p.len() - divisor.len();
+            for (i, c) in divisor.iter().enumerate() {
+                p[shift + i] -= factor * c;
+            }
+            quotient[shift] = factor;
+            p.pop();
+        }
+        p = quotient;
+    }
+    p
+}
+
+/// The family of a polynomial, up to a constant factor, of degree 3 or
+/// more. The coefficients are compared exactly, as written.
+pub fn recognize(coefficients: &[f32]) -> Option<Family> {
+    let degree = coefficients.len().checked_sub(1)?;
+    if !(3..=MAX_DEGREE).contains(&degree) {
+        return None;
+    }
+    let (integers, _) = decimal::scaled_integers(coefficients)?;
+    let lead = integers[degree];
+    if integers.iter().any(|c| c % lead != 0) {
+        return None;
+    }
+    let monic: Vec<i128> = integers.iter().map(|c| c / lead).collect();
+    if monic[1..degree].iter().all(|&c| c == 0) {
+        match monic[0] {
+            -1 => return Some(Family::MinusOne(degree)),
+            1 => return Some(Family::PlusOne(degree)),
+            _ => {}
+        }
+    }
+    // φ(n) >= √(n / 2)
+    (1..=2 * degree * degree)
+        .filter(|&n| totient(n) == degree)
+        .find(|&n| polynomial(n) == monic)
+        .map(Family::Cyclotomic)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Poly;
+
+    fn family(equation: &str) -> Option<Family> {
+        recognize(Poly::new(equation).unwrap().coefficients())
+    }
+
+    #[test]
+    fn cyclotomic_polynomials() {
+        assert_eq!(polynomial(1), [-1, 1]);
+        assert_eq!(polynomial(6), [1, -1, 1]);
+        assert_eq!(polynomial(12), [1, 0, -1, 0, 1]);
+        // the first with a coefficient other than 0 and ±1
+        assert!(polynomial(105).contains(&-2));
+        let product = Family::MinusOne(12).indices();
+        assert_eq!(product, [1, 2, 3, 4, 6, 12]);
+        assert_eq!(product.iter().map(|&d| totient(d)).sum::<usize>(), 12);
+        assert_eq!(Family::PlusOne(6).indices(), [4, 12]);
+    }
+
+    #[test]
+    fn families_are_recognized() {
+        assert_eq!(family("X^6 - 1 = 0"), Some(Family::MinusOne(6)));
+        assert_eq!(family("2 * X^5 + 2 = 0"), Some(Family::PlusOne(5)));
+        assert_eq!(
+            family("X^4 + X^3 + X^2 + X + 1 = 0"),
+            Some(Family::Cyclotomic(5))
+        );
+        assert_eq!(family("X^4 - X^2 + 1 = 0"), Some(Family::Cyclotomic(12)));
+        assert_eq!(family("X^4 + X^2 + 1 = 0"), None);
+        assert_eq!(family("X^2 + X + 1 = 0"), None);
+        assert_eq!(family("X^3 - 2 = 0"), None);
+        assert_eq!(Family::MinusOne(6).real_roots(), [-1.0, 1.0]);
+        assert_eq!(Family::PlusOne(5).real_roots(), [-1.0]);
+        assert!(Family::Cyclotomic(5).real_roots().is_empty());
+        assert!(Family::MinusOne(3)
+            .roots()
+            .starts_with("the 3rd roots of unity"));
+        assert!(Family::PlusOne(12)
+            .roots()
+            .starts_with("the 12th roots of -1"));
+    }
+}
@@ -0,0 +1,392 @@
+use crate::float::{self, Big};
+
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// How ties are broken when rounding to a number of decimal places.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Rounding {
+    /// Ties away from zero: 2.5 -> 3, -2.5 -> -3
+    #[default]
+    HalfUp,
+    /// Ties to the even digit, as in banking: 2.5 -> 2, 3.5 -> 4
+    HalfEven,
+}
+
+/// A solution of a degree 1 or 2 equation, computed without loss from the
+/// decimal coefficients.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Exact {
+    /// `numerator / denominator`, the denominator being positive
+    Rational(i128, i128),
+    /// `(m + sign * √d) / q`, `d` not being a perfect square and `q` being
+    /// positive
+    Radical {
+        m: i128,
+        sign: i128,
+        d: i128,
+        q: i128,
+    },
+}
+
+impl Exact {
+    fn radical(m: i128, sign: i128, d: i128, q: i128) -> Exact {
+        if q < 0 {
+            Exact::Radical {
+                m: -m,
+                sign: -sign,
+                d,
+                q: -q,
+            }
+        } else {
+            Exact::Radical { m, sign, d, q }
+        }
+    }
+
+    fn rational(numerator: i128, denominator: i128) -> Exact {
+        let sign = denominator.signum();
+        Exact::Rational(sign * numerator, sign * denominator)
+    }
+
+    pub fn to_f64(self) -> f64 {
+        match self {
+            Exact::Rational(p, q) => p as f64 / q as f64,
+            Exact::Radical { m, sign, d, q } => {
+                (m as f64 + sign as f64 * (d as f64).sqrt()) / q as f64
+            }
+        }
+    }
+
+    /// The value with `digits` decimal places, prefixed with `≈` unless it
+    /// is exact, computed on integers of any size.
+    pub fn round(&self, digits: usize, rounding: Rounding) -> String {
+        let (rounded, negative, exact) = match *self {
+            Exact::Rational(p, q) => {
+                let mut scaled = Big::from_u128(p.unsigned_abs());
+                scale(&mut scaled, digits);
+                let q = Big::from_u128(q.unsigned_abs());
+                let (k, r) = scaled.div_rem(&q);
+                let mut twice = r.clone();
+                twice.mul_small(2);
+                // on the magnitude, away from zero is up
+                let up = match twice.compare(&q) {
+                    Ordering::Less => false,
+                    Ordering::Greater => true,
+                    Ordering::Equal => match rounding {
+                        Rounding::HalfUp => true,
+                        Rounding::HalfEven => k.is_odd(),
+                    },
+                };
+                let k = if up { k.add(&Big::new(1)) } else { k };
+                (k, p < 0, r.is_zero())
+            }
+            Exact::Radical { m, sign, d, q } => {
+                let (rounded, negative) = round_radical(m, sign, d, q, digits);
+                (rounded, negative, false)
+            }
+        };
+        let mut text = rounded.to_decimal();
+        if text.len() <= digits {
+            text = "0".repeat(digits + 1 - text.len()) + &text;
+        }
+        if digits > 0 {
+            text.insert(text.len() - digits, '.');
+        }
+        if negative && !rounded.is_zero() {
+            text.insert(0, '-');
+        }
+        if exact {
+            text
+        } else {
+            format!("≈ {}", text)
+        }
+    }
+}
+
+/// Multiplies by `10^digits`.
+fn scale(n: &mut Big, digits: usize) {
+    for _ in 0..digits {
+        n.mul_small(10);
+    }
+}
+
+/// The magnitude of `(m + sign * √d) / q` times `10^digits`, rounded to the
+/// nearest integer, and whether the value is negative. Being irrational the
+/// value is never halfway: with `D = 4 * d * 10^(2 * digits)` it rounds to
+/// the floor of `(2 * m * 10^digits + q + sign * √D) / (2 * q)`, where only
+/// the integer square root of `D` is needed.
+fn round_radical(m: i128, sign: i128, d: i128, q: i128, digits: usize) -> (Big, bool) {
+    // m + sign * √d < 0 when m is negative and beats √d, or when both
+    // terms are negative or √d beats a positive m
+    let above_root = m.checked_mul(m).is_none_or(|square| square > d);
+    let negative = match sign > 0 {
+        true => m < 0 && above_root,
+        false => m < 0 || !above_root,
+    };
+    // the magnitude has the same form with both signs flipped
+    let (m, sign) = if negative { (-m, -sign) } else { (m, sign) };
+    let mut big_d = Big::from_u128(d.unsigned_abs());
+    big_d.mul_small(4);
+    scale(&mut big_d, 2 * digits);
+    let root = big_d.isqrt();
+    let mut twice_m = Big::from_u128(m.unsigned_abs());
+    twice_m.mul_small(2);
+    scale(&mut twice_m, digits);
+    let q = Big::from_u128(q.unsigned_abs());
+    // all the terms of the numerator, which is not negative, by their sign
+    let (mut numerator, below) = match (sign > 0, m < 0) {
+        (true, false) => (root.add(&twice_m).add(&q), Big::new(0)),
+        (true, true) => (root.add(&q), twice_m),
+        // ⌈√D⌉ = ⌊√D⌋ + 1, D not being a perfect square
+        (false, _) => (twice_m.add(&q), root.add(&Big::new(1))),
+    };
+    numerator.sub_assign(&below);
+    let mut twice_q = q;
+    twice_q.mul_small(2);
+    (numerator.div_rem(&twice_q).0, negative)
+}
+
+/// The floor of `(a + sign * √d) / q` for a positive `q`, searched from the
+/// floating point estimate and checked with integers only.
+pub fn floor_radical(a: i128, sign: i128, d: i128, q: i128) -> Option<i128> {
+    // Whether k <= (a + sign * √d) / q, i.e. k * q - a <= sign * √d
+    let at_most = |k: i128| -> Option<bool> {
+        let t = k.checked_mul(q)?.checked_sub(a)?;
+        let above = t.checked_mul(t).is_none_or(|square| square > d);
+        Some(if sign > 0 {
+            t < 0 || !above
+        } else {
+            t <= 0 && above
+        })
+    };
+    let estimate = (a as f64 + sign as f64 * (d as f64).sqrt()) / q as f64;
+    let mut low = estimate.floor() as i128;
+    let mut step = 1i128;
+    while !at_most(low)? {
+        low = low.checked_sub(step)?;
+        step = step.checked_mul(2)?;
+    }
+    let mut high = low.checked_add(1)?;
+    step = 1;
+    while at_most(high)? {
+        low = high;
+        high = high.checked_add(step)?;
+        step = step.checked_mul(2)?;
+    }
+    while high - low > 1 {
+        let middle = low + (high - low) / 2;
+        if at_most(middle)? {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    Some(low)
+}
+
+/// The coefficient as the fraction of its shortest decimal representation,
+/// which is what was written in the equation.
+fn decimal_fraction(c: f32) -> Option<(i128, i128)> {
+    let text = float::shortest(c);
+    let (integer, fraction) = text.split_once('.').unwrap_or((&text, ""));
+    let numerator: i128 = format!("{}{}", integer, fraction).parse().ok()?;
+    let denominator = 10i128.checked_pow(fraction.len().try_into().ok()?)?;
+    Some((numerator, denominator))
+}
+
+/// The coefficients as written, times the smallest power of ten that makes
+/// them all integers, and that power. None on overflow.
+pub fn scaled_integers(coefficients: &[f32]) -> Option<(Vec<i128>, i128)> {
+    let fractions: Vec<(i128, i128)> = coefficients
+        .iter()
+        .map(|&c| decimal_fraction(c))
+        .collect::<Option<_>>()?;
+    let denominator = fractions.iter().map(|&(_, q)| q).max().unwrap_or(1);
+    let integers = fractions
+        .iter()
+        .map(|&(p, q)| p.checked_mul(denominator / q))
+        .collect::<Option<_>>()?;
+    Some((integers, denominator))
+}
+
+/// The significant digits an `f32` approximation is known to, its 24 bits
+/// holding a little over 7 of them.
+const SIGNIFICANT_DIGITS: i64 = 7;
+
+/// An approximate root with `digits` decimal places, rounded from its
+/// shortest decimal representation the way `Exact::round` rounds, so that
+/// the text is the same on every platform. Past its significant digits it
+/// is rounded to those it has, and says so rather than padding with zeros.
+pub fn round_approximate(x: f32, digits: usize, rounding: Rounding) -> String {
+    let Some((p, q)) = decimal_fraction(x) else {
+        // past the range of i128, no decimal place is known
+        return match digits {
+            0 => format!("≈ {}", float::shortest(x)),
+            _ => format!("≈ {} (known to no decimal place)", float::shortest(x)),
+        };
+    };
+    // the position of the first significant digit after the point
+    let places = q.ilog10() as i64;
+    let integer_digits = p
+        .unsigned_abs()
+        .checked_ilog10()
+        .map_or(0, |n| n as i64 + 1)
+        - places;
+    let known = (SIGNIFICANT_DIGITS - integer_digits).max(0) as usize;
+    let rounded = |digits| {
+        let text = Exact::Rational(p, q).round(digits, rounding);
+        format!("≈ {}", text.trim_start_matches("≈ "))
+    };
+    match known {
+        _ if p == 0 || digits <= known => rounded(digits),
+        0 => format!("{} (known to no decimal place)", rounded(0)),
+        1 => format!("{} (known to 1 decimal place)", rounded(1)),
+        known => format!("{} (known to {} decimal places)", rounded(known), known),
+    }
+}
+
+/// The real solutions of a degree 1 or 2 equation, in the order of
+/// `Poly::solve`. None for other degrees or when the integers overflow.
+pub fn exact_roots(coefficients: &[f32]) -> Option<Vec<Exact>> {
+    if !(2..=3).contains(&coefficients.len()) {
+        return None;
+    }
+    let (integers, _) = scaled_integers(coefficients)?;
+    match integers[..] {
+        [c, b] => Some(vec![Exact::rational(c.checked_neg()?, b)]),
+        [c, b, a] => {
+            let discriminant = b
+                .checked_mul(b)?
+                .checked_sub(a.checked_mul(c)?.checked_mul(4)?)?;
+            let (m, q) = (b.checked_neg()?, a.checked_mul(2)?);
+            if discriminant < 0 {
+                return Some(vec![]);
+            }
+            let root = discriminant.isqrt();
+            if root * root == discriminant {
+                let mut roots = vec![Exact::rational(m + root, q)];
+                if root != 0 {
+                    roots.push(Exact::rational(m - root, q));
+                }
+                Some(roots)
+            } else {
+                Some(vec![
+                    Exact::radical(m, 1, discriminant, q),
+                    Exact::radical(m, -1, discriminant, q),
+                ])
+            }
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rounded(coefficients: &[f32], digits: usize, rounding: Rounding) -> Vec<String> {
+        exact_roots(coefficients)
+            .unwrap()
+            .iter()
+            .map(|root| root.round(digits, rounding))
+            .collect()
+    }
+
+    #[test]
+    fn ties_follow_rounding_mode() {
+        // 2 * X = 5 and 2 * X = -5
+        assert_eq!(rounded(&[-5.0, 2.0], 0, Rounding::HalfUp), ["≈ 3"]);
+        assert_eq!(rounded(&[-5.0, 2.0], 0, Rounding::HalfEven), ["≈ 2"]);
+        assert_eq!(rounded(&[5.0, 2.0], 0, Rounding::HalfUp), ["≈ -3"]);
+        assert_eq!(rounded(&[5.0, 2.0], 0, Rounding::HalfEven), ["≈ -2"]);
+        // 8 * X = 1
+        assert_eq!(rounded(&[-1.0, 8.0], 2, Rounding::HalfEven), ["≈ 0.12"]);
+    }
+
+    #[test]
+    fn decimal_ties_are_exact() {
+        // 1.005 is below the tie as an f32 but not as written
+        assert_eq!(rounded(&[-1.005, 1.0], 2, Rounding::HalfUp), ["≈ 1.01"]);
+        assert_eq!(rounded(&[-0.5, 1.0], 3, Rounding::HalfUp), ["0.500"]);
+        // 3 * X = 1
+        assert_eq!(rounded(&[-1.0, 3.0], 4, Rounding::HalfUp), ["≈ 0.3333"]);
+    }
+
+    #[test]
+    fn radicals_round_to_nearest() {
+        // X^2 - 2 = 0
+        assert_eq!(
+            rounded(&[-2.0, 0.0, 1.0], 6, Rounding::HalfUp),
+            ["≈ 1.414214", "≈ -1.414214"]
+        );
+        // -9.3 * X^2 + 4 * X + 4 = 0
+        assert_eq!(
+            rounded(&[4.0, 4.0, -9.3], 4, Rounding::HalfEven),
+            ["≈ -0.4751", "≈ 0.9052"]
+        );
+        assert_eq!(exact_roots(&[1.0, 0.0, 1.0]), Some(vec![]));
+        assert_eq!(rounded(&[1.0, 2.0, 1.0], 1, Rounding::HalfUp), ["-1.0"]);
+    }
+
+    #[test]
+    fn any_precision_is_exact() {
+        let root = Exact::Rational(1, 3);
+        assert_eq!(
+            root.round(40, Rounding::HalfUp),
+            format!("≈ 0.{}", "3".repeat(40))
+        );
+        // X^2 - 2 = 0, past the digits of an f32 and of an i128
+        let sqrt_2 = "1.4142135623730950488016887242096980785696718753769480731766797379907324784621070388503875343276415727";
+        let roots = rounded(&[-2.0, 0.0, 1.0], 100, Rounding::HalfUp);
+        assert_eq!(roots, [format!("≈ {}", sqrt_2), format!("≈ -{}", sqrt_2)]);
+        assert_eq!(
+            rounded(&[-2.0, 0.0, 1.0], 20, Rounding::HalfEven)[0],
+            "≈ 1.41421356237309504880"
+        );
+        assert_eq!(
+            rounded(&[4.0, 4.0, -9.3], 30, Rounding::HalfUp),
+            [
+                "≈ -0.475131463908869355866316531164",
+                "≈ 0.905238990790589785973843412884"
+            ]
+        );
+        assert!(exact_roots(&[1.0, 0.0, 0.0, 1.0]).is_none());
+    }
+
+    #[test]
+    fn approximations_round_from_their_shortest_decimal() {
+        assert_eq!(round_approximate(0.6823278, 3, Rounding::HalfUp), "≈ 0.682");
+        assert_eq!(round_approximate(2.5, 0, Rounding::HalfUp), "≈ 3");
+        assert_eq!(round_approximate(2.5, 0, Rounding::HalfEven), "≈ 2");
+        assert_eq!(round_approximate(-0.5, 2, Rounding::HalfUp), "≈ -0.50");
+        assert_eq!(
+            round_approximate(0.0, 20, Rounding::HalfUp),
+            "≈ 0.00000000000000000000"
+        );
+        // past the 7 significant digits of an f32, no padding with zeros
+        assert_eq!(
+            round_approximate(2.0945516, 20, Rounding::HalfUp),
+            "≈ 2.094552 (known to 6 decimal places)"
+        );
+        assert_eq!(
+            round_approximate(0.0001234, 12, Rounding::HalfUp),
+            "≈ 0.0001234000 (known to 10 decimal places)"
+        );
+        assert_eq!(
+            round_approximate(123456.7, 3, Rounding::HalfUp),
+            "≈ 123456.7 (known to 1 decimal place)"
+        );
+        assert_eq!(
+            round_approximate(1e30, 2, Rounding::HalfUp),
+            "≈ 1000000000000000000000000000000 (known to no decimal place)"
+        );
+        assert_eq!(
+            round_approximate(f32::MAX, 30, Rounding::HalfUp),
+            format!(
+                "≈ {} (known to no decimal place)",
+                float::shortest(f32::MAX)
+            )
+        );
+    }
+}
@@ -0,0 +1,41 @@
+use crate::float;
+use std::fmt;
+
+/// Every this many iterations the solvers report their progress.
+pub const PROGRESS_EVERY: usize = 100;
+
+/// What happens while an equation is solved, in order, for a front end to
+/// show progress live.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Event {
+    /// Both sides parsed, as they will be expanded.
+    Parsed { equation: String },
+    /// The coefficients after a reduction step, by ascending degree: the
+    /// expansion, then the edits of the options if they change anything.
+    Reduced { coefficients: Vec<f32> },
+    /// The iterations taken so far by the numeric solvers.
+    Iteration { iterations: usize },
+    /// A real root of the final solution.
+    Root { value: f64 },
+}
+
+/// Called with each event as it happens, possibly from another thread than
+/// the one that started solving.
+pub type Observer<'a> = &'a (dyn Fn(&Event) + Sync);
+
+/// `parsed: 5 + X = 0`, one line per event.
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Event::Parsed { equation } => write!(f, "parsed: {}", equation),
+            Event::Reduced { coefficients } => {
+                let coefficients: Vec<String> =
+                    coefficients.iter().map(|&c| float::shortest(c)).collect();
+                write!(f, "reduced: [{}]", coefficients.join(", "))
+            }
+            Event::Iteration { iterations } => write!(f, "iteration: {}", iterations),
+            Event::Root { value } => write!(f, "root: {}", float::shortest(*value as f32)),
+        }
+    }
+}
@@ -0,0 +1,522 @@
+use crate::cyclotomic;
+use crate::decimal;
+use crate::format::{format_polynomial, Style};
+use crate::numeric;
+use crate::Poly;
+
+/// Past this many candidate factors of one degree, Kronecker's method
+/// gives up.
+const CANDIDATE_LIMIT: usize = 1_000_000;
+
+/// Past this a value of the polynomial has too many divisors to try.
+const VALUE_LIMIT: i128 = 10_000_000_000;
+
+/// The integer points Kronecker's method picks its samples from.
+const SAMPLES: std::ops::RangeInclusive<i128> = -10..=10;
+
+/// Why there is no factorization over the integers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FactorError {
+    /// The zero polynomial of `0 = 0`.
+    Zero,
+    /// The integers of the coefficients or of a quotient do not fit.
+    Overflow,
+    /// Kronecker's method would try more than `CANDIDATE_LIMIT` candidates,
+    /// or the values of the polynomial have too many divisors.
+    SearchTooLarge,
+}
+
+/// A polynomial equal to `base^exponent` up to a constant factor, so that
+/// they have the same roots.
+pub struct Power {
+    pub base: Poly,
+    pub exponent: usize,
+}
+
+/// The real roots of `a * X^m + b`, None for a polynomial with other
+/// terms.
+pub fn binomial_roots(coefficients: &[f32]) -> Option<Vec<f32>> {
+    let m = coefficients.len().checked_sub(1).filter(|&m| m > 0)?;
+    if coefficients[1..m].iter().any(|&c| c != 0.0) {
+        return None;
+    }
+    let value = -coefficients[0] as f64 / coefficients[m] as f64;
+    let root = value.abs().powf(1.0 / m as f64);
+    Some(match (m % 2, value) {
+        (_, 0.0) => vec![0.0],
+        (1, v) => vec![(root * v.signum()) as f32],
+        (_, v) if v > 0.0 => vec![-root as f32, root as f32],
+        _ => vec![],
+    })
+}
+
+/// Whether `low = t * high` for some `t`, on integers.
+fn proportional(low: &[i128], high: &[i128]) -> bool {
+    let (Some(&a), Some(&b)) = (low.last(), high.last()) else {
+        return false;
+    };
+    low.iter().zip(high).all(|(&c, &d)| {
+        c.checked_mul(b)
+            .zip(d.checked_mul(a))
+            .is_some_and(|(x, y)| x == y)
+    })
+}
+
+/// Factoring by grouping a polynomial of degree 3 or 4 whose coefficients,
+/// as written, split into a high group `X^m * Q` and a low one `t * Q`,
+/// terms between them being zero, like `X^3 + 3 * X^2 + 2 * X + 6` which is
+/// `X^2 * (X + 3) + 2 * (X + 3)`. Returns `X^m + t` and `Q`.
+pub fn by_grouping(coefficients: &[f32]) -> Option<Vec<Poly>> {
+    let n = coefficients.len().checked_sub(1)?;
+    if !(3..=4).contains(&n) || coefficients[0] == 0.0 {
+        return None;
+    }
+    let (integers, _) = decimal::scaled_integers(coefficients)?;
+    // 2m - 1 >= n so that the groups do not overlap
+    for m in n / 2 + 1..n {
+        let (low, high) = (&integers[..=n - m], &integers[m..]);
+        if integers[n - m + 1..m].iter().any(|&c| c != 0) || !proportional(low, high) {
+            continue;
+        }
+        let mut binomial = vec![0.0; m + 1];
+        binomial[0] = coefficients[n - m] / coefficients[n];
+        binomial[m] = 1.0;
+        return Some(vec![
+            Poly::from_coefficients(binomial),
+            Poly::from_coefficients(coefficients[m..].to_vec()),
+        ]);
+    }
+    None
+}
+
+/// Drops the leading coefficients below `1e-9 * scale`, left by rounding.
+fn trim(mut p: Vec<f64>, scale: f64) -> Vec<f64> {
+    while p.last().is_some_and(|c| c.abs() <= 1e-9 * scale) {
+        p.pop();
+    }
+    p
+}
+
+/// `c - b'`, a step of Yun's algorithm.
+fn minus_derivative(c: &[f64], b: &[f64], scale: f64) -> Vec<f64> {
+    let b = numeric::derivative(b);
+    let d = (0..c.len().max(b.len()))
+        .map(|i| c.get(i).unwrap_or(&0.0) - b.get(i).unwrap_or(&0.0))
+        .collect();
+    trim(d, scale)
+}
+
+/// Yun's square-free decomposition: the monic factors `a_i`, none of them
+/// with a repeated root and no two sharing a root, with `P = c * Π a_i^i`.
+/// Only the factors of degree 1 or more are returned, with their `i`.
+pub fn square_free(coefficients: &[f64]) -> Vec<(Vec<f64>, usize)> {
+    let scale = coefficients
+        .iter()
+        .fold(0.0, |max: f64, c| max.max(c.abs()));
+    let derivative = numeric::derivative(coefficients);
+    let a = numeric::gcd(coefficients, &derivative);
+    if a.is_empty() {
+        return vec![];
+    }
+    let mut b = numeric::quotient(coefficients, &a);
+    let c = numeric::quotient(&derivative, &a);
+    let mut d = minus_derivative(&c, &b, scale);
+    let mut factors = vec![];
+    let mut i = 1;
+    while b.len() > 1 {
+        let a = numeric::gcd(&b, &d);
+        if a.len() > 1 {
+            factors.push((a.clone(), i));
+        }
+        let c = numeric::quotient(&d, &a);
+        b = numeric::quotient(&b, &a);
+        d = minus_derivative(&c, &b, scale);
+        i += 1;
+    }
+    factors
+}
+
+/// The base and exponent when the polynomial, of degree 3 or more, is a
+/// perfect power: when the multiplicities of its square-free factors have
+/// a common divisor `k > 1`, the base being the product of each factor to
+/// its multiplicity divided by `k`. The base is scaled so that its power
+/// has the same leading coefficient, up to sign.
+pub fn perfect_power(coefficients: &[f32]) -> Option<Power> {
+    if coefficients.len() < 4 {
+        return None;
+    }
+    let wide: Vec<f64> = coefficients.iter().map(|&c| c as f64).collect();
+    let factors = square_free(&wide);
+    let exponent = factors
+        .iter()
+        .fold(0, |k, &(_, i)| crate::gcd(k as i128, i as i128) as usize);
+    if exponent < 2 {
+        return None;
+    }
+    let base = factors.iter().fold(vec![1.0], |base, (factor, i)| {
+        (0..i / exponent).fold(base, |base, _| numeric::product(&base, factor))
+    });
+    let power = (0..exponent).fold(vec![1.0], |power, _| numeric::product(&power, &base));
+    let lead = wide[wide.len() - 1];
+    let scale = wide.iter().fold(0.0, |max: f64, c| max.max(c.abs()));
+    let close = power.len() == wide.len()
+        && power
+            .iter()
+            .zip(&wide)
+            .all(|(p, c)| (p * lead - c).abs() <= 1e-6 * scale);
+    if !close {
+        return None;
+    }
+    let root = match exponent % 2 {
+        1 => lead.abs().powf(1.0 / exponent as f64) * lead.signum(),
+        _ => lead.abs().powf(1.0 / exponent as f64),
+    };
+    let base = base.iter().map(|c| (c * root) as f32).collect();
+    Some(Power {
+        base: Poly::from_coefficients(base),
+        exponent,
+    })
+}
+
+/// A polynomial as `content * Π factor^multiplicity`, each factor being
+/// irreducible over the integers, with coprime coefficients and a positive
+/// lead, by increasing degree.
+#[derive(Debug, PartialEq)]
+pub struct Factorization {
+    /// A reduced fraction, the coefficients as written being decimals.
+    pub content: (i128, i128),
+    pub factors: Vec<(Vec<i128>, usize)>,
+}
+
+impl Factorization {
+    pub fn render(&self, style: Style) -> String {
+        let (p, q) = self.content;
+        let mut parts = vec![];
+        if (p, q) != (1, 1) || self.factors.is_empty() {
+            parts.push(match q {
+                1 => p.to_string(),
+                q => format!("{}/{}", p, q),
+            });
+        }
+        for (factor, multiplicity) in &self.factors {
+            let factor: Vec<f32> = factor.iter().map(|&c| c as f32).collect();
+            let factor = format_polynomial(&factor, style);
+            parts.push(match multiplicity {
+                1 => format!("({})", factor),
+                m => format!("({})^{}", factor, m),
+            });
+        }
+        parts.join(" * ")
+    }
+}
+
+fn eval_integers(p: &[i128], x: i128) -> Option<i128> {
+    p.iter()
+        .rev()
+        .try_fold(0i128, |value, &c| value.checked_mul(x)?.checked_add(c))
+}
+
+/// The quotient when `q` divides `p` over the integers.
+fn divide_integers(p: &[i128], q: &[i128]) -> Option<Vec<i128>> {
+    let lead = *q.last()?;
+    let mut remainder = p.to_vec();
+    let mut quotient = vec![0; (p.len() + 1).checked_sub(q.len())?];
+    while remainder.len() >= q.len() {
+        let top = remainder[remainder.len() - 1];
+        if top % lead != 0 {
+            return None;
+        }
+        let factor = top / lead;
+        let shift = remainder.len() - q.len();
+        for (i, &c) in q.iter().enumerate() {
+            remainder[shift + i] = remainder[shift + i].checked_sub(factor.checked_mul(c)?)?;
+        }
+        quotient[shift] = factor;
+        remainder.pop();
+    }
+    remainder.iter().all(|&c| c == 0).then_some(quotient)
+}
+
+/// The polynomial through the points when its coefficients are integers,
+/// from Newton's divided differences, which are integers then.
+fn interpolate(points: &[(i128, i128)]) -> Option<Vec<i128>> {
+    let mut differences: Vec<i128> = points.iter().map(|&(_, y)| y).collect();
+    for j in 1..points.len() {
+        for i in (j..points.len()).rev() {
+            let numerator = differences[i].checked_sub(differences[i - 1])?;
+            let denominator = points[i].0 - points[i - j].0;
+            if numerator % denominator != 0 {
+                return None;
+            }
+            differences[i] = numerator / denominator;
+        }
+    }
+    let mut p = vec![*differences.last()?];
+    for i in (0..points.len() - 1).rev() {
+        // p * (X - x_i) + d_i
+        let mut next = vec![0i128; p.len() + 1];
+        for (k, &c) in p.iter().enumerate() {
+            next[k + 1] = next[k + 1].checked_add(c)?;
+            next[k] = next[k].checked_sub(c.checked_mul(points[i].0)?)?;
+        }
+        next[0] = next[0].checked_add(differences[i])?;
+        p = next;
+    }
+    while p.last() == Some(&0) {
+        p.pop();
+    }
+    Some(p)
+}
+
+/// A factor of degree `d` of `p` by Kronecker's method: it takes at `d + 1`
+/// integer points values dividing those of `p`, so each choice of divisors
+/// gives one candidate to interpolate. The points with the fewest divisors
+/// are used. None when there is no such factor.
+fn kronecker(p: &[i128], d: usize) -> Result<Option<Vec<i128>>, FactorError> {
+    let mut samples = vec![];
+    for x in SAMPLES {
+        let value = eval_integers(p, x).ok_or(FactorError::Overflow)?;
+        if value == 0 {
+            return Ok(Some(vec![-x, 1]));
+        }
+        if let Some(divisors) = crate::hints::divisors_up_to(value, VALUE_LIMIT) {
+            samples.push((x, divisors));
+        }
+    }
+    if samples.len() < d + 1 {
+        return Err(FactorError::SearchTooLarge);
+    }
+    samples.sort_by_key(|(_, divisors)| divisors.len());
+    samples.truncate(d + 1);
+    // the sign of a factor is free, the first value is taken positive
+    let choices: Vec<Vec<i128>> = samples
+        .iter()
+        .enumerate()
+        .map(|(i, (_, divisors))| match i {
+            0 => divisors.clone(),
+            _ => divisors.iter().flat_map(|&v| [v, -v]).collect(),
+        })
+        .collect();
+    let count = choices
+        .iter()
+        .try_fold(1usize, |count, values| count.checked_mul(values.len()))
+        .filter(|&count| count <= CANDIDATE_LIMIT)
+        .ok_or(FactorError::SearchTooLarge)?;
+    for mut n in 0..count {
+        let points: Vec<(i128, i128)> = samples
+            .iter()
+            .zip(&choices)
+            .map(|((x, _), values)| {
+                let value = values[n % values.len()];
+                n /= values.len();
+                (*x, value)
+            })
+            .collect();
+        let Some(candidate) = interpolate(&points) else {
+            continue;
+        };
+        if candidate.len() == d + 1 && divide_integers(p, &candidate).is_some() {
+            return Ok(Some(candidate));
+        }
+    }
+    Ok(None)
+}
+
+/// The factorization over the integers of the coefficients as written,
+/// exact unlike the numeric roots. The cyclotomic families are factored
+/// from their identities, without search.
+pub fn over_integers(coefficients: &[f32]) -> Result<Factorization, FactorError> {
+    let (integers, scale) = decimal::scaled_integers(coefficients).ok_or(FactorError::Overflow)?;
+    let lead = *integers.last().ok_or(FactorError::Zero)?;
+    let divisor = integers.iter().fold(0, |a, &b| crate::gcd(a, b)) * lead.signum();
+    let reduced = crate::gcd(divisor, scale);
+    let content = (divisor / reduced, scale / reduced);
+    if let Some(family) = cyclotomic::recognize(coefficients) {
+        let mut factors: Vec<(Vec<i128>, usize)> = family
+            .indices()
+            .into_iter()
+            .map(|d| (cyclotomic::polynomial(d), 1))
+            .collect();
+        factors.sort_by(|(a, _), (b, _)| a.len().cmp(&b.len()).then(a.cmp(b)));
+        return Ok(Factorization { content, factors });
+    }
+    let primitive: Vec<i128> = integers.iter().map(|c| c / divisor).collect();
+    let k = primitive.iter().take_while(|&&c| c == 0).count();
+    let mut factors = vec![vec![0, 1]; k];
+    let mut rest = primitive[k..].to_vec();
+    let mut d = 1;
+    while rest.len() > 2 * d {
+        match kronecker(&rest, d)? {
+            Some(mut factor) => {
+                if factor[factor.len() - 1] < 0 {
+                    factor.iter_mut().for_each(|c| *c = -*c);
+                }
+                rest = divide_integers(&rest, &factor).ok_or(FactorError::Overflow)?;
+                factors.push(factor);
+            }
+            None => d += 1,
+        }
+    }
+    if rest.len() > 1 {
+        factors.push(rest);
+    }
+    factors.sort_by(|a, b| a.len().cmp(&b.len()).then(a.cmp(b)));
+    let mut grouped: Vec<(Vec<i128>, usize)> = vec![];
+    for factor in factors {
+        match grouped.last_mut() {
+            Some((last, multiplicity)) if *last == factor => *multiplicity += 1,
+            _ => grouped.push((factor, 1)),
+        }
+    }
+    Ok(Factorization {
+        content,
+        factors: grouped,
+    })
+}
+
+/// The real roots of a product, in increasing order without repetition,
+/// exact for factors of degree 2 at most and for binomials.
+pub fn real_roots(factors: &[Poly]) -> Vec<f32> {
+    let mut roots: Vec<f32> = factors
+        .iter()
+        .flat_map(|factor| match factor.get_degree() {
+            d if d > 2 => {
+                binomial_roots(factor.coefficients()).unwrap_or_else(|| factor.real_roots())
+            }
+            _ => factor.real_roots(),
+        })
+        .collect();
+    roots.sort_by(f32::total_cmp);
+    roots.dedup_by(|a, b| (*a - *b).abs() <= 1e-6 * a.abs().max(1.0));
+    roots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::{Format, Order};
+
+    fn factors_of(equation: &str) -> Option<Vec<Vec<f32>>> {
+        let poly = Poly::new(equation).unwrap();
+        let factors = by_grouping(poly.coefficients())?;
+        Some(factors.iter().map(|f| f.coefficients().to_vec()).collect())
+    }
+
+    #[test]
+    fn groups_split_into_factors() {
+        assert_eq!(
+            factors_of("X^3 + 3 * X^2 + 2 * X + 6 = 0"),
+            Some(vec![vec![2.0, 0.0, 1.0], vec![3.0, 1.0]])
+        );
+        // X^3 * (2 * X - 1) - 8 * (2 * X - 1)
+        assert_eq!(
+            factors_of("2 * X^4 - X^3 - 16 * X + 8 = 0"),
+            Some(vec![vec![-8.0, 0.0, 0.0, 1.0], vec![-1.0, 2.0]])
+        );
+        assert_eq!(
+            factors_of("0.5 * X^3 - X^2 - 2 * X + 4 = 0"),
+            Some(vec![vec![-4.0, 0.0, 1.0], vec![-1.0, 0.5]])
+        );
+        assert_eq!(factors_of("X^3 + 3 * X^2 + 2 * X + 7 = 0"), None);
+        assert_eq!(factors_of("X^4 + X^3 + X^2 + X + 1 = 0"), None);
+        assert_eq!(factors_of("X^2 + 3 * X + 2 = 0"), None);
+    }
+
+    #[test]
+    fn roots_of_the_factors() {
+        let poly = Poly::new("2 * X^4 - X^3 - 16 * X + 8 = 0").unwrap();
+        let factors = by_grouping(poly.coefficients()).unwrap();
+        assert_eq!(real_roots(&factors), [0.5, 2.0]);
+        let poly = Poly::new("X^3 - X^2 - X + 1 = 0").unwrap();
+        let factors = by_grouping(poly.coefficients()).unwrap();
+        assert_eq!(real_roots(&factors), [-1.0, 1.0]);
+        assert_eq!(
+            binomial_roots(&[-16.0, 0.0, 0.0, 0.0, 1.0]),
+            Some(vec![-2.0, 2.0])
+        );
+        assert_eq!(binomial_roots(&[27.0, 0.0, 0.0, 1.0]), Some(vec![-3.0]));
+        assert_eq!(binomial_roots(&[1.0, 0.0, 1.0]), Some(vec![]));
+        assert_eq!(binomial_roots(&[1.0, 1.0, 1.0]), None);
+    }
+
+    fn power_of(equation: &str) -> Option<(Vec<f32>, usize)> {
+        let poly = Poly::new(equation).unwrap();
+        let power = perfect_power(poly.coefficients())?;
+        Some((power.base.coefficients().to_vec(), power.exponent))
+    }
+
+    #[test]
+    fn square_free_factors_with_multiplicities() {
+        // (X - 1)^3 (X + 2)
+        let factors = square_free(&[-2.0, 5.0, -3.0, -1.0, 1.0]);
+        assert_eq!(factors.len(), 2);
+        assert_eq!(factors[0].1, 1);
+        assert!((factors[0].0[0] - 2.0).abs() < 1e-9);
+        assert_eq!(factors[1].1, 3);
+        assert!((factors[1].0[0] + 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn perfect_powers_are_found() {
+        assert_eq!(
+            power_of("X^4 + 2 * X^2 + 1 = 0"),
+            Some((vec![1.0, 0.0, 1.0], 2))
+        );
+        assert_eq!(
+            power_of("X^3 + 3 * X^2 + 3 * X + 1 = 0"),
+            Some((vec![1.0, 1.0], 3))
+        );
+        assert_eq!(
+            power_of("9 * X^4 - 12 * X^2 + 4 = 0"),
+            Some((vec![-2.0, 0.0, 3.0], 2))
+        );
+        assert_eq!(power_of("-X^4 + 2 * X^2 - 1 = 0").unwrap().1, 2);
+        // (X - 1)^4 (X + 1)^2 is ((X - 1)^2 (X + 1))^2
+        let (base, exponent) =
+            power_of("X^6 - 2 * X^5 - X^4 + 4 * X^3 - X^2 - 2 * X + 1 = 0").unwrap();
+        assert_eq!((base.len(), exponent), (4, 2));
+        assert_eq!(power_of("X^4 - 2 * X^3 + X^2 + 1 = 0"), None);
+        assert_eq!(power_of("(X - 1)^3 * (X + 2) = 0"), None);
+        assert_eq!(power_of("X^2 + 2 * X + 1 = 0"), None);
+    }
+
+    fn factored(equation: &str) -> String {
+        let poly = Poly::new(equation).unwrap();
+        match over_integers(poly.coefficients()) {
+            Ok(factorization) => factorization.render(Style {
+                format: Format::Pretty,
+                order: Order::Descending,
+            }),
+            Err(error) => format!("{:?}", error),
+        }
+    }
+
+    #[test]
+    fn factors_over_the_integers() {
+        assert_eq!(factored("2 * X^3 - 2 = 0"), "2 * (X - 1) * (X^2 + X + 1)");
+        // (X^2 + X + 1)(X^2 - X + 1), no rational root to start from
+        assert_eq!(
+            factored("X^4 + X^2 + 1 = 0"),
+            "(X^2 - X + 1) * (X^2 + X + 1)"
+        );
+        assert_eq!(factored("X^4 + 1 = 0"), "(X^4 + 1)");
+        assert_eq!(
+            factored("-X^5 + 2 * X^4 - X^3 = 0"),
+            "-1 * (X - 1)^2 * (X)^3"
+        );
+        assert_eq!(factored("0.5 * X^2 - 0.5 = 0"), "1/2 * (X - 1) * (X + 1)");
+        assert_eq!(
+            factored("(X^3 + 2 * X + 7) * (X^3 - 3) * (X + 5) = 0"),
+            "(X + 5) * (X^3 - 3) * (X^3 + 2X + 7)"
+        );
+        assert_eq!(factored("6 = 0"), "6");
+        assert_eq!(
+            factored("3 * X^6 - 3 = 0"),
+            "3 * (X - 1) * (X + 1) * (X^2 - X + 1) * (X^2 + X + 1)"
+        );
+        assert_eq!(factored("X^40 - 1 = 0").matches(" * ").count(), 7);
+        assert_eq!(factored("X = X"), "Zero");
+        // the values have too many divisors to try every candidate
+        assert_eq!(factored("X^8 + X + 720720 = 0"), "SearchTooLarge");
+    }
+}
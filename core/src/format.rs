@@ -0,0 +1,202 @@
+use crate::float;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Order {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Format {
+    /// `a * X^p` terms exactly as written in the subject
+    #[default]
+    Subject,
+    /// `4X` instead of `4 * X^1`, `5` instead of `5 * X^0`, no `1 *`
+    Pretty,
+    /// Like `Pretty`, with a lowercase `x`, superscript exponents and the
+    /// minus sign: `−9.3x² + 4x + 4`
+    Unicode,
+}
+
+const SUPERSCRIPTS: [char; 10] = ['⁰', '¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹'];
+const MINUS: char = '−';
+
+fn superscript(n: usize) -> String {
+    n.to_string()
+        .chars()
+        .map(|digit| SUPERSCRIPTS[digit.to_digit(10).unwrap_or(0) as usize])
+        .collect()
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Style {
+    pub format: Format,
+    pub order: Order,
+}
+
+fn format_term(c: f32, degree: usize, format: Format) -> String {
+    match (format, degree) {
+        (Format::Subject, _) => format!("{} * X^{}", float::shortest(c), degree),
+        (Format::Unicode, _) => {
+            let ascii = format_term(c, degree, Format::Pretty).replace('-', &MINUS.to_string());
+            match ascii.split_once("X^") {
+                Some((coefficient, _)) => format!("{}x{}", coefficient, superscript(degree)),
+                None => ascii.replace('X', "x"),
+            }
+        }
+        (Format::Pretty, 0) => float::shortest(c),
+        (Format::Pretty, _) => {
+            let coefficient = match c {
+                1.0 => String::new(),
+                -1.0 => String::from("-"),
+                _ => float::shortest(c),
+            };
+            if degree == 1 {
+                format!("{}X", coefficient)
+            } else {
+                format!("{}X^{}", coefficient, degree)
+            }
+        }
+    }
+}
+
+pub fn format_polynomial(coefficients: &[f32], style: Style) -> String {
+    let mut terms: Vec<(usize, f32)> = coefficients
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| **c != 0.0)
+        .map(|(degree, c)| (degree, *c))
+        .collect();
+    if style.order == Order::Descending {
+        terms.reverse();
+    }
+    let mut output = String::new();
+    for (i, (degree, c)) in terms.into_iter().enumerate() {
+        if i == 0 {
+            output += &format_term(c, degree, style.format);
+            continue;
+        }
+        if c < 0.0 && style.format == Format::Unicode {
+            output += &format!(" {} ", MINUS);
+        } else if c < 0.0 {
+            output += " - ";
+        } else {
+            output += " + ";
+        }
+        output += &format_term(c.abs(), degree, style.format);
+    }
+    if output.is_empty() {
+        output += "0";
+    }
+    output
+}
+
+/// Rewrites the pretty form with a lowercase `x`, which is what graphing
+/// calculators expect as the variable.
+fn calculator_syntax(coefficients: &[f32], braced_exponents: bool) -> String {
+    let pretty = Style {
+        format: Format::Pretty,
+        order: Order::Descending,
+    };
+    let polynomial = format_polynomial(coefficients, pretty);
+    let mut chars = polynomial.chars().peekable();
+    let mut output = String::new();
+    while let Some(c) = chars.next() {
+        if c != 'X' {
+            output.push(c);
+            continue;
+        }
+        output.push('x');
+        if chars.next_if_eq(&'^').is_some() {
+            let mut exponent = String::new();
+            while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+                exponent.push(digit);
+            }
+            if braced_exponents {
+                output += &format!("^{{{}}}", exponent);
+            } else {
+                output += &format!("^{}", exponent);
+            }
+        }
+    }
+    output
+}
+
+/// The reduced polynomial as typed into Desmos, which only reads the first
+/// digit of an exponent unless it is braced.
+pub fn desmos_expression(coefficients: &[f32]) -> String {
+    format!("y={}", calculator_syntax(coefficients, true))
+}
+
+/// The reduced polynomial as a GeoGebra input bar command.
+pub fn geogebra_expression(coefficients: &[f32]) -> String {
+    format!("f(x) = {}", calculator_syntax(coefficients, false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn style(format: Format, order: Order) -> Style {
+        Style { format, order }
+    }
+
+    #[test]
+    fn format_in_both_orders() {
+        let coefficients = [4.0, 0.0, -9.3];
+        assert_eq!(
+            format_polynomial(&coefficients, style(Format::Subject, Order::Ascending)),
+            "4 * X^0 - 9.3 * X^2"
+        );
+        assert_eq!(
+            format_polynomial(&coefficients, style(Format::Subject, Order::Descending)),
+            "-9.3 * X^2 + 4 * X^0"
+        );
+        assert_eq!(format_polynomial(&[], Style::default()), "0");
+    }
+
+    #[test]
+    fn pretty_format_drops_redundant_notation() {
+        let pretty = style(Format::Pretty, Order::Descending);
+        assert_eq!(
+            format_polynomial(&[4.0, 4.0, -9.3], pretty),
+            "-9.3X^2 + 4X + 4"
+        );
+        assert_eq!(
+            format_polynomial(&[-1.0, 0.0, 0.0, -1.0], pretty),
+            "-X^3 - 1"
+        );
+        assert_eq!(format_polynomial(&[0.0, 1.0, 1.0], pretty), "X^2 + X");
+    }
+
+    #[test]
+    fn unicode_format() {
+        let unicode = style(Format::Unicode, Order::Descending);
+        assert_eq!(
+            format_polynomial(&[4.0, 4.0, -9.3], unicode),
+            "−9.3x² + 4x + 4"
+        );
+        let mut coefficients = vec![-1.0, -1.0];
+        coefficients.resize(12, 0.0);
+        coefficients.push(1.0);
+        assert_eq!(format_polynomial(&coefficients, unicode), "x¹² − x − 1");
+    }
+
+    #[test]
+    fn calculator_expressions() {
+        let mut coefficients = vec![-4.0, 0.5];
+        coefficients.resize(10, 0.0);
+        coefficients.push(2.0);
+        assert_eq!(desmos_expression(&coefficients), "y=2x^{10} + 0.5x - 4");
+        assert_eq!(
+            geogebra_expression(&coefficients),
+            "f(x) = 2x^10 + 0.5x - 4"
+        );
+        assert_eq!(desmos_expression(&[]), "y=0");
+    }
+}
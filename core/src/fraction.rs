@@ -0,0 +1,133 @@
+use crate::decimal::{self, Exact};
+
+/// Below this the fractional part of a floating point value is taken as
+/// rounding error rather than the start of a new term.
+const EPSILON: f64 = 1e-9;
+
+/// The first `count` terms `[a0; a1, a2, ...]` of the expansion, computed
+/// with integers only so that the periodic expansion of a quadratic
+/// irrational is exact. It ends early for rationals. None on overflow.
+pub fn exact_terms(value: Exact, count: usize) -> Option<Vec<i128>> {
+    let mut terms = vec![];
+    match value {
+        Exact::Rational(mut p, mut q) => {
+            while terms.len() < count && q != 0 {
+                let a = p.div_euclid(q);
+                terms.push(a);
+                (p, q) = (q, p - a * q);
+            }
+        }
+        Exact::Radical { m, sign, d, q } => {
+            // As (p + √d) / q with q dividing d - p², so that each complete
+            // quotient keeps that form.
+            let (mut p, mut q, mut d) = (sign * m, sign * q, d);
+            if (d - p.checked_mul(p)?) % q != 0 {
+                let scale = q.abs();
+                (p, q, d) = (
+                    p.checked_mul(scale)?,
+                    q.checked_mul(scale)?,
+                    d.checked_mul(scale)?.checked_mul(scale)?,
+                );
+            }
+            while terms.len() < count {
+                let a = if q > 0 {
+                    decimal::floor_radical(p, 1, d, q)?
+                } else {
+                    decimal::floor_radical(-p, -1, d, -q)?
+                };
+                terms.push(a);
+                p = a.checked_mul(q)?.checked_sub(p)?;
+                q = d.checked_sub(p.checked_mul(p)?)? / q;
+            }
+        }
+    }
+    Some(terms)
+}
+
+/// The expansion of a numeric approximation, which stops when the
+/// remainder is lost in rounding error.
+pub fn approximate_terms(mut x: f64, count: usize) -> Vec<i128> {
+    let mut terms = vec![];
+    while terms.len() < count && x.is_finite() && x.abs() < i64::MAX as f64 {
+        let a = x.floor();
+        terms.push(a as i128);
+        if x - a < EPSILON {
+            break;
+        }
+        x = 1.0 / (x - a);
+    }
+    terms
+}
+
+/// The best rational approximations `p / q` given by the terms, stopping
+/// before the integers overflow.
+pub fn convergents(terms: &[i128]) -> Vec<(i128, i128)> {
+    let mut convergents = vec![];
+    let (mut p, mut previous_p) = (1i128, 0i128);
+    let (mut q, mut previous_q) = (0i128, 1i128);
+    for &a in terms {
+        let next = a
+            .checked_mul(p)
+            .and_then(|ap| ap.checked_add(previous_p))
+            .zip(a.checked_mul(q).and_then(|aq| aq.checked_add(previous_q)));
+        let Some((next_p, next_q)) = next else {
+            break;
+        };
+        (previous_p, p) = (p, next_p);
+        (previous_q, q) = (q, next_q);
+        convergents.push((p, q));
+    }
+    convergents
+}
+
+/// `[a0; a1, a2]`
+pub fn format_terms(terms: &[i128]) -> String {
+    match terms.split_first() {
+        Some((first, [])) => format!("[{}]", first),
+        Some((first, rest)) => {
+            let rest: Vec<String> = rest.iter().map(|a| a.to_string()).collect();
+            format!("[{}; {}]", first, rest.join(", "))
+        }
+        None => String::from("[]"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quadratic_irrationals_are_periodic() {
+        // X^2 - 2 = 0
+        let roots = decimal::exact_roots(&[-2.0, 0.0, 1.0]).unwrap();
+        assert_eq!(exact_terms(roots[0], 5), Some(vec![1, 2, 2, 2, 2]));
+        assert_eq!(exact_terms(roots[1], 5), Some(vec![-2, 1, 1, 2, 2]));
+        // X^2 - X - 1 = 0, the golden ratio first
+        let roots = decimal::exact_roots(&[-1.0, -1.0, 1.0]).unwrap();
+        assert_eq!(exact_terms(roots[0], 6), Some(vec![1; 6]));
+        // -9.3 * X^2 + 4 * X + 4 = 0
+        let roots = decimal::exact_roots(&[4.0, 4.0, -9.3]).unwrap();
+        let terms = exact_terms(roots[1], 8).unwrap();
+        assert_eq!(terms, approximate_terms(roots[1].to_f64(), 8));
+    }
+
+    #[test]
+    fn rationals_terminate() {
+        assert_eq!(
+            exact_terms(Exact::Rational(-7, 3), 10),
+            Some(vec![-3, 1, 2])
+        );
+        assert_eq!(approximate_terms(0.5, 10), vec![0, 2]);
+        assert_eq!(format_terms(&[-3, 1, 2]), "[-3; 1, 2]");
+        assert_eq!(format_terms(&[4]), "[4]");
+    }
+
+    #[test]
+    fn convergents_approach_value() {
+        assert_eq!(
+            convergents(&[1, 2, 2, 2]),
+            vec![(1, 1), (3, 2), (7, 5), (17, 12)]
+        );
+        assert_eq!(convergents(&[i128::MAX, i128::MAX]).len(), 1);
+    }
+}
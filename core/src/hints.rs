@@ -0,0 +1,246 @@
+use crate::decimal;
+use crate::method;
+use std::fmt;
+
+/// Past this a constant or leading coefficient has too many divisors to
+/// try as rational roots.
+const DIVISOR_LIMIT: i128 = 1_000_000;
+
+/// A technique to solve by hand an equation of a degree without a closed
+/// form here.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Hint {
+    /// X^k divides every term, X = 0 being a root of multiplicity k.
+    CommonFactor(usize),
+    /// The rational roots other than 0, as reduced fractions with a positive
+    /// denominator, each dividing out a factor of degree 1.
+    RationalRoots(Vec<(i128, i128)>),
+    /// Only powers of X^step appear, a polynomial of `degree` in Y = X^step.
+    Substitution { step: usize, degree: usize },
+    /// The coefficients, once X^k is factored out, read the same both ways.
+    Palindromic,
+}
+
+impl fmt::Display for Hint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Hint::CommonFactor(1) => write!(f, "factor out X: X = 0 is a root"),
+            Hint::CommonFactor(k) => write!(
+                f,
+                "factor out X^{}: X = 0 is a root of multiplicity {}",
+                k, k
+            ),
+            Hint::RationalRoots(roots) => {
+                let roots: Vec<String> = roots
+                    .iter()
+                    .map(|&(p, q)| match q {
+                        1 => format!("X = {}", p),
+                        q => format!("X = {}/{}", p, q),
+                    })
+                    .collect();
+                let plural = if roots.len() > 1 { "s" } else { "" };
+                write!(
+                    f,
+                    "rational root{} {}: dividing by X - r for each lowers the degree",
+                    plural,
+                    roots.join(", ")
+                )
+            }
+            Hint::Substitution { step: 2, degree: 2 } => write!(
+                f,
+                "biquadratic: with Y = X^2 it is a quadratic in Y, then X = ±√Y"
+            ),
+            // no Y, which would read as the variable of an equation in X
+            // and Y
+            Hint::Substitution { step, degree: 1 } => write!(
+                f,
+                "a binomial: X^{} = c is solved by the real roots of order {} of c",
+                step, step
+            ),
+            Hint::Substitution { step, degree } => write!(
+                f,
+                "only powers of X^{} appear: with Y = X^{} it has degree {} in Y",
+                step, step, degree
+            ),
+            Hint::Palindromic => write!(
+                f,
+                "palindromic: X = -1 is a root when the degree is odd, otherwise dividing by X^(n/2) and setting Y = X + 1/X halves the degree"
+            ),
+        }
+    }
+}
+
+/// The positive divisors of `n`, none when there are too many to try.
+fn divisors(n: i128) -> Option<Vec<i128>> {
+    divisors_up_to(n, DIVISOR_LIMIT)
+}
+
+/// The positive divisors of `n`, none when it is 0 or past `limit`.
+pub(crate) fn divisors_up_to(n: i128, limit: i128) -> Option<Vec<i128>> {
+    let n = n.abs();
+    if n == 0 || n > limit {
+        return None;
+    }
+    let mut small = vec![];
+    let mut large = vec![];
+    let mut d = 1;
+    while d * d <= n {
+        if n % d == 0 {
+            small.push(d);
+            if d * d != n {
+                large.push(n / d);
+            }
+        }
+        d += 1;
+    }
+    small.extend(large.into_iter().rev());
+    Some(small)
+}
+
+/// Whether p/q is a root, from `q^n P(p/q)` computed with integers only.
+/// False on overflow.
+fn is_root(integers: &[i128], p: i128, q: i128) -> bool {
+    let n = integers.len() as u32 - 1;
+    let mut sum: Option<i128> = Some(0);
+    for (i, &c) in integers.iter().enumerate() {
+        let term = p
+            .checked_pow(i as u32)
+            .zip(q.checked_pow(n - i as u32))
+            .and_then(|(x, y)| x.checked_mul(y))
+            .and_then(|x| x.checked_mul(c));
+        sum = sum.zip(term).and_then(|(sum, term)| sum.checked_add(term));
+    }
+    sum == Some(0)
+}
+
+/// The rational root theorem on the coefficients as written: a root p/q in
+/// lowest terms has p dividing the constant and q the leading coefficient.
+fn rational_roots(coefficients: &[f32]) -> Vec<(i128, i128)> {
+    let Some((integers, _)) = decimal::scaled_integers(coefficients) else {
+        return vec![];
+    };
+    let (Some(&constant), Some(&lead)) = (integers.first(), integers.last()) else {
+        return vec![];
+    };
+    let (Some(numerators), Some(denominators)) = (divisors(constant), divisors(lead)) else {
+        return vec![];
+    };
+    let mut roots = vec![];
+    for &q in &denominators {
+        for &p in &numerators {
+            if crate::gcd(p, q) != 1 {
+                continue;
+            }
+            for p in [-p, p] {
+                if is_root(&integers, p, q) {
+                    roots.push((p, q));
+                }
+            }
+        }
+    }
+    roots.sort_by(|&(a, b), &(c, d)| (a * d).cmp(&(c * b)));
+    roots
+}
+
+/// The techniques that apply to a polynomial of degree above 2, in the
+/// order to try them: factoring out X^k first, the others looking at what
+/// is left. None for a lower degree, the closed forms solving it.
+pub fn hints(coefficients: &[f32]) -> Vec<Hint> {
+    let degree = coefficients.len().saturating_sub(1);
+    if degree <= 2 {
+        return vec![];
+    }
+    let mut hints = vec![];
+    let k = coefficients.iter().take_while(|&&c| c == 0.0).count();
+    if k > 0 {
+        hints.push(Hint::CommonFactor(k));
+    }
+    let rest = &coefficients[k..];
+    let roots = rational_roots(rest);
+    if !roots.is_empty() {
+        hints.push(Hint::RationalRoots(roots));
+    }
+    let wide: Vec<f64> = rest.iter().map(|&c| c as f64).collect();
+    let step = method::exponent_step(&wide);
+    if step > 1 {
+        hints.push(Hint::Substitution {
+            step,
+            degree: (rest.len() - 1) / step,
+        });
+    }
+    let palindromic = rest.iter().eq(rest.iter().rev());
+    if rest.len() > 3 && palindromic {
+        hints.push(Hint::Palindromic);
+    }
+    hints
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Poly;
+
+    fn hints_of(equation: &str) -> Vec<Hint> {
+        hints(Poly::new(equation).unwrap().coefficients())
+    }
+
+    #[test]
+    fn rational_roots_are_found_exactly() {
+        // (2X - 1)(X + 3)(X - 2)
+        let found = hints_of("2 * X^3 + X^2 - 13 * X + 6 = 0");
+        assert_eq!(found, [Hint::RationalRoots(vec![(-3, 1), (1, 2), (2, 1)])]);
+        assert_eq!(
+            found[0].to_string(),
+            "rational roots X = -3, X = 1/2, X = 2: dividing by X - r for each lowers the degree"
+        );
+        let decimals = hints_of("X^3 - 0.5 * X^2 - X + 0.5 = 0");
+        assert_eq!(
+            decimals,
+            [Hint::RationalRoots(vec![(-1, 1), (1, 2), (1, 1)])]
+        );
+        assert_eq!(
+            hints_of("X^3 - 2 = 0"),
+            [Hint::Substitution { step: 3, degree: 1 }]
+        );
+        assert_eq!(
+            hints_of("X^4 - 16 = 0")[1].to_string(),
+            "a binomial: X^4 = c is solved by the real roots of order 4 of c"
+        );
+        assert!(hints_of("X^3 + X - 3 = 0").is_empty());
+    }
+
+    #[test]
+    fn forms_with_a_technique() {
+        assert_eq!(
+            hints_of("X^4 - 5 * X^2 + 7 = 0"),
+            [Hint::Substitution { step: 2, degree: 2 }]
+        );
+        assert!(hints_of("X^4 - 5 * X^2 + 7 = 0")[0]
+            .to_string()
+            .starts_with("biquadratic"));
+        assert_eq!(
+            hints_of("X^5 + 3 * X^4 + 5 * X^3 = 0"),
+            [Hint::CommonFactor(3)]
+        );
+        assert_eq!(
+            hints_of("2 * X^4 + 3 * X^3 + 5 * X^2 + 3 * X + 2 = 0"),
+            [Hint::Palindromic]
+        );
+        assert_eq!(
+            hints_of("X^4 + X^3 + X^2 + X = 0"),
+            [
+                Hint::CommonFactor(1),
+                Hint::RationalRoots(vec![(-1, 1)]),
+                Hint::Palindromic
+            ]
+        );
+    }
+
+    #[test]
+    fn nothing_below_degree_three() {
+        assert!(hints_of("X^2 - 1 = 0").is_empty());
+        assert!(hints_of("0 = 0").is_empty());
+        assert!(hints(&[0.1, 0.7, 0.3, 1e30]).is_empty());
+    }
+}
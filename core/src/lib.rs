@@ -0,0 +1,749 @@
+//! The polynomials and their solvers, without the command line, for other
+//! programs to embed. Builds on `computor-parse` for the grammar, which it
+//! re-exports; `computor-cli` adds the printing, the plots and the terminal
+//! on top of both.
+
+pub mod complex;
+pub mod cyclotomic;
+pub mod decimal;
+pub mod event;
+pub mod factor;
+pub mod format;
+pub mod fraction;
+pub mod hints;
+pub mod method;
+pub mod numeric;
+pub mod random;
+pub mod render;
+pub mod report;
+pub mod resultant;
+pub mod sign;
+pub mod solve;
+pub mod steps;
+pub mod symmetric;
+pub mod transform;
+pub mod warnings;
+
+pub use computor_parse::{expr, float, ParseError};
+use format::{format_polynomial, Style};
+use report::Report;
+use std::ops::{Add, Mul, Neg, Sub};
+
+/// A change to the reduced polynomial before solving it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub enum Edit {
+    /// `set_coefficient`.
+    Set(usize, f32),
+    /// `add_term`.
+    Add(usize, f32),
+    /// `make_lead_positive`.
+    PositiveLead,
+}
+
+/// A polynomial by ascending degree, without trailing zero coefficients.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Poly {
+    coefficients: Vec<f32>,
+}
+
+/// What a reduced equation is, so that the zero polynomial of `0 = 0`,
+/// `X - X = 0` or `0 * X^5 = 0` is matched on rather than told by its empty
+/// list of coefficients.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub enum Reduced<'a> {
+    /// Every real number is a solution. Its degree is undefined, printed as
+    /// 0 like the subject does.
+    ZeroPolynomial,
+    /// A nonzero constant, without solution.
+    Constant(f32),
+    /// Degree 1 and above, by ascending degree.
+    Polynomial(&'a [f32]),
+}
+
+impl<'a> Reduced<'a> {
+    /// Classifies coefficients by ascending degree, ignoring their trailing
+    /// zeros. `const`, like `degree`, so that fixed polynomials such as
+    /// calibration curves can be checked at build time.
+    pub const fn of(coefficients: &'a [f32]) -> Reduced<'a> {
+        let mut len = coefficients.len();
+        while len > 0 && coefficients[len - 1] == 0.0 {
+            len -= 1;
+        }
+        match coefficients.split_at(len).0 {
+            [] => Reduced::ZeroPolynomial,
+            [c] => Reduced::Constant(*c),
+            coefficients => Reduced::Polynomial(coefficients),
+        }
+    }
+
+    /// The degree, undefined for the zero polynomial.
+    pub const fn degree(self) -> Option<usize> {
+        match self {
+            Reduced::ZeroPolynomial => None,
+            Reduced::Constant(_) => Some(0),
+            Reduced::Polynomial(coefficients) => Some(coefficients.len() - 1),
+        }
+    }
+}
+
+impl Poly {
+    pub fn new(line: &str) -> Result<Poly, ParseError> {
+        let coefficients = parse(line)?;
+        Ok(Poly { coefficients })
+    }
+
+    /// The polynomial of coefficients by ascending degree, trailing zeros
+    /// dropped.
+    pub fn from_coefficients(coefficients: Vec<f32>) -> Poly {
+        let mut poly = Poly { coefficients };
+        poly.normalize();
+        poly
+    }
+
+    pub fn coefficients(&self) -> &[f32] {
+        &self.coefficients
+    }
+
+    /// The polynomial `lead * (X - r1) * ... * (X - rn)`.
+    pub fn from_roots(lead: f32, roots: &[f32]) -> Poly {
+        let mut coefficients = vec![lead];
+        for root in roots {
+            let mut product = vec![0.0; coefficients.len() + 1];
+            for (degree, c) in coefficients.iter().enumerate() {
+                product[degree + 1] += c;
+                product[degree] -= c * root;
+            }
+            coefficients = product;
+        }
+        Poly { coefficients }
+    }
+
+    /// Replaces the coefficient of `X^degree`, growing or trimming the
+    /// polynomial so that the degree stays that of the last nonzero term.
+    pub fn set_coefficient(&mut self, degree: usize, value: f32) {
+        if degree >= self.coefficients.len() {
+            self.coefficients.resize(degree + 1, 0.0);
+        }
+        self.coefficients[degree] = value;
+        self.normalize();
+    }
+
+    /// Adds `value * X^degree` to the polynomial.
+    pub fn add_term(&mut self, degree: usize, value: f32) {
+        let current = self.coefficients.get(degree).copied().unwrap_or(0.0);
+        self.set_coefficient(degree, current + value);
+    }
+
+    pub fn apply(&mut self, edit: Edit) {
+        match edit {
+            Edit::Set(degree, value) => self.set_coefficient(degree, value),
+            Edit::Add(degree, value) => self.add_term(degree, value),
+            Edit::PositiveLead => self.make_lead_positive(),
+        }
+    }
+
+    /// Drops the trailing zero coefficients, as parsing does.
+    fn normalize(&mut self) {
+        while self.coefficients.last() == Some(&0.0) {
+            self.coefficients.pop();
+        }
+    }
+
+    /// Moves every term to the other side of the equation when that makes the
+    /// leading coefficient positive. The solutions are left unchanged.
+    pub fn make_lead_positive(&mut self) {
+        if self.coefficients.last().is_some_and(|&lead| lead < 0.0) {
+            for c in self.coefficients.iter_mut() {
+                *c = -*c;
+            }
+        }
+    }
+
+    pub fn coefficients_f64(&self) -> Vec<f64> {
+        self.coefficients.iter().map(|&c| c as f64).collect()
+    }
+
+    /// The coefficients as written, scaled to coprime integers with a
+    /// positive leading one, so that equations only differing by a factor or
+    /// by how their terms are spread over the two sides agree. None when the
+    /// integers overflow.
+    pub fn canonical_form(&self) -> Option<Vec<i128>> {
+        let (mut integers, _) = decimal::scaled_integers(&self.coefficients)?;
+        let divisor = integers.iter().fold(0, |a, &b| gcd(a, b));
+        let lead = integers.last().copied().unwrap_or(1).signum();
+        for c in integers.iter_mut() {
+            *c = *c / divisor.max(1) * lead;
+        }
+        Some(integers)
+    }
+
+    /// The `k` with `self = k * other` as a reduced fraction, computed on
+    /// the decimal coefficients as written so that `2 * X^2 - 8` and
+    /// `X^2 - 4` give exactly 2. None when the polynomials are not multiples,
+    /// are zero, or when the integers overflow.
+    pub fn scale_factor(&self, other: &Poly) -> Option<(i128, i128)> {
+        if self.coefficients.len() != other.coefficients.len() || self.is_zero() {
+            return None;
+        }
+        let (p, p_scale) = decimal::scaled_integers(&self.coefficients)?;
+        let (q, q_scale) = decimal::scaled_integers(&other.coefficients)?;
+        let (a, b) = (p[p.len() - 1], q[q.len() - 1]);
+        for (&c, &d) in p.iter().zip(&q) {
+            if c.checked_mul(b)? != d.checked_mul(a)? {
+                return None;
+            }
+        }
+        // self = (a / p_scale) / (b / q_scale) * other
+        let numerator = a.checked_mul(q_scale)?;
+        let denominator = b.checked_mul(p_scale)?;
+        let divisor = gcd(numerator, denominator) * denominator.signum();
+        Some((numerator / divisor, denominator / divisor))
+    }
+
+    /// FNV-1a of the canonical form, or of the monic coefficients when it
+    /// overflows. Unlike `std::hash`, the value is the same on every
+    /// platform and release, for fingerprints stored across runs.
+    pub fn canonical_hash(&self) -> u64 {
+        let bytes: Vec<u8> = match self.canonical_form() {
+            Some(integers) => integers.iter().flat_map(|c| c.to_le_bytes()).collect(),
+            None => {
+                let lead = self.coefficients.last().copied().unwrap_or(1.0);
+                let monic = self.coefficients.iter().map(|c| (c / lead + 0.0).to_bits());
+                // a distinct first byte, 16 bytes per integer otherwise
+                std::iter::once(1)
+                    .chain(monic.flat_map(u32::to_le_bytes))
+                    .collect()
+            }
+        };
+        bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+            (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+        })
+    }
+
+    pub fn reduced(&self) -> Reduced<'_> {
+        Reduced::of(&self.coefficients)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.reduced() == Reduced::ZeroPolynomial
+    }
+
+    /// The degree, undefined for the zero polynomial.
+    pub fn degree(&self) -> Option<usize> {
+        self.reduced().degree()
+    }
+
+    /// The largest coefficient in absolute value.
+    pub fn height(&self) -> f32 {
+        numeric::height(&self.coefficients_f64()) as f32
+    }
+
+    pub fn l2_norm(&self) -> f32 {
+        numeric::l2_norm(&self.coefficients_f64()) as f32
+    }
+
+    /// An estimate from the numeric roots, 0 for the zero polynomial.
+    pub fn mahler_measure(&self) -> f32 {
+        if self.is_zero() {
+            return 0.0;
+        }
+        numeric::mahler_measure(&self.coefficients_f64()) as f32
+    }
+
+    pub fn get_degree(&self) -> i32 {
+        let degree: i32 = self.coefficients.len().try_into().unwrap();
+        degree - 1
+    }
+
+    pub fn solve(&self) -> Option<Vec<f32>> {
+        match self.get_degree() {
+            0 => {
+                if self.coefficients[0] == 0.0 {
+                    Some(vec![])
+                } else {
+                    None
+                }
+            }
+            1 => Some(vec![-self.coefficients[0] / self.coefficients[1]]),
+            2 => self.quadratic_formula(),
+            _ => None,
+        }
+    }
+
+    /// The largest `k` such that `X^k` divides every term, and the quotient,
+    /// whose roots are the others. `(0, self)` for the zero polynomial.
+    pub fn factor_x_power(&self) -> (usize, Poly) {
+        let k = match self.is_zero() {
+            true => 0,
+            false => self.coefficients.iter().take_while(|&&c| c == 0.0).count(),
+        };
+        (k, Poly::from_coefficients(self.coefficients[k..].to_vec()))
+    }
+
+    pub fn approximate_roots(&self) -> Vec<f32> {
+        let coefficients = self.coefficients_f64();
+        numeric::real_roots(&coefficients)
+            .into_iter()
+            .map(|root| root as f32)
+            .collect()
+    }
+
+    pub fn count_roots(&self, a: f32, b: f32) -> Option<usize> {
+        if self.is_zero() {
+            return None;
+        }
+        let coefficients = self.coefficients_f64();
+        Some(numeric::count_roots(&coefficients, a as f64, b as f64))
+    }
+
+    /// Whether a real root lies in `[a, b]`, without finding it: a sign
+    /// change between the ends settles it at the cost of two evaluations,
+    /// Sturm's theorem otherwise. Always true for `0 = 0`.
+    pub fn has_real_root_in(&self, a: f32, b: f32) -> bool {
+        let coefficients = self.coefficients_f64();
+        let (fa, fb) = (
+            numeric::eval(&coefficients, a as f64),
+            numeric::eval(&coefficients, b as f64),
+        );
+        if self.is_zero() || fa * fb <= 0.0 {
+            return true;
+        }
+        coefficients.len() > 2 && numeric::count_roots(&coefficients, a as f64, b as f64) > 0
+    }
+
+    /// The real roots printed by `print`: exact for degree 1 and 2,
+    /// approximated numerically above unless factoring out `X^k` leaves
+    /// a lower degree, 0 then being added to the roots of the quotient.
+    pub fn real_roots(&self) -> Vec<f32> {
+        match (self.get_degree(), self.factor_x_power()) {
+            (1 | 2, _) => self.solve().unwrap_or_default(),
+            (d, (0, _)) if d > 2 => {
+                match (cyclotomic::recognize(&self.coefficients), self.factors()) {
+                    (Some(family), _) => family.real_roots(),
+                    (None, Some(factors)) => factor::real_roots(&factors),
+                    (None, None) => self.approximate_roots(),
+                }
+            }
+            (d, (_, rest)) if d > 2 => {
+                let mut roots = rest.real_roots();
+                roots.push(0.0);
+                roots.sort_by(f32::total_cmp);
+                roots
+            }
+            _ => vec![],
+        }
+    }
+
+    /// Factors with the same roots, from grouping or from the base of a
+    /// perfect power, whose roots are solved instead.
+    fn factors(&self) -> Option<Vec<Poly>> {
+        factor::by_grouping(&self.coefficients)
+            .or_else(|| factor::perfect_power(&self.coefficients).map(|power| vec![power.base]))
+    }
+
+    /// Whether `real_roots` are exact rather than approximated.
+    pub fn has_exact_roots(&self) -> bool {
+        let (_, rest) = self.factor_x_power();
+        rest.get_degree() <= 2
+            || cyclotomic::recognize(&rest.coefficients).is_some()
+            || rest
+                .factors()
+                .is_some_and(|factors| factors.iter().all(Poly::has_exact_roots_of_factor))
+    }
+
+    /// Like `has_exact_roots`, binomials being solved exactly as factors.
+    fn has_exact_roots_of_factor(&self) -> bool {
+        factor::binomial_roots(&self.coefficients).is_some() || self.has_exact_roots()
+    }
+
+    pub fn discriminant(&self) -> Option<f32> {
+        match self.coefficients[..] {
+            [c, b, a] => Some(b * b - 4.0 * a * c),
+            _ => None,
+        }
+    }
+
+    pub fn report(&self, style: Style) -> Report {
+        self.report_with_roots(style, self.real_roots())
+    }
+
+    /// The report for real roots already computed, e.g. by a cache.
+    pub fn report_with_roots(&self, style: Style, solutions: Vec<f32>) -> Report {
+        let reduced_form = format!("{} = 0", format_polynomial(&self.coefficients, style));
+        let degree = self.degree().unwrap_or(0);
+        Report {
+            fingerprint: format!("{:016x}", self.canonical_hash()),
+            discriminant: self.discriminant(),
+            solutions,
+            every_real_is_solution: self.is_zero(),
+            exact: self.has_exact_roots(),
+            ..Report::new(reduced_form, degree, self.coefficients.clone())
+        }
+    }
+
+    /// Matches each candidate to a different real solution within the
+    /// tolerance, relative to the magnitude of the solution. Returns the
+    /// verdict for each candidate and the solutions left unmatched.
+    pub fn check_answer(&self, candidates: &[f32], tolerance: f32) -> (Vec<(f32, bool)>, Vec<f32>) {
+        if self.is_zero() {
+            return (candidates.iter().map(|&c| (c, true)).collect(), vec![]);
+        }
+        let mut missing = self.real_roots();
+        let verdicts = candidates
+            .iter()
+            .map(|&candidate| {
+                let found = missing
+                    .iter()
+                    .position(|root| (candidate - root).abs() <= tolerance * root.abs().max(1.0));
+                if let Some(i) = found {
+                    missing.remove(i);
+                }
+                (candidate, found.is_some())
+            })
+            .collect();
+        (verdicts, missing)
+    }
+
+    fn quadratic_formula(&self) -> Option<Vec<f32>> {
+        let a = self.coefficients[2];
+        let b = self.coefficients[1];
+        let c = self.coefficients[0];
+        let discriminant = b * b - 4.0 * a * c;
+        match discriminant {
+            d if d > 0.0 => Some(vec![
+                (-b + d.sqrt()) / (2.0 * a),
+                (-b - d.sqrt()) / (2.0 * a),
+            ]),
+            0.0 => Some(vec![-b / (2.0 * a)]),
+            _ => None,
+        }
+    }
+}
+
+/// Nonnegative, shared by the modules working on integer coefficients.
+fn gcd(a: i128, b: i128) -> i128 {
+    if b == 0 {
+        a.abs()
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+impl Add for Poly {
+    type Output = Poly;
+
+    fn add(mut self, other: Poly) -> Poly {
+        for (degree, &c) in other.coefficients.iter().enumerate() {
+            self.add_term(degree, c);
+        }
+        self
+    }
+}
+
+impl Neg for Poly {
+    type Output = Poly;
+
+    fn neg(self) -> Poly {
+        let coefficients = self.coefficients.iter().map(|c| -c).collect();
+        Poly { coefficients }
+    }
+}
+
+impl Sub for Poly {
+    type Output = Poly;
+
+    fn sub(self, other: Poly) -> Poly {
+        self + -other
+    }
+}
+
+impl Mul for Poly {
+    type Output = Poly;
+
+    fn mul(self, other: Poly) -> Poly {
+        if self.is_zero() || other.is_zero() {
+            return Poly {
+                coefficients: vec![],
+            };
+        }
+        let mut product = Poly {
+            coefficients: vec![0.0; self.coefficients.len() + other.coefficients.len() - 1],
+        };
+        for (i, a) in self.coefficients.iter().enumerate() {
+            for (j, b) in other.coefficients.iter().enumerate() {
+                product.coefficients[i + j] += a * b;
+            }
+        }
+        product.normalize();
+        product
+    }
+}
+
+fn parse(line: &str) -> Result<Vec<f32>, ParseError> {
+    let (left, right) = expr::parse_equation(line)?;
+    expr::Expr::Sub(Box::new(left), Box::new(right)).expand()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn equivalent_solution(left: Vec<f32>, right: Vec<f32>) -> bool {
+        if left.len() != right.len() {
+            return false;
+        }
+        let wrong = left
+            .iter()
+            .zip(right)
+            .filter(|&(a, b)| (a - b).abs() > 0.00001)
+            .count();
+        wrong == 0
+    }
+
+    #[test]
+    fn update_coefficients_in_place() {
+        let mut poly = Poly::new("X^2 - 4 = 0").unwrap();
+        poly.set_coefficient(2, 0.0);
+        assert_eq!((poly.get_degree(), poly.real_roots()), (0, vec![]));
+        poly.add_term(1, 2.0);
+        assert_eq!(poly.real_roots(), vec![2.0]);
+        poly.add_term(3, 1.0);
+        poly.add_term(3, -1.0);
+        assert_eq!(poly.coefficients, vec![-4.0, 2.0]);
+        poly.set_coefficient(0, 0.0);
+        poly.set_coefficient(1, 0.0);
+        assert_eq!(poly.get_degree(), -1);
+    }
+
+    #[test]
+    fn zero_polynomial() {
+        for line in ["0 = 0", "X - X = 0", "0 * X^5 = 0"] {
+            let poly = Poly::new(line).unwrap();
+            assert_eq!(poly.reduced(), Reduced::ZeroPolynomial, "{}", line);
+            assert_eq!(poly.degree(), None);
+            let report = poly.report(Style::default());
+            assert!(report.every_real_is_solution && report.solutions.is_empty());
+            assert_eq!((report.degree, report.reduced_form.as_str()), (0, "0 = 0"));
+            assert_eq!(poly.count_roots(-1.0, 1.0), None);
+            assert_eq!((poly.height(), poly.mahler_measure()), (0.0, 0.0));
+        }
+        const CALIBRATION: Reduced = Reduced::of(&[0.5, 2.0, 0.0]);
+        const DEGREE: Option<usize> = CALIBRATION.degree();
+        assert_eq!(DEGREE, Some(1));
+        assert_eq!(Reduced::of(&[0.0, 0.0]), Reduced::ZeroPolynomial);
+        let constant = Poly::new("5 = 0").unwrap();
+        assert_eq!(constant.reduced(), Reduced::Constant(5.0));
+        assert_eq!(constant.degree(), Some(0));
+        assert!(!constant.report(Style::default()).every_real_is_solution);
+        assert_eq!(Poly::new("X^2 = 1").unwrap().degree(), Some(2));
+    }
+
+    #[test]
+    fn canonical_hash_ignores_scaling() {
+        let hash = |line| Poly::new(line).unwrap().canonical_hash();
+        assert_eq!(hash("X^2 = 4"), hash("8 - 2 * X^2 = 0"));
+        assert_eq!(hash("0.5 * X = 1"), hash("X - 2 = 0"));
+        assert_ne!(hash("X^2 = 4"), hash("X^2 = 9"));
+        assert_eq!(
+            Poly::new("0.5 * X = 1").unwrap().canonical_form(),
+            Some(vec![-2, 1])
+        );
+        // stable across releases, unlike std::hash
+        assert_eq!(hash("X = 1"), 0x0752_1e73_d6c7_dd54);
+    }
+
+    #[test]
+    fn scale_factor_is_exact() {
+        let poly = |line| Poly::new(line).unwrap();
+        assert_eq!(
+            poly("2*X^2 - 8 = 0").scale_factor(&poly("X^2 - 4 = 0")),
+            Some((2, 1))
+        );
+        assert_eq!(
+            poly("X^2 = 4").scale_factor(&poly("-2 * X^2 + 8 = 0")),
+            Some((-1, 2))
+        );
+        assert_eq!(
+            poly("0.3 * X = 0.1").scale_factor(&poly("3 * X = 1")),
+            Some((1, 10))
+        );
+        assert_eq!(poly("X^2 = 4").scale_factor(&poly("X^2 = 9")), None);
+        assert_eq!(poly("X = X").scale_factor(&poly("0 = 0")), None);
+    }
+
+    #[test]
+    fn polynomial_operators() {
+        let p = || Poly::new("X^2 - 1 = 0").unwrap();
+        let q = || Poly::new("X + 1 = 0").unwrap();
+        assert_eq!((p() + q()).coefficients, [0.0, 1.0, 1.0]);
+        assert_eq!((p() - q()).coefficients, [-2.0, -1.0, 1.0]);
+        assert_eq!((p() * q()).coefficients, [-1.0, -1.0, 1.0, 1.0]);
+        assert!((p() - p()).coefficients.is_empty());
+        assert!((p() * Poly::new("0 = 0").unwrap()).coefficients.is_empty());
+    }
+
+    #[test]
+    fn from_roots_expands_product() {
+        let poly = Poly::from_roots(2.0, &[1.0, -3.0]);
+        assert_eq!(poly.coefficients, vec![-6.0, 4.0, 2.0]);
+        assert_eq!(Poly::from_roots(5.0, &[]).coefficients, vec![5.0]);
+    }
+
+    #[test]
+    fn check_answer_matches_each_solution_once() {
+        let poly = Poly::new("X^2 - 4 = 0").unwrap();
+        assert_eq!(
+            poly.check_answer(&[2.0, -2.00001], 1e-4),
+            (vec![(2.0, true), (-2.00001, true)], vec![])
+        );
+        assert_eq!(
+            poly.check_answer(&[2.0, 2.0], 1e-4),
+            (vec![(2.0, true), (2.0, false)], vec![-2.0])
+        );
+        let identity = Poly::new("X = X").unwrap();
+        assert!(identity.check_answer(&[7.0], 1e-4).1.is_empty());
+    }
+
+    #[test]
+    fn error_when_no_equal_sign() {
+        let no_equal_sign = "5 * X^0 + 4 * X^1 - 9.3 * X^2";
+        assert!(parse(no_equal_sign).is_err());
+    }
+
+    #[test]
+    fn parse_basic_monomial() {
+        let basic_monomial = "5*X^0 = 0";
+        assert_eq!(parse(basic_monomial), Ok(vec![5.0]));
+    }
+
+    #[test]
+    fn test_parse_equation() {
+        let line = "8 * X^0 - 6 * X^1 + 0 * X^2 - 5.6 * X^3 = 3 * X^0";
+        let simplified = parse(line);
+        let answer: Vec<f32> = vec![5.0, -6.0, 0.0, -5.6];
+        assert_eq!(simplified, Ok(answer));
+    }
+
+    #[test]
+    fn test_parse_bonus() {
+        let line = "5 + 4 * X + X^2= X^2";
+        let simplified = parse(line);
+        let answer: Vec<f32> = vec![5.0, 4.0];
+        assert_eq!(simplified, Ok(answer));
+    }
+
+    #[test]
+    fn test_poly() {
+        let line = "5 * X^0 + 4 * X^1 - 9.3 * X^2 = 1 * X^0";
+        let coefficients: Vec<f32> = vec![4.0, 4.0, -9.3];
+        let poly = Poly::new(line).unwrap();
+        assert_eq!(poly.coefficients, coefficients);
+        assert_eq!(poly.get_degree(), 2);
+    }
+
+    #[test]
+    fn positive_lead() {
+        let mut poly = Poly::new("5 * X^0 + 4 * X^1 - 9.3 * X^2 = 1 * X^0").unwrap();
+        poly.make_lead_positive();
+        assert_eq!(poly.coefficients, vec![-4.0, -4.0, 9.3]);
+        poly.make_lead_positive();
+        assert_eq!(poly.coefficients, vec![-4.0, -4.0, 9.3]);
+    }
+
+    #[test]
+    fn test_solve() {
+        let line = "5 * X^0 + 4 * X^1 - 9.3 * X^2 = 1 * X^0";
+        let poly = Poly::new(line).unwrap();
+        let solutions = poly.solve().unwrap();
+        assert!(equivalent_solution(solutions, vec![-0.475131, 0.905239]));
+
+        let line = "5 * X^0 + 4 * X^1 = 4 * X^0";
+        let poly = Poly::new(line).unwrap();
+        let solutions = poly.solve().unwrap();
+        assert!(equivalent_solution(solutions, vec![-0.25]));
+
+        let line = "8 * X^0 - 6 * X^1 + 0 * X^2 - 5.6 * X^3 = 3 * X^0";
+        let poly = Poly::new(line).unwrap();
+        let solutions = poly.solve();
+        assert_eq!(solutions, None);
+
+        let line = "5 + 4 * X + X^2= X^2";
+        let poly = Poly::new(line).unwrap();
+        let solutions = poly.solve().unwrap();
+        assert!(equivalent_solution(solutions, vec![-1.25]));
+
+        let line = "42 * X^0= 42 * X^0";
+        let poly = Poly::new(line).unwrap();
+        let solutions = poly.solve();
+        assert_eq!(solutions, None);
+
+        let line = "3 = 0";
+        let poly = Poly::new(line).unwrap();
+        let solutions = poly.solve();
+        assert_eq!(solutions, None);
+    }
+
+    #[test]
+    fn approximate_roots_above_degree_2() {
+        let line = "8 * X^0 - 6 * X^1 + 0 * X^2 - 5.6 * X^3 = 3 * X^0";
+        let poly = Poly::new(line).unwrap();
+        assert!(equivalent_solution(
+            poly.approximate_roots(),
+            vec![0.615598]
+        ));
+    }
+
+    #[test]
+    fn real_root_in_interval() {
+        let poly = |line| Poly::new(line).unwrap();
+        // sign change at the ends
+        assert!(poly("X^2 = 2").has_real_root_in(1.0, 2.0));
+        // no sign change, but (X - 1)(X - 2) has both roots inside
+        assert!(poly("X^2 - 3 * X + 2 = 0").has_real_root_in(0.0, 3.0));
+        // a root at an end
+        assert!(poly("X = 1").has_real_root_in(1.0, 5.0));
+        assert!(!poly("X^2 + 1 = 0").has_real_root_in(-10.0, 10.0));
+        assert!(!poly("X^2 - 3 * X + 2 = 0").has_real_root_in(3.0, 9.0));
+        assert!(!poly("3 = 0").has_real_root_in(-1.0, 1.0));
+        assert!(poly("X = X").has_real_root_in(-1.0, 1.0));
+    }
+
+    #[test]
+    fn common_x_power_is_factored_out() {
+        let poly = Poly::new("X^3 - 4 * X = 0").unwrap();
+        let (k, rest) = poly.factor_x_power();
+        assert_eq!((k, rest.coefficients()), (1, &[-4.0, 0.0, 1.0][..]));
+        assert_eq!(poly.real_roots(), [-2.0, 0.0, 2.0]);
+        assert!(poly.has_exact_roots() && poly.report(Style::default()).exact);
+        let poly = Poly::new("X^5 + X^3 = 0").unwrap();
+        assert_eq!(poly.factor_x_power().0, 3);
+        assert_eq!(poly.real_roots(), [0.0]);
+        assert_eq!(Poly::new("X^4 = 0").unwrap().real_roots(), [0.0]);
+        assert!(!Poly::new("X^4 + X^2 + X = 0").unwrap().has_exact_roots());
+        assert_eq!(Poly::new("X = X").unwrap().factor_x_power().0, 0);
+        assert_eq!(Poly::new("X^3 = 1").unwrap().factor_x_power().0, 0);
+    }
+
+    #[test]
+    fn perfect_power_roots_come_from_the_base() {
+        // (X^2 - 2)^2 never changes sign, bracketing would find nothing
+        let poly = Poly::new("X^4 - 4 * X^2 + 4 = 0").unwrap();
+        let roots = poly.real_roots();
+        assert_eq!(roots.len(), 2);
+        assert!((roots[1] - 2f32.sqrt()).abs() < 1e-6 && roots[0] == -roots[1]);
+        assert!(poly.has_exact_roots());
+        assert!(!Poly::new("(X^3 + X + 1)^2 = 0").unwrap().has_exact_roots());
+    }
+
+    #[test]
+    fn roots_of_even_multiplicity_are_found() {
+        let roots = Poly::new("(X - 1)^2 * (X - 2) = 0").unwrap().real_roots();
+        assert_eq!(roots.len(), 2);
+        assert!((roots[0] - 1.0).abs() < 1e-6 && (roots[1] - 2.0).abs() < 1e-6);
+        let poly = Poly::new("(X - 1)^2 * (X^2 + 1) = 0").unwrap();
+        assert_eq!(poly.real_roots().len(), 1);
+        assert!(poly.has_real_root_in(0.0, 2.0));
+    }
+}
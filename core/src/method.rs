@@ -0,0 +1,431 @@
+use crate::complex::Complex;
+use crate::float;
+use crate::numeric::{self, Work};
+use std::time::Instant;
+
+/// Above this ratio between the largest and smallest nonzero coefficient,
+/// roots of very different sizes may share a cell of the bracketing grid.
+const SPREAD_LIMIT: f64 = 1e8;
+
+/// How close to the real axis a simultaneous root must be to count as real,
+/// relative to its magnitude.
+const REAL_TOLERANCE: f64 = 1e-7;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub enum Method {
+    Auto,
+    /// The linear and quadratic formulas.
+    ClosedForm,
+    /// `Y = X^k` when only multiples of `k` appear as exponents, leaving an
+    /// equation of degree 1 or 2 in `Y`.
+    Substitution,
+    /// Sign changes on a grid, polished with Newton and Brent.
+    Bracketing,
+    /// Every complex root at once, keeping the real ones.
+    DurandKerner,
+}
+
+pub const METHODS: [(&str, Method); 5] = [
+    ("auto", Method::Auto),
+    ("closed-form", Method::ClosedForm),
+    ("substitution", Method::Substitution),
+    ("bracketing", Method::Bracketing),
+    ("durand-kerner", Method::DurandKerner),
+];
+
+impl Method {
+    pub fn name(self) -> &'static str {
+        METHODS
+            .iter()
+            .find(|(_, method)| *method == self)
+            .map_or("", |(name, _)| name)
+    }
+}
+
+fn degree(coefficients: &[f64]) -> usize {
+    coefficients.len().saturating_sub(1)
+}
+
+/// The largest `k` dividing every exponent with a nonzero coefficient.
+pub(crate) fn exponent_step(coefficients: &[f64]) -> usize {
+    fn gcd(a: usize, b: usize) -> usize {
+        if b == 0 {
+            a
+        } else {
+            gcd(b, a % b)
+        }
+    }
+    coefficients
+        .iter()
+        .enumerate()
+        .skip(1)
+        .filter(|(_, &c)| c != 0.0)
+        .fold(0, |step, (exponent, _)| gcd(step, exponent))
+}
+
+/// Whether the polynomial shares a root with its derivative, which
+/// bracketing misses when the multiplicity is even.
+fn has_repeated_root(coefficients: &[f64]) -> bool {
+    numeric::gcd(coefficients, &numeric::derivative(coefficients)).len() > 1
+}
+
+/// The ratio between the largest and the smallest nonzero coefficient.
+fn spread(coefficients: &[f64]) -> f64 {
+    let magnitudes = coefficients.iter().map(|c| c.abs()).filter(|&c| c > 0.0);
+    let (low, high) = magnitudes.fold((f64::INFINITY, 0.0), |(low, high): (f64, f64), c| {
+        (low.min(c), high.max(c))
+    });
+    high / low
+}
+
+/// The solver `auto` routes to, and why.
+pub fn choose(coefficients: &[f64]) -> (Method, String) {
+    let degree = degree(coefficients);
+    if degree <= 2 {
+        return (
+            Method::ClosedForm,
+            format!("degree {} has a closed form", degree),
+        );
+    }
+    let step = exponent_step(coefficients);
+    if step > 1 && degree / step <= 2 {
+        return (
+            Method::Substitution,
+            format!(
+                "only powers of X^{} appear, degree {} in Y = X^{}",
+                step,
+                degree / step,
+                step
+            ),
+        );
+    }
+    if has_repeated_root(coefficients) {
+        return (
+            Method::DurandKerner,
+            String::from("a repeated root may not change sign"),
+        );
+    }
+    if spread(coefficients) > SPREAD_LIMIT {
+        return (
+            Method::DurandKerner,
+            format!(
+                "the coefficients span more than {}, too wide for the grid",
+                float::shortest_scientific(SPREAD_LIMIT as f32)
+            ),
+        );
+    }
+    (
+        Method::Bracketing,
+        format!("degree {} with no special form", degree),
+    )
+}
+
+/// The real roots of an equation of degree 1 or 2 in the order of the
+/// quadratic formula, `None` above.
+fn closed_form(coefficients: &[f64]) -> Option<Vec<f64>> {
+    match *coefficients {
+        [_] | [] => Some(vec![]),
+        [b, a] => Some(vec![-b / a]),
+        [c, b, a] => {
+            let discriminant = b * b - 4.0 * a * c;
+            Some(match discriminant {
+                d if d > 0.0 => vec![(-b + d.sqrt()) / (2.0 * a), (-b - d.sqrt()) / (2.0 * a)],
+                0.0 => vec![-b / (2.0 * a)],
+                _ => vec![],
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Solves for `Y = X^k`, then takes the real `k`-th roots of each `Y`.
+fn substitution(coefficients: &[f64]) -> Option<Vec<f64>> {
+    let step = exponent_step(coefficients);
+    if step <= 1 {
+        return None;
+    }
+    let reduced: Vec<f64> = coefficients.iter().step_by(step).copied().collect();
+    let mut roots = vec![];
+    for y in closed_form(&reduced)? {
+        let x = y.abs().powf(1.0 / step as f64);
+        if y == 0.0 {
+            roots.push(0.0);
+        } else if step % 2 == 1 {
+            roots.push(x.copysign(y));
+        } else if y > 0.0 {
+            roots.extend([-x, x]);
+        }
+    }
+    roots.sort_by(f64::total_cmp);
+    Some(roots)
+}
+
+fn durand_kerner(coefficients: &[f64], work: &mut Work) -> Vec<f64> {
+    let mut roots: Vec<f64> = numeric::durand_kerner_counting(coefficients, work)
+        .into_iter()
+        .filter(|z| z.im.abs() <= REAL_TOLERANCE * z.norm().max(1.0))
+        .map(|z| z.re)
+        .collect();
+    roots.sort_by(f64::total_cmp);
+    // the copies of a repeated root converge side by side
+    roots.dedup_by(|a, b| (*a - *b).abs() <= 1e-6 * b.abs().max(1.0));
+    roots
+}
+
+/// Every root of an equation of degree 1 or 2, `None` above.
+fn complex_closed_form(coefficients: &[f64]) -> Option<Vec<Complex>> {
+    match *coefficients {
+        [c, b, a] if b * b - 4.0 * a * c < 0.0 => {
+            let (re, im) = (
+                -b / (2.0 * a),
+                (4.0 * a * c - b * b).sqrt() / (2.0 * a).abs(),
+            );
+            Some(vec![
+                Complex::new(re + 0.0, im),
+                Complex::new(re + 0.0, -im),
+            ])
+        }
+        _ => Some(
+            closed_form(coefficients)?
+                .into_iter()
+                .map(Complex::from)
+                .collect(),
+        ),
+    }
+}
+
+/// Every `k`-th root of every `Y`, complex or not.
+fn complex_substitution(coefficients: &[f64]) -> Option<Vec<Complex>> {
+    let step = exponent_step(coefficients);
+    if step <= 1 {
+        return None;
+    }
+    let reduced: Vec<f64> = coefficients.iter().step_by(step).copied().collect();
+    let mut roots = vec![];
+    for y in complex_closed_form(&reduced)? {
+        let norm = y.norm().powf(1.0 / step as f64);
+        let angle = y.im.atan2(y.re);
+        for k in 0..step {
+            let turn = 2.0 * std::f64::consts::PI * k as f64;
+            roots.push(Complex::from_polar(norm, (angle + turn) / step as f64));
+        }
+    }
+    Some(roots)
+}
+
+/// The complex roots from the same method as the real ones, none when it
+/// does not apply. The numeric methods run Durand-Kerner on the
+/// square-free part, where no copies of a repeated root spread around it.
+pub fn complex_roots_counting(
+    method: Method,
+    coefficients: &[f64],
+    work: &mut Work,
+) -> Vec<Complex> {
+    if degree(coefficients) < 1 {
+        return vec![];
+    }
+    match method {
+        Method::Auto => complex_roots_counting(choose(coefficients).0, coefficients, work),
+        Method::ClosedForm => complex_closed_form(coefficients).unwrap_or_default(),
+        Method::Substitution => complex_substitution(coefficients).unwrap_or_default(),
+        Method::Bracketing | Method::DurandKerner => {
+            numeric::durand_kerner_counting(&numeric::square_free_part(coefficients), work)
+        }
+    }
+}
+
+/// The real roots found by the method, `None` when it does not apply. Auto
+/// always applies for degree 1 or more.
+pub fn solve(method: Method, coefficients: &[f64]) -> Option<Vec<f64>> {
+    solve_counting(method, coefficients, &mut Work::default())
+}
+
+/// Adds the iterations of the numeric methods to `work`, the closed forms
+/// count none and cannot be cancelled.
+pub fn solve_counting(method: Method, coefficients: &[f64], work: &mut Work) -> Option<Vec<f64>> {
+    if degree(coefficients) < 1 {
+        return None;
+    }
+    match method {
+        Method::Auto => solve_counting(choose(coefficients).0, coefficients, work),
+        Method::ClosedForm => closed_form(coefficients),
+        Method::Substitution => substitution(coefficients),
+        Method::Bracketing => Some(numeric::real_roots_counting(coefficients, work)),
+        Method::DurandKerner => Some(durand_kerner(coefficients, work)),
+    }
+}
+
+/// One row of the comparison.
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct Run {
+    pub method: Method,
+    pub roots: Vec<f64>,
+    /// The largest `|P(x)|` over the roots.
+    pub residual: f64,
+    pub iterations: usize,
+    pub micros: u128,
+}
+
+/// Every method but auto that applies, in the order of `METHODS`.
+pub fn compare(coefficients: &[f64]) -> Vec<Run> {
+    let mut runs = vec![];
+    for (_, method) in &METHODS[1..] {
+        let mut work = Work::default();
+        let start = Instant::now();
+        let roots = solve_counting(*method, coefficients, &mut work);
+        let micros = start.elapsed().as_micros();
+        let Some(roots) = roots else {
+            continue;
+        };
+        let residual = roots
+            .iter()
+            .map(|&x| numeric::eval(coefficients, x).abs())
+            .fold(0.0, f64::max);
+        runs.push(Run::new(*method, roots, residual, work.iterations, micros));
+    }
+    runs
+}
+
+impl Run {
+    pub fn new(
+        method: Method,
+        roots: Vec<f64>,
+        residual: f64,
+        iterations: usize,
+        micros: u128,
+    ) -> Run {
+        Run {
+            method,
+            roots,
+            residual,
+            iterations,
+            micros,
+        }
+    }
+}
+
+/// The runs side by side, the timings left out for reproducible output.
+pub fn comparison_table(runs: &[Run], timings: bool) -> String {
+    let rows: Vec<[String; 5]> = runs
+        .iter()
+        .map(|run| {
+            let roots: Vec<String> = run
+                .roots
+                .iter()
+                .map(|&x| float::shortest(x as f32))
+                .collect();
+            [
+                run.method.name().to_string(),
+                if roots.is_empty() {
+                    String::from("none")
+                } else {
+                    roots.join(", ")
+                },
+                float::scientific(run.residual, 1),
+                run.iterations.to_string(),
+                if timings {
+                    format!("{} µs", run.micros)
+                } else {
+                    String::from("-")
+                },
+            ]
+        })
+        .collect();
+    let header = ["method", "real roots", "residual", "iterations", "time"];
+    let mut widths = header.map(|title| title.chars().count());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let line = |cells: [&str; 5]| {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect();
+        format!("{}\n", padded.join("  ").trim_end())
+    };
+    let mut table = line(header);
+    for row in &rows {
+        table += &line(row.each_ref().map(|cell| cell.as_str()));
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn auto_chooses_by_form() {
+        let chosen = |coefficients: &[f64]| choose(coefficients).0;
+        assert_eq!(chosen(&[-4.0, 0.0, 1.0]), Method::ClosedForm);
+        // X^4 - 5 * X^2 + 4
+        assert_eq!(chosen(&[4.0, 0.0, -5.0, 0.0, 1.0]), Method::Substitution);
+        // (X - 1)^2 * (X + 2)
+        assert_eq!(chosen(&[2.0, -3.0, 0.0, 1.0]), Method::DurandKerner);
+        assert_eq!(chosen(&[1e-9, 0.0, 3.0, 1.0]), Method::DurandKerner);
+        assert_eq!(chosen(&[6.0, -7.0, 0.0, 1.0]), Method::Bracketing);
+        assert!(choose(&[4.0, 0.0, -5.0, 0.0, 1.0]).1.contains("Y = X^2"));
+    }
+
+    #[test]
+    fn methods_agree_on_roots() {
+        // X^4 - 5 * X^2 + 4, roots ±1 and ±2
+        let coefficients = [4.0, 0.0, -5.0, 0.0, 1.0];
+        assert_eq!(
+            solve(Method::Substitution, &coefficients),
+            Some(vec![-2.0, -1.0, 1.0, 2.0])
+        );
+        for method in [Method::Bracketing, Method::DurandKerner, Method::Auto] {
+            let roots = solve(method, &coefficients).unwrap();
+            assert_eq!(roots.len(), 4);
+            for (root, expected) in roots.iter().zip([-2.0, -1.0, 1.0, 2.0]) {
+                assert!((root - expected).abs() < 1e-9);
+            }
+        }
+        assert_eq!(solve(Method::ClosedForm, &coefficients), None);
+        assert_eq!(solve(Method::Substitution, &[6.0, -7.0, 0.0, 1.0]), None);
+        // X^3 + 8 = 0 has the single real root -2
+        assert_eq!(
+            solve(Method::Substitution, &[8.0, 0.0, 0.0, 1.0]),
+            Some(vec![-2.0])
+        );
+    }
+
+    #[test]
+    fn compares_applicable_methods() {
+        // X^4 - 5 * X^2 + 4
+        let runs = compare(&[4.0, 0.0, -5.0, 0.0, 1.0]);
+        let methods: Vec<Method> = runs.iter().map(|run| run.method).collect();
+        assert_eq!(
+            methods,
+            [
+                Method::Substitution,
+                Method::Bracketing,
+                Method::DurandKerner
+            ]
+        );
+        assert!(runs.iter().all(|run| run.residual < 1e-9));
+        assert_eq!(runs[0].iterations, 0);
+        assert!(runs[2].iterations > 0);
+        let table = comparison_table(&runs, false);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("method         real roots"));
+        assert!(lines[1].starts_with("substitution   -2, -1, 1, 2  0.0e0"));
+        assert!(lines[1].ends_with("  -"));
+    }
+
+    #[test]
+    fn repeated_roots_are_found_once() {
+        // (X - 1)^2 * (X + 2), the double root does not change sign
+        let coefficients = [2.0, -3.0, 0.0, 1.0];
+        let roots = solve(Method::Auto, &coefficients).unwrap();
+        assert_eq!(roots.len(), 2);
+        assert!((roots[0] + 2.0).abs() < 1e-9 && (roots[1] - 1.0).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,637 @@
+use crate::complex::Complex;
+use crate::event::{Event, Observer, PROGRESS_EVERY};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+const EPSILON: f64 = 1e-12;
+const MAX_ITERATIONS: usize = 100;
+const GRID_SAMPLES: usize = 1000;
+const GRAEFFE_ITERATIONS: u32 = 6;
+const DURAND_KERNER_ITERATIONS: usize = 500;
+
+/// What the iterative solvers share with their caller: the steps taken so
+/// far, and a flag another thread may raise to stop them. Stopped solvers
+/// return what they have, a bracket midpoint or the current estimates.
+#[derive(Default)]
+pub struct Work<'a> {
+    pub iterations: usize,
+    pub cancel: Option<&'a AtomicBool>,
+    /// Told of the progress every `PROGRESS_EVERY` steps.
+    pub observer: Option<Observer<'a>>,
+}
+
+impl Work<'_> {
+    /// Counts one step, false once cancelled.
+    fn step(&mut self) -> bool {
+        self.iterations += 1;
+        if let (Some(observer), 0) = (self.observer, self.iterations % PROGRESS_EVERY) {
+            observer(&Event::Iteration {
+                iterations: self.iterations,
+            });
+        }
+        !self.cancelled()
+    }
+
+    pub fn cancelled(&self) -> bool {
+        self.cancel
+            .is_some_and(|cancel| cancel.load(Ordering::Relaxed))
+    }
+}
+
+/// Evaluates the polynomial with Horner's scheme. Coefficients are stored
+/// by ascending degree, like in `Poly`. `const`, for tables computed at
+/// build time.
+pub const fn eval(coefficients: &[f64], x: f64) -> f64 {
+    let mut value = 0.0;
+    let mut degree = coefficients.len();
+    while degree > 0 {
+        degree -= 1;
+        value = value * x + coefficients[degree];
+    }
+    value
+}
+
+pub fn derivative(coefficients: &[f64]) -> Vec<f64> {
+    coefficients
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(degree, c)| c * degree as f64)
+        .collect()
+}
+
+/// Every root lies strictly inside `[-bound, bound]`.
+pub fn cauchy_bound(coefficients: &[f64]) -> f64 {
+    let lead = coefficients[coefficients.len() - 1];
+    1.0 + coefficients[..coefficients.len() - 1]
+        .iter()
+        .map(|c| (c / lead).abs())
+        .fold(0.0, f64::max)
+}
+
+/// The largest coefficient in absolute value.
+pub fn height(coefficients: &[f64]) -> f64 {
+    coefficients.iter().map(|c| c.abs()).fold(0.0, f64::max)
+}
+
+/// The square root of the sum of the squared coefficients.
+pub fn l2_norm(coefficients: &[f64]) -> f64 {
+    coefficients.iter().map(|c| c * c).sum::<f64>().sqrt()
+}
+
+/// `|lead| * Π max(1, |z|)` over the complex roots, estimated from the
+/// Durand-Kerner roots. Landau's inequality keeps it below the L2 norm.
+pub fn mahler_measure(coefficients: &[f64]) -> f64 {
+    let lead = coefficients[coefficients.len() - 1].abs();
+    if coefficients.len() < 2 {
+        return lead;
+    }
+    durand_kerner(coefficients)
+        .iter()
+        .fold(lead, |measure, root| measure * root.norm().max(1.0))
+}
+
+pub fn newton(coefficients: &[f64], x0: f64) -> Option<f64> {
+    newton_counting(coefficients, x0, &mut Work::default())
+}
+
+/// Adds the steps taken to `work`, like the other `_counting` variants.
+fn newton_counting(coefficients: &[f64], x0: f64, work: &mut Work) -> Option<f64> {
+    let derivative = derivative(coefficients);
+    let mut x = x0;
+    for _ in 0..MAX_ITERATIONS {
+        if !work.step() {
+            return None;
+        }
+        let slope = eval(&derivative, x);
+        if slope == 0.0 {
+            return None;
+        }
+        let next = x - eval(coefficients, x) / slope;
+        if !next.is_finite() {
+            return None;
+        }
+        if (next - x).abs() <= EPSILON * next.abs().max(1.0) {
+            return Some(next);
+        }
+        x = next;
+    }
+    None
+}
+
+/// The successive Newton iterates from `x0`, stopping once they converge or
+/// after `limit` steps.
+pub fn newton_iterates(coefficients: &[f64], x0: f64, limit: usize) -> Vec<f64> {
+    let derivative = derivative(coefficients);
+    let mut iterates = vec![x0];
+    let mut x = x0;
+    for _ in 0..limit {
+        let slope = eval(&derivative, x);
+        let next = x - eval(coefficients, x) / slope;
+        if slope == 0.0 || !next.is_finite() {
+            break;
+        }
+        iterates.push(next);
+        if (next - x).abs() <= EPSILON * next.abs().max(1.0) {
+            break;
+        }
+        x = next;
+    }
+    iterates
+}
+
+/// Brent's method on `[a, b]`. The polynomial must change sign on the
+/// interval, otherwise there is nothing to bracket and `None` is returned.
+pub fn brent(coefficients: &[f64], a: f64, b: f64) -> Option<f64> {
+    brent_counting(coefficients, a, b, &mut Work::default())
+}
+
+fn brent_counting(coefficients: &[f64], a: f64, b: f64, work: &mut Work) -> Option<f64> {
+    let (mut a, mut b) = (a, b);
+    let mut fa = eval(coefficients, a);
+    let mut fb = eval(coefficients, b);
+    if fa * fb > 0.0 {
+        return None;
+    }
+    let (mut c, mut fc) = (a, fa);
+    let mut d = b - a;
+    let mut e = d;
+    for _ in 0..MAX_ITERATIONS {
+        if !work.step() {
+            break;
+        }
+        if fb * fc > 0.0 {
+            c = a;
+            fc = fa;
+            d = b - a;
+            e = d;
+        }
+        if fc.abs() < fb.abs() {
+            a = b;
+            b = c;
+            c = a;
+            fa = fb;
+            fb = fc;
+            fc = fa;
+        }
+        let tolerance = 2.0 * f64::EPSILON * b.abs() + 0.5 * EPSILON;
+        let middle = 0.5 * (c - b);
+        if middle.abs() <= tolerance || fb == 0.0 {
+            return Some(b);
+        }
+        if e.abs() >= tolerance && fa.abs() > fb.abs() {
+            // Inverse quadratic interpolation, or secant when only two points
+            let s = fb / fa;
+            let (mut p, mut q) = if a == c {
+                (2.0 * middle * s, 1.0 - s)
+            } else {
+                let q = fa / fc;
+                let r = fb / fc;
+                (
+                    s * (2.0 * middle * q * (q - r) - (b - a) * (r - 1.0)),
+                    (q - 1.0) * (r - 1.0) * (s - 1.0),
+                )
+            };
+            if p > 0.0 {
+                q = -q;
+            } else {
+                p = -p;
+            }
+            if 2.0 * p < (3.0 * middle * q - (tolerance * q).abs()).min((e * q).abs()) {
+                e = d;
+                d = p / q;
+            } else {
+                d = middle;
+                e = middle;
+            }
+        } else {
+            d = middle;
+            e = middle;
+        }
+        a = b;
+        fa = fb;
+        b += if d.abs() > tolerance {
+            d
+        } else {
+            tolerance.copysign(middle)
+        };
+        fb = eval(coefficients, b);
+    }
+    Some(b)
+}
+
+/// Samples a regular grid inside the Cauchy bound and returns every
+/// interval where the polynomial changes sign. Exact zeros on the grid are
+/// returned as degenerate `(x, x)` intervals.
+pub fn sign_change_brackets(coefficients: &[f64]) -> Vec<(f64, f64)> {
+    let bound = cauchy_bound(coefficients);
+    let step = 2.0 * bound / GRID_SAMPLES as f64;
+    let mut brackets = vec![];
+    let mut a = -bound;
+    let mut fa = eval(coefficients, a);
+    for i in 1..=GRID_SAMPLES {
+        let b = -bound + step * i as f64;
+        let fb = eval(coefficients, b);
+        if fa == 0.0 {
+            brackets.push((a, a));
+        } else if fa * fb < 0.0 {
+            brackets.push((a, b));
+        }
+        a = b;
+        fa = fb;
+    }
+    brackets
+}
+
+/// Real roots, found where the square-free part crosses zero so that a
+/// root of even multiplicity is found too. Newton is tried first from the
+/// middle of each bracket; when it diverges or escapes the bracket,
+/// Brent's method takes over, which always converges on a sign change.
+pub fn real_roots(coefficients: &[f64]) -> Vec<f64> {
+    real_roots_counting(coefficients, &mut Work::default())
+}
+
+pub fn real_roots_counting(coefficients: &[f64], work: &mut Work) -> Vec<f64> {
+    let simple = square_free_part(coefficients);
+    refine_roots_counting(&simple, &sign_change_brackets(&simple), work)
+}
+
+/// The polynomial divided by its gcd with its derivative: the same roots,
+/// each of them simple.
+pub fn square_free_part(coefficients: &[f64]) -> Vec<f64> {
+    let common = gcd(coefficients, &derivative(coefficients));
+    if common.len() <= 1 {
+        return coefficients.to_vec();
+    }
+    quotient(coefficients, &common)
+}
+
+/// Polishes each bracket into a root with Newton's method, falling back to
+/// Brent's method when Newton leaves the bracket. Once cancelled, the
+/// brackets left are not refined.
+fn refine_roots_counting(
+    coefficients: &[f64],
+    brackets: &[(f64, f64)],
+    work: &mut Work,
+) -> Vec<f64> {
+    let mut roots = vec![];
+    for &(a, b) in brackets {
+        if work.cancelled() {
+            break;
+        }
+        if a == b {
+            roots.push(a);
+            continue;
+        }
+        match newton_counting(coefficients, (a + b) / 2.0, work) {
+            Some(root) if a <= root && root <= b => roots.push(root),
+            _ => roots.extend(brent_counting(coefficients, a, b, work)),
+        }
+    }
+    roots
+}
+
+/// Remainder of the polynomial division of `dividend` by `divisor`, with
+/// the leading coefficients lost to rounding trimmed away.
+pub fn remainder(dividend: &[f64], divisor: &[f64]) -> Vec<f64> {
+    let mut remainder = dividend.to_vec();
+    let lead = divisor[divisor.len() - 1];
+    while remainder.len() >= divisor.len() {
+        let factor = remainder[remainder.len() - 1] / lead;
+        let shift = remainder.len() - divisor.len();
+        for (i, c) in divisor.iter().enumerate() {
+            remainder[shift + i] -= factor * c;
+        }
+        remainder.pop();
+    }
+    let scale = dividend.iter().fold(0.0, |max: f64, c| max.max(c.abs()));
+    while remainder.last().is_some_and(|c| c.abs() <= 1e-9 * scale) {
+        remainder.pop();
+    }
+    remainder
+}
+
+/// Quotient of the polynomial division of `dividend` by `divisor`, the
+/// remainder being dropped.
+pub fn quotient(dividend: &[f64], divisor: &[f64]) -> Vec<f64> {
+    let mut remainder = dividend.to_vec();
+    let lead = divisor[divisor.len() - 1];
+    let mut quotient = vec![0.0; (dividend.len() + 1).saturating_sub(divisor.len())];
+    while remainder.len() >= divisor.len() {
+        let factor = remainder[remainder.len() - 1] / lead;
+        let shift = remainder.len() - divisor.len();
+        for (i, c) in divisor.iter().enumerate() {
+            remainder[shift + i] -= factor * c;
+        }
+        quotient[shift] = factor;
+        remainder.pop();
+    }
+    quotient
+}
+
+pub fn product(a: &[f64], b: &[f64]) -> Vec<f64> {
+    let mut product = vec![0.0; (a.len() + b.len()).saturating_sub(1)];
+    for (i, x) in a.iter().enumerate() {
+        for (j, y) in b.iter().enumerate() {
+            product[i + j] += x * y;
+        }
+    }
+    product
+}
+
+/// The coefficients of `P(X + by)`, by repeated synthetic division: each
+/// pass divides by `X - by` and keeps the remainder as the next Taylor
+/// coefficient. Exact for integers while they fit in the mantissa.
+pub fn taylor_shift(coefficients: &[f64], by: f64) -> Vec<f64> {
+    let mut shifted = coefficients.to_vec();
+    let degree = shifted.len().saturating_sub(1);
+    for i in 0..degree {
+        for j in (i..degree).rev() {
+            shifted[j] += by * shifted[j + 1];
+        }
+    }
+    shifted
+}
+
+/// The monic greatest common divisor, empty when both are zero.
+pub fn gcd(a: &[f64], b: &[f64]) -> Vec<f64> {
+    let monic = |p: &[f64]| -> Vec<f64> {
+        let mut p = p.to_vec();
+        while p.last() == Some(&0.0) {
+            p.pop();
+        }
+        let lead = p.last().copied().unwrap_or(1.0);
+        p.iter().map(|c| c / lead).collect()
+    };
+    let (mut a, mut b) = (monic(a), monic(b));
+    while !b.is_empty() {
+        let r = monic(&remainder(&a, &b));
+        (a, b) = (b, r);
+    }
+    a
+}
+
+pub fn sturm_sequence(coefficients: &[f64]) -> Vec<Vec<f64>> {
+    let mut sequence = vec![coefficients.to_vec(), derivative(coefficients)];
+    loop {
+        let previous = &sequence[sequence.len() - 2];
+        let last = &sequence[sequence.len() - 1];
+        if last.is_empty() {
+            sequence.pop();
+            return sequence;
+        }
+        let next: Vec<f64> = remainder(previous, last).iter().map(|c| -c).collect();
+        if next.is_empty() {
+            return sequence;
+        }
+        sequence.push(next);
+    }
+}
+
+fn sign_variations(sequence: &[Vec<f64>], x: f64) -> usize {
+    let signs: Vec<f64> = sequence
+        .iter()
+        .map(|p| eval(p, x))
+        .filter(|&value| value != 0.0)
+        .collect();
+    signs.windows(2).filter(|w| w[0] * w[1] < 0.0).count()
+}
+
+/// Number of distinct real roots in `[a, b]` from Sturm's theorem, without
+/// computing any of them.
+pub fn count_roots(coefficients: &[f64], a: f64, b: f64) -> usize {
+    let sequence = sturm_sequence(coefficients);
+    let count = sign_variations(&sequence, a).saturating_sub(sign_variations(&sequence, b));
+    if eval(coefficients, a) == 0.0 {
+        count + 1
+    } else {
+        count
+    }
+}
+
+pub fn eval_complex(coefficients: &[f64], z: Complex) -> Complex {
+    coefficients
+        .iter()
+        .rev()
+        .fold(Complex::from(0.0), |acc, &c| acc * z + Complex::from(c))
+}
+
+/// Estimates the root magnitudes, largest first, with Graeffe's
+/// root-squaring: each iteration produces the polynomial whose roots are the
+/// squares of the previous ones, so the magnitudes separate quickly and can
+/// be read off consecutive coefficient ratios.
+pub fn graeffe_magnitudes(coefficients: &[f64]) -> Vec<f64> {
+    let degree = coefficients.len() - 1;
+    let mut current = coefficients.to_vec();
+    for _ in 0..GRAEFFE_ITERATIONS {
+        let mut squared = vec![0.0; degree + 1];
+        for (i, a) in current.iter().enumerate() {
+            for (j, b) in current.iter().enumerate() {
+                if (i + j) % 2 == 0 {
+                    let sign = if j % 2 == 0 { 1.0 } else { -1.0 };
+                    squared[(i + j) / 2] += sign * a * b;
+                }
+            }
+        }
+        // Rescale to keep the coefficients away from overflow
+        let scale = squared.iter().fold(0.0, |max: f64, c| max.max(c.abs()));
+        current = squared.iter().map(|c| c / scale).collect();
+    }
+    let power = 1.0 / 2_f64.powi(GRAEFFE_ITERATIONS as i32);
+    (1..=degree)
+        .map(|k| {
+            let ratio = (current[degree - k] / current[degree - k + 1]).abs();
+            if ratio.is_finite() {
+                ratio.powf(power)
+            } else {
+                0.0
+            }
+        })
+        .collect()
+}
+
+/// Finds every complex root simultaneously. The starting points are spread
+/// around circles whose radii come from the Graeffe magnitude estimates.
+pub fn durand_kerner(coefficients: &[f64]) -> Vec<Complex> {
+    durand_kerner_counting(coefficients, &mut Work::default())
+}
+
+pub fn durand_kerner_counting(coefficients: &[f64], work: &mut Work) -> Vec<Complex> {
+    let degree = coefficients.len() - 1;
+    let lead = coefficients[degree];
+    let monic: Vec<f64> = coefficients.iter().map(|c| c / lead).collect();
+    let mut roots: Vec<Complex> = graeffe_magnitudes(&monic)
+        .iter()
+        .enumerate()
+        .map(|(k, &magnitude)| {
+            let radius = if magnitude > 0.0 { magnitude } else { 1.0 };
+            let angle = 2.0 * std::f64::consts::PI * k as f64 / degree as f64 + 0.4;
+            Complex::from_polar(radius, angle)
+        })
+        .collect();
+    for _ in 0..DURAND_KERNER_ITERATIONS {
+        if !work.step() {
+            break;
+        }
+        let mut change: f64 = 0.0;
+        for i in 0..degree {
+            let mut denominator = Complex::from(1.0);
+            for j in 0..degree {
+                if i != j {
+                    denominator = denominator * (roots[i] - roots[j]);
+                }
+            }
+            let delta = eval_complex(&monic, roots[i]) / denominator;
+            if delta.re.is_finite() && delta.im.is_finite() {
+                roots[i] = roots[i] - delta;
+                change = change.max(delta.norm());
+            }
+        }
+        if change <= EPSILON {
+            break;
+        }
+    }
+    roots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evaluation_at_build_time() {
+        const CURVE: [f64; 3] = [0.5, -2.0, 0.25];
+        const TABLE: [f64; 3] = [eval(&CURVE, 0.0), eval(&CURVE, 1.0), eval(&CURVE, 2.0)];
+        assert_eq!(TABLE, [0.5, -1.25, -2.5]);
+        assert_eq!(eval(&[], 3.0), 0.0);
+    }
+
+    #[test]
+    fn newton_iterates_converge() {
+        // X^2 - 2 from 2 decreases monotonically towards sqrt(2)
+        let iterates = newton_iterates(&[-2.0, 0.0, 1.0], 2.0, 20);
+        assert_eq!(iterates[..2], [2.0, 1.5]);
+        assert!(iterates.windows(2).all(|w| w[1] <= w[0]));
+        assert!((iterates.last().unwrap() - 2f64.sqrt()).abs() < 1e-12);
+        assert_eq!(newton_iterates(&[-2.0, 0.0, 1.0], 2.0, 3).len(), 4);
+    }
+
+    #[test]
+    fn brent_square_root_of_two() {
+        let root = brent(&[-2.0, 0.0, 1.0], 0.0, 2.0).unwrap();
+        assert!((root - 2.0_f64.sqrt()).abs() < 1e-10);
+        assert_eq!(brent(&[-2.0, 0.0, 1.0], 2.0, 3.0), None);
+        let mut work = Work::default();
+        real_roots_counting(&[-2.0, 0.0, 1.0], &mut work);
+        assert!(work.iterations > 0 && work.iterations <= 2 * MAX_ITERATIONS);
+    }
+
+    #[test]
+    fn real_roots_of_cubic() {
+        // (X + 3)(X - 1)(X - 2)
+        let roots = real_roots(&[6.0, -7.0, 0.0, 1.0]);
+        assert_eq!(roots.len(), 3);
+        for (root, expected) in roots.iter().zip([-3.0, 1.0, 2.0]) {
+            assert!((root - expected).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn roots_of_even_multiplicity() {
+        // (X - 1)^2 (X - 2), the sign only changes at 2
+        assert_eq!(square_free_part(&[-2.0, 5.0, -4.0, 1.0]), [2.0, -3.0, 1.0]);
+        let roots = real_roots(&[-2.0, 5.0, -4.0, 1.0]);
+        assert_eq!(roots.len(), 2);
+        assert!((roots[0] - 1.0).abs() < 1e-9 && (roots[1] - 2.0).abs() < 1e-9);
+        // (X - 1)^2 (X^2 + 1), never below zero
+        let roots = real_roots(&[1.0, -2.0, 2.0, -2.0, 1.0]);
+        assert_eq!(roots.len(), 1);
+        assert!((roots[0] - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn graeffe_estimates_magnitudes() {
+        // (X - 4)(X + 2)(X - 0.5)
+        let magnitudes = graeffe_magnitudes(&[4.0, -7.0, -2.5, 1.0]);
+        for (magnitude, expected) in magnitudes.iter().zip([4.0, 2.0, 0.5]) {
+            assert!((magnitude - expected).abs() < 0.05);
+        }
+    }
+
+    #[test]
+    fn norms_and_mahler_measure() {
+        // 2(X - 3)(X^2 + 0.25)
+        let coefficients = [-1.5, 0.5, -6.0, 2.0];
+        assert_eq!(height(&coefficients), 6.0);
+        assert_eq!(l2_norm(&[3.0, 0.0, -4.0]), 5.0);
+        let measure = mahler_measure(&coefficients);
+        assert!((measure - 6.0).abs() < 1e-9);
+        assert!(measure <= l2_norm(&coefficients));
+        assert_eq!(mahler_measure(&[-2.0]), 2.0);
+    }
+
+    #[test]
+    fn durand_kerner_complex_roots() {
+        // (X - 1)(X^2 + 4)
+        let mut roots = durand_kerner(&[-4.0, 4.0, -1.0, 1.0]);
+        roots.sort_by(|a, b| a.im.partial_cmp(&b.im).unwrap());
+        for (root, expected) in roots.iter().zip([(0.0, -2.0), (1.0, 0.0), (0.0, 2.0)]) {
+            assert!((*root - Complex::new(expected.0, expected.1)).norm() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn taylor_shift_substitutes() {
+        // (X + 2)^3 = X^3 + 6 * X^2 + 12 * X + 8
+        assert_eq!(
+            taylor_shift(&[0.0, 0.0, 0.0, 1.0], 2.0),
+            [8.0, 12.0, 6.0, 1.0]
+        );
+        let coefficients = [5.0, 4.0, -9.3, 0.5];
+        let shifted = taylor_shift(&coefficients, -1.5);
+        for x in [-2.0, 0.0, 3.0] {
+            assert!((eval(&shifted, x) - eval(&coefficients, x - 1.5)).abs() < 1e-9);
+        }
+        assert_eq!(taylor_shift(&[7.0], 3.0), [7.0]);
+        assert!(taylor_shift(&[], 3.0).is_empty());
+    }
+
+    #[test]
+    fn gcd_keeps_common_factors() {
+        // (X - 1)(X + 2) and (X - 1)(X - 3)
+        let divisor = gcd(&[-2.0, 1.0, 1.0], &[3.0, -4.0, 1.0]);
+        assert_eq!(divisor.len(), 2);
+        assert!((divisor[0] + 1.0).abs() < 1e-12 && divisor[1] == 1.0);
+        assert_eq!(gcd(&[-2.0, 1.0, 1.0], &[1.0, 0.0, 1.0]), [1.0]);
+        assert_eq!(gcd(&[], &[4.0, 2.0]), [2.0, 1.0]);
+        assert!(gcd(&[0.0], &[]).is_empty());
+    }
+
+    #[test]
+    fn quotient_undoes_product() {
+        // (X - 1)(X + 2) = X^2 + X - 2
+        let product = product(&[-1.0, 1.0], &[2.0, 1.0]);
+        assert_eq!(product, [-2.0, 1.0, 1.0]);
+        assert_eq!(quotient(&product, &[2.0, 1.0]), [-1.0, 1.0]);
+        assert_eq!(quotient(&[3.0, 1.0], &[1.0, 0.0, 1.0]), [] as [f64; 0]);
+    }
+
+    #[test]
+    fn sturm_counts_roots_in_interval() {
+        // (X + 3)(X - 1)(X - 2)
+        let coefficients = [6.0, -7.0, 0.0, 1.0];
+        assert_eq!(count_roots(&coefficients, -10.0, 10.0), 3);
+        assert_eq!(count_roots(&coefficients, 0.0, 1.5), 1);
+        assert_eq!(count_roots(&coefficients, 1.0, 2.0), 2);
+        assert_eq!(count_roots(&[1.0, 0.0, 1.0], -10.0, 10.0), 0);
+    }
+
+    #[test]
+    fn brent_rescues_diverging_newton() {
+        // Newton cycles between 0 and 1 on X^3 - 2X + 2
+        let coefficients = [2.0, -2.0, 0.0, 1.0];
+        assert_eq!(newton(&coefficients, 0.0), None);
+        let roots = real_roots(&coefficients);
+        assert_eq!(roots.len(), 1);
+        assert!(eval(&coefficients, roots[0]).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,274 @@
+use crate::float;
+use crate::report::{Report, CSV_HEADER};
+
+/// Turns the report of one equation into the text written to stdout. The
+/// CLI picks one by the name given to `--output`; other formats only need
+/// an implementation registered in `Renderers`.
+pub trait OutputRenderer: Send + Sync {
+    fn name(&self) -> &str;
+    fn render(&self, report: &Report) -> String;
+
+    /// The record of one line of a batch, `render` unless the format has a
+    /// more compact one.
+    fn render_line(&self, _line: &str, report: &Report) -> String {
+        self.render(report)
+    }
+
+    /// Written once before the records of a batch.
+    fn header(&self) -> Option<&str> {
+        None
+    }
+
+    /// The last line of the output of an interrupted batch, so that a
+    /// partial file is not taken for a complete one.
+    fn truncation_marker(&self) -> Option<&str> {
+        None
+    }
+}
+
+/// The reduced form, the degree and the solutions in a few words.
+pub struct Text;
+
+impl OutputRenderer for Text {
+    fn name(&self) -> &str {
+        "text"
+    }
+
+    fn render(&self, report: &Report) -> String {
+        format!(
+            "Reduced form: {}\nPolynomial degree: {}\nSolutions: {}\n",
+            report.reduced_form,
+            report.degree,
+            report.summary()
+        )
+    }
+
+    fn render_line(&self, line: &str, report: &Report) -> String {
+        format!("{}: {}", line.trim(), report.summary())
+    }
+}
+
+pub struct Json;
+
+impl OutputRenderer for Json {
+    fn name(&self) -> &str {
+        "json"
+    }
+
+    fn render(&self, report: &Report) -> String {
+        report.to_json()
+    }
+
+    fn render_line(&self, _line: &str, report: &Report) -> String {
+        report.to_json_line()
+    }
+
+    fn truncation_marker(&self) -> Option<&str> {
+        Some(r#"{"truncated":true}"#)
+    }
+}
+
+pub struct Csv;
+
+impl OutputRenderer for Csv {
+    fn name(&self) -> &str {
+        "csv"
+    }
+
+    fn render(&self, report: &Report) -> String {
+        report.to_csv()
+    }
+
+    fn render_line(&self, _line: &str, report: &Report) -> String {
+        report.csv_row()
+    }
+
+    fn header(&self) -> Option<&str> {
+        Some(CSV_HEADER)
+    }
+
+    fn truncation_marker(&self) -> Option<&str> {
+        Some("# truncated")
+    }
+}
+
+/// An `align*` block with the reduced equation and one line per solution.
+pub struct Latex;
+
+/// Highest degree first, `-9.3x^{2} + 4x + 4`.
+fn latex_polynomial(coefficients: &[f32]) -> String {
+    let mut text = String::new();
+    for (degree, &c) in coefficients.iter().enumerate().rev() {
+        if c == 0.0 {
+            continue;
+        }
+        let sign = match (text.is_empty(), c < 0.0) {
+            (true, true) => "-",
+            (true, false) => "",
+            (false, true) => " - ",
+            (false, false) => " + ",
+        };
+        let magnitude = match (c.abs(), degree) {
+            (1.0, 1..) => String::new(),
+            (c, _) => float::shortest(c),
+        };
+        let power = match degree {
+            0 => String::new(),
+            1 => String::from("x"),
+            _ => format!("x^{{{}}}", degree),
+        };
+        text.push_str(&format!("{}{}{}", sign, magnitude, power));
+    }
+    if text.is_empty() {
+        text.push('0');
+    }
+    text
+}
+
+impl OutputRenderer for Latex {
+    fn name(&self) -> &str {
+        "latex"
+    }
+
+    fn render(&self, report: &Report) -> String {
+        let mut lines = vec![format!("{} &= 0", latex_polynomial(&report.coefficients))];
+        let solutions = &report.solutions;
+        match solutions.len() {
+            _ if report.every_real_is_solution => lines.push(String::from("x &\\in \\mathbb{R}")),
+            0 if report.complex_roots.is_empty() => {
+                lines.push(String::from("&\\text{no real solution}"))
+            }
+            1 => lines.push(format!("x &= {}", float::shortest(solutions[0]))),
+            _ => lines.extend(
+                solutions
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &s)| format!("x_{{{}}} &= {}", i + 1, float::shortest(s))),
+            ),
+        }
+        for pair in &report.complex_roots {
+            lines.push(format!("x &= {}", pair.latex));
+        }
+        format!(
+            "\\begin{{align*}}\n{}\n\\end{{align*}}\n",
+            lines.join(" \\\\\n")
+        )
+    }
+
+    fn truncation_marker(&self) -> Option<&str> {
+        Some("% truncated")
+    }
+}
+
+/// The renderers the CLI can select by name, the built-in ones first.
+pub struct Renderers {
+    renderers: Vec<Box<dyn OutputRenderer>>,
+}
+
+impl Default for Renderers {
+    fn default() -> Renderers {
+        let mut renderers = Renderers { renderers: vec![] };
+        renderers.register(Box::new(Text));
+        renderers.register(Box::new(Json));
+        renderers.register(Box::new(Csv));
+        renderers.register(Box::new(Latex));
+        renderers
+    }
+}
+
+impl Renderers {
+    /// Adds a renderer, replacing the one of the same name.
+    pub fn register(&mut self, renderer: Box<dyn OutputRenderer>) {
+        self.renderers
+            .retain(|registered| registered.name() != renderer.name());
+        self.renderers.push(renderer);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn OutputRenderer> {
+        self.renderers
+            .iter()
+            .find(|renderer| renderer.name() == name)
+            .map(|renderer| renderer.as_ref())
+    }
+
+    /// In the order they were registered.
+    pub fn names(&self) -> Vec<&str> {
+        self.renderers
+            .iter()
+            .map(|renderer| renderer.name())
+            .collect()
+    }
+
+    /// The report rendered by the renderer named `name`, if there is one.
+    pub fn render(&self, name: &str, report: &Report) -> Option<String> {
+        self.get(name).map(|renderer| renderer.render(report))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Poly;
+
+    fn report(line: &str) -> Report {
+        Poly::new(line).unwrap().report(Default::default())
+    }
+
+    #[test]
+    fn built_in_renderers() {
+        let renderers = Renderers::default();
+        let report = report("X^2 = 4");
+        assert_eq!(
+            renderers.render("text", &report).unwrap(),
+            "Reduced form: -4 * X^0 + 1 * X^2 = 0\nPolynomial degree: 2\nSolutions: 2, -2\n"
+        );
+        assert_eq!(renderers.render("json", &report), Some(report.to_json()));
+        assert_eq!(renderers.render("csv", &report), Some(report.to_csv()));
+        assert_eq!(
+            renderers.render("latex", &report).unwrap(),
+            "\\begin{align*}\nx^{2} - 4 &= 0 \\\\\nx_{1} &= 2 \\\\\nx_{2} &= -2\n\\end{align*}\n"
+        );
+        assert_eq!(renderers.render("yaml", &report), None);
+        assert_eq!(renderers.names(), ["text", "json", "csv", "latex"]);
+        assert_eq!(Text.render_line(" X^2 = 4 ", &report), "X^2 = 4: 2, -2");
+        assert_eq!(renderers.get("csv").unwrap().header(), Some(CSV_HEADER));
+    }
+
+    #[test]
+    fn latex_forms() {
+        assert_eq!(latex_polynomial(&[4.0, 4.0, -9.3]), "-9.3x^{2} + 4x + 4");
+        assert_eq!(latex_polynomial(&[0.0, -1.0]), "-x");
+        assert_eq!(latex_polynomial(&[]), "0");
+        let every = Latex.render(&report("X = X"));
+        assert!(every.contains("0 &= 0 \\\\\nx &\\in \\mathbb{R}"));
+        assert!(Latex
+            .render(&report("5 = 0"))
+            .contains("\\text{no real solution}"));
+    }
+
+    struct Count;
+
+    impl OutputRenderer for Count {
+        fn name(&self) -> &str {
+            "count"
+        }
+
+        fn render(&self, report: &Report) -> String {
+            report.solutions.len().to_string()
+        }
+    }
+
+    #[test]
+    fn custom_renderer() {
+        let mut renderers = Renderers::default();
+        renderers.register(Box::new(Count));
+        assert_eq!(
+            renderers.render("count", &report("X^2 = 4")).as_deref(),
+            Some("2")
+        );
+        assert_eq!(renderers.names().last(), Some(&"count"));
+        let count = renderers.get("count").unwrap();
+        assert_eq!(count.render_line("X^2 = 4", &report("X^2 = 4")), "2");
+        assert_eq!(count.truncation_marker(), None);
+    }
+}
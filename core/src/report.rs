@@ -0,0 +1,210 @@
+use crate::float;
+use crate::warnings::Warning;
+
+use serde::Serialize;
+
+/// A real solution with how far from zero the polynomial is there.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Root {
+    pub value: f32,
+    pub residual: f32,
+    /// From a formula rather than approximated.
+    pub exact: bool,
+}
+
+/// A real solution left out of `solutions` by `--domain`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Excluded {
+    pub value: f32,
+    /// `below a` or `above b`.
+    pub reason: String,
+}
+
+/// A root off the real axis and its conjugate.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ComplexPair {
+    pub re: f32,
+    /// Positive, the conjugate having `-im`.
+    pub im: f32,
+    /// `a ± bi`.
+    pub text: String,
+    /// `a \pm bi`.
+    pub latex: String,
+}
+
+/// The result of solving an equation in a form scripts can consume.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct Report {
+    pub reduced_form: String,
+    pub degree: usize,
+    /// Ascending degree, like the reduced form of the subject.
+    pub coefficients: Vec<f32>,
+    /// `Poly::canonical_hash` in hexadecimal, shared by equivalent
+    /// equations.
+    pub fingerprint: String,
+    pub discriminant: Option<f32>,
+    /// `resultant::discriminant` from the decimals as written, `n` or
+    /// `n/d`, for degree 2 and above.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exact_discriminant: Option<String>,
+    pub solutions: Vec<f32>,
+    /// Set for `0 = 0`, in which case `solutions` is empty.
+    pub every_real_is_solution: bool,
+    /// False when the solutions were approximated numerically.
+    pub exact: bool,
+    /// Set when solving was interrupted, the roots being those found by then.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub cancelled: bool,
+    /// The roots off the real axis, only filled in by `solve_all`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub complex_roots: Vec<ComplexPair>,
+    /// Left out when there are none, as for the lines of a batch.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<Warning>,
+    /// The expansion of both sides, with `--steps`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub steps: Vec<String>,
+    /// `solutions` in increasing order with their residual, only filled in
+    /// by `solve_all`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub roots: Vec<Root>,
+    /// The real solutions outside `--domain`, only filled in by `solve_all`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub excluded: Vec<Excluded>,
+    /// The unit of `X` implied by the `[unit]` annotations, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+    /// `Solution::rounded`, for the text output only.
+    #[serde(skip)]
+    pub rounded: Vec<String>,
+}
+
+pub const CSV_HEADER: &str =
+    "reduced_form,degree,discriminant,solutions,every_real_is_solution,exact";
+
+impl Report {
+    /// A report without solutions, the other fields to be filled in.
+    pub fn new(reduced_form: String, degree: usize, coefficients: Vec<f32>) -> Report {
+        Report {
+            reduced_form,
+            degree,
+            coefficients,
+            fingerprint: String::new(),
+            discriminant: None,
+            exact_discriminant: None,
+            solutions: vec![],
+            every_real_is_solution: false,
+            exact: false,
+            cancelled: false,
+            complex_roots: vec![],
+            warnings: vec![],
+            steps: vec![],
+            roots: vec![],
+            excluded: vec![],
+            unit: None,
+            rounded: vec![],
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default() + "\n"
+    }
+
+    /// The JSON on a single line, for one record per input line.
+    pub fn to_json_line(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    /// A header and a single row.
+    pub fn to_csv(&self) -> String {
+        format!("{}\n{}\n", CSV_HEADER, self.csv_row())
+    }
+
+    /// The row under `CSV_HEADER`, the solutions separated by `;`.
+    pub fn csv_row(&self) -> String {
+        let solutions: Vec<String> = self.solutions.iter().map(|&s| float::shortest(s)).collect();
+        let discriminant = self.discriminant.map(float::shortest);
+        format!(
+            "\"{}\",{},{},{},{},{}",
+            self.reduced_form.replace('"', "\"\""),
+            self.degree,
+            discriminant.unwrap_or_default(),
+            solutions.join(";"),
+            self.every_real_is_solution,
+            self.exact
+        )
+    }
+
+    /// The solutions in a few words, for one line per equation.
+    pub fn summary(&self) -> String {
+        let solutions: Vec<String> = self.solutions.iter().map(|&s| float::shortest(s)).collect();
+        match (self.every_real_is_solution, self.degree) {
+            (true, _) => String::from("every real number is a solution"),
+            (false, 0) => String::from("no solution"),
+            _ if solutions.is_empty() => String::from("no real solution"),
+            _ if self.exact => solutions.join(", "),
+            _ => format!("{} (approximate)", solutions.join(", ")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> Report {
+        Report {
+            reduced_form: String::from("-4 * X^0 + 1 * X^2 = 0"),
+            degree: 2,
+            coefficients: vec![-4.0, 0.0, 1.0],
+            fingerprint: String::from("0123456789abcdef"),
+            discriminant: Some(16.0),
+            solutions: vec![2.0, -2.0],
+            every_real_is_solution: false,
+            exact: true,
+            exact_discriminant: None,
+            cancelled: false,
+            complex_roots: vec![],
+            warnings: vec![],
+            steps: vec![],
+            roots: vec![],
+            excluded: vec![],
+            unit: None,
+            rounded: vec![String::from("2.0")],
+        }
+    }
+
+    #[test]
+    fn report_as_json() {
+        let json: serde_json::Value = serde_json::from_str(&report().to_json()).unwrap();
+        assert_eq!(json["degree"], 2);
+        assert_eq!(json["solutions"], serde_json::json!([2.0, -2.0]));
+        assert_eq!(json["discriminant"], 16.0);
+        assert_eq!(json["fingerprint"], "0123456789abcdef");
+        assert!(json.get("rounded").is_none());
+    }
+
+    #[test]
+    fn report_summary() {
+        assert_eq!(report().summary(), "2, -2");
+        let every = Report {
+            every_real_is_solution: true,
+            solutions: vec![],
+            ..report()
+        };
+        assert_eq!(every.summary(), "every real number is a solution");
+        assert!(!report().to_json_line().contains('\n'));
+    }
+
+    #[test]
+    fn report_as_csv() {
+        assert_eq!(
+            report().to_csv(),
+            format!(
+                "{}\n\"-4 * X^0 + 1 * X^2 = 0\",2,16,2;-2,false,true\n",
+                CSV_HEADER
+            )
+        );
+    }
+}
@@ -0,0 +1,164 @@
+use crate::decimal;
+use crate::factor;
+use crate::numeric;
+
+/// Bareiss' fraction-free elimination, where every division is exact so
+/// that the entries stay integers. None on overflow.
+fn determinant(mut matrix: Vec<Vec<i128>>) -> Option<i128> {
+    let n = matrix.len();
+    if n == 0 {
+        return Some(1);
+    }
+    let (mut sign, mut previous) = (1, 1);
+    for k in 0..n - 1 {
+        if matrix[k][k] == 0 {
+            let Some(pivot) = (k + 1..n).find(|&i| matrix[i][k] != 0) else {
+                return Some(0);
+            };
+            matrix.swap(k, pivot);
+            sign = -sign;
+        }
+        for i in k + 1..n {
+            for j in k + 1..n {
+                let product = matrix[i][j].checked_mul(matrix[k][k])?;
+                let cross = matrix[i][k].checked_mul(matrix[k][j])?;
+                matrix[i][j] = product.checked_sub(cross)? / previous;
+            }
+        }
+        previous = matrix[k][k];
+    }
+    matrix[n - 1][n - 1].checked_mul(sign)
+}
+
+/// The determinant of the Sylvester matrix, zero exactly when the two
+/// polynomials share a root. Coefficients are by ascending degree and the
+/// last one must not be zero.
+pub fn resultant(p: &[i128], q: &[i128]) -> Option<i128> {
+    let (m, n) = (p.len() - 1, q.len() - 1);
+    let mut matrix = vec![vec![0; m + n]; m + n];
+    for row in 0..n {
+        for (i, &c) in p.iter().rev().enumerate() {
+            matrix[row][row + i] = c;
+        }
+    }
+    for row in 0..m {
+        for (i, &c) in q.iter().rev().enumerate() {
+            matrix[n + row][row + i] = c;
+        }
+    }
+    determinant(matrix)
+}
+
+/// `(-1)^(n(n-1)/2) Res(P, P') / a_n` as a reduced fraction with a positive
+/// denominator, from the decimal coefficients as written. None below
+/// degree 2 or on overflow.
+pub fn discriminant(coefficients: &[f32]) -> Option<(i128, i128)> {
+    let n = coefficients.len().checked_sub(1).filter(|&n| n >= 2)?;
+    let (p, scale) = decimal::scaled_integers(coefficients)?;
+    let numerator = integer_discriminant(&p)?;
+    // the coefficients were multiplied by the scale, the discriminant by
+    // its power 2n - 2
+    let denominator = scale.checked_pow(2 * n as u32 - 2)?;
+    let divisor = crate::gcd(numerator, denominator);
+    Some((numerator / divisor, denominator / divisor))
+}
+
+/// The discriminant of integer coefficients, of degree 2 or more.
+fn integer_discriminant(p: &[i128]) -> Option<i128> {
+    let n = p.len() - 1;
+    let derivative: Vec<i128> = p
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(i, &c)| c.checked_mul(i as i128))
+        .collect::<Option<_>>()?;
+    let sign = if (n * (n - 1) / 2).is_multiple_of(2) {
+        1
+    } else {
+        -1
+    };
+    Some(resultant(p, &derivative)? / p[n] * sign)
+}
+
+fn product(p: &[i128], q: &[i128]) -> Option<Vec<i128>> {
+    let mut product = vec![0i128; p.len() + q.len() - 1];
+    for (i, &a) in p.iter().enumerate() {
+        for (j, &b) in q.iter().enumerate() {
+            product[i + j] = product[i + j].checked_add(a.checked_mul(b)?)?;
+        }
+    }
+    Some(product)
+}
+
+/// Mignotte's lower bound on the distance between two distinct roots, real
+/// or complex: `√(3|D|) n^(-(n+2)/2) ‖P‖₂^(1-n)` for `P` the square-free
+/// part, of degree `n` and discriminant `D`, with the integers of the
+/// decimals as written. None with fewer than two distinct roots, or when
+/// the integers overflow.
+pub fn separation_bound(coefficients: &[f32]) -> Option<f64> {
+    let (mut p, _) = decimal::scaled_integers(coefficients)?;
+    if p.len() < 3 {
+        return None;
+    }
+    let mut d = integer_discriminant(&p)?;
+    if d == 0 {
+        let factorization = factor::over_integers(coefficients).ok()?;
+        p = factorization
+            .factors
+            .iter()
+            .try_fold(vec![1], |p, (factor, _)| product(&p, factor))?;
+        if p.len() < 3 {
+            return None;
+        }
+        d = integer_discriminant(&p)?;
+    }
+    let n = (p.len() - 1) as f64;
+    let norm = numeric::l2_norm(&p.iter().map(|&c| c as f64).collect::<Vec<f64>>());
+    // in logarithms, the powers overflow quickly
+    let log =
+        (3.0 * (d as f64).abs()).ln() / 2.0 - (n + 2.0) / 2.0 * n.ln() - (n - 1.0) * norm.ln();
+    Some(log.exp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn discriminant_of_quadratic_and_cubic() {
+        assert_eq!(discriminant(&[-2.0, 1.0, 1.0]), Some((9, 1)));
+        // -9.3 * X^2 + 4 * X + 4: 16 + 148.8
+        assert_eq!(discriminant(&[4.0, 4.0, -9.3]), Some((824, 5)));
+        // X^3 - 3 * X + 2 = (X - 1)^2 (X + 2)
+        assert_eq!(discriminant(&[2.0, -3.0, 0.0, 1.0]), Some((0, 1)));
+        // X^3 - 7 * X + 6 = (X - 1)(X - 2)(X + 3): (1 * 4 * 5)^2
+        assert_eq!(discriminant(&[6.0, -7.0, 0.0, 1.0]), Some((400, 1)));
+        // X^3 + X + 1: -4 - 27
+        assert_eq!(discriminant(&[1.0, 1.0, 0.0, 1.0]), Some((-31, 1)));
+        assert_eq!(discriminant(&[1.0, 2.0]), None);
+    }
+
+    #[test]
+    fn separation_bound_is_below_the_closest_roots() {
+        // ±1, 2 apart
+        let bound = separation_bound(&[-1.0, 0.0, 1.0]).unwrap();
+        assert!((bound - 12f64.sqrt() / 4.0 / 2f64.sqrt()).abs() < 1e-12);
+        // (X - 1)(X - 1.1)(X + 3), 0.1 apart, scaled by 10 to integers
+        let bound = separation_bound(&[3.3, -5.2, 0.9, 1.0]).unwrap();
+        assert!(bound > 0.0 && bound < 0.1);
+        // (X - 1)^2 (X + 2) has the distinct roots of X^2 + X - 2
+        let bound = separation_bound(&[2.0, -3.0, 0.0, 1.0]).unwrap();
+        assert_eq!(bound, separation_bound(&[-2.0, 1.0, 1.0]).unwrap());
+        assert_eq!(separation_bound(&[1.0, -2.0, 1.0]), None);
+        assert_eq!(separation_bound(&[1.0, 2.0]), None);
+    }
+
+    #[test]
+    fn resultant_detects_common_roots() {
+        // (X - 1)(X + 2) and (X - 1)(X - 3)
+        assert_eq!(resultant(&[-2, 1, 1], &[3, -4, 1]), Some(0));
+        // X - 2 and X - 5
+        assert_eq!(resultant(&[-2, 1], &[-5, 1]), Some(-3));
+        assert_eq!(determinant(vec![vec![0, 1], vec![1, 0]]), Some(-1));
+    }
+}
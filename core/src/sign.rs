@@ -0,0 +1,271 @@
+use crate::float;
+use crate::method::{self, Method};
+use crate::numeric;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Sign {
+    Negative,
+    Zero,
+    Positive,
+}
+
+impl Sign {
+    fn of(value: f64) -> Sign {
+        if value > 0.0 {
+            Sign::Positive
+        } else if value < 0.0 {
+            Sign::Negative
+        } else {
+            Sign::Zero
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Sign::Negative => "negative",
+            Sign::Zero => "zero",
+            Sign::Positive => "positive",
+        }
+    }
+}
+
+/// The distinct real roots, in increasing order. Auto solves quadratics
+/// with the discriminant and finds the roots of even multiplicity, which do
+/// not change the sign, above.
+fn roots(coefficients: &[f64]) -> Vec<f64> {
+    let mut roots: Vec<f64> = method::solve(Method::Auto, coefficients)
+        .unwrap_or_default()
+        .into_iter()
+        // -b / a is -0 for b = 0
+        .map(|root| root + 0.0)
+        .collect();
+    roots.sort_by(f64::total_cmp);
+    roots.dedup();
+    roots
+}
+
+/// The sign on each open interval between consecutive real roots, from
+/// minus to plus infinity. A constant has a single interval.
+pub fn intervals(coefficients: &[f64]) -> Vec<(f64, f64, Sign)> {
+    let roots = roots(coefficients);
+    let mut bounds = vec![f64::NEG_INFINITY];
+    bounds.extend(&roots);
+    bounds.push(f64::INFINITY);
+    bounds
+        .windows(2)
+        .map(|pair| {
+            let (a, b) = (pair[0], pair[1]);
+            let inside = match (a.is_finite(), b.is_finite()) {
+                (true, true) => (a + b) / 2.0,
+                (true, false) => a + 1.0,
+                (false, true) => b - 1.0,
+                (false, false) => 0.0,
+            };
+            (a, b, Sign::of(numeric::eval(coefficients, inside)))
+        })
+        .collect()
+}
+
+fn bound(x: f64) -> String {
+    if x.is_finite() {
+        float::shortest(x as f32)
+    } else if x < 0.0 {
+        String::from("-inf")
+    } else {
+        String::from("inf")
+    }
+}
+
+/// Whether the equation ever holds: the sign of the reduced polynomial,
+/// with the intervals where it changes.
+pub fn describe(coefficients: &[f64]) -> String {
+    let intervals = intervals(coefficients);
+    let signs: Vec<Sign> = intervals.iter().map(|&(_, _, sign)| sign).collect();
+    let zeros: Vec<String> = intervals[1..]
+        .iter()
+        .map(|&(a, _, _)| float::shortest(a as f32))
+        .collect();
+    let mut description = match coefficients {
+        [c, b, a] => format!(
+            "Discriminant: {}\n",
+            float::shortest((b * b - 4.0 * a * c) as f32)
+        ),
+        _ => String::new(),
+    };
+    if signs.iter().all(|&sign| sign == signs[0]) {
+        description += &format!("The polynomial is {} for every X", signs[0].name());
+        if !zeros.is_empty() {
+            description += &format!(" but X = {}, where it is zero", zeros.join(", "));
+        }
+        description += "\n";
+        return description;
+    }
+    description += "The polynomial changes sign:\n";
+    for (a, b, sign) in intervals {
+        description += &format!("({}, {}): {}\n", bound(a), bound(b), sign.name());
+    }
+    description
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Critical {
+    Minimum,
+    Maximum,
+    /// The slope is zero without changing sign, as for `X^3` at 0.
+    Stationary,
+}
+
+/// The critical points, where the derivative is zero, with the value there,
+/// told apart by the sign of the derivative on either side.
+pub fn extrema(coefficients: &[f64]) -> Vec<(f64, f64, Critical)> {
+    let slopes = intervals(&numeric::derivative(coefficients));
+    slopes
+        .windows(2)
+        .map(|pair| {
+            let x = pair[0].1;
+            let kind = match (pair[0].2, pair[1].2) {
+                (Sign::Negative, Sign::Positive) => Critical::Minimum,
+                (Sign::Positive, Sign::Negative) => Critical::Maximum,
+                _ => Critical::Stationary,
+            };
+            (x, numeric::eval(coefficients, x), kind)
+        })
+        .collect()
+}
+
+pub fn describe_extrema(coefficients: &[f64]) -> String {
+    let extrema = extrema(coefficients);
+    if extrema.is_empty() {
+        return String::from("No local extremum\n");
+    }
+    let mut description = String::new();
+    for (x, y, kind) in extrema {
+        let kind = match kind {
+            Critical::Minimum => "Local minimum",
+            Critical::Maximum => "Local maximum",
+            Critical::Stationary => "Stationary point, not an extremum,",
+        };
+        description += &format!(
+            "{} at X = {}: {}\n",
+            kind,
+            float::shortest(x as f32),
+            float::shortest(y as f32)
+        );
+    }
+    description
+}
+
+/// The concavity on each interval between the zeros of the second
+/// derivative, and the inflection points where it changes.
+pub fn describe_curvature(coefficients: &[f64]) -> String {
+    let curvature = intervals(&numeric::derivative(&numeric::derivative(coefficients)));
+    if curvature.iter().all(|&(_, _, sign)| sign == Sign::Zero) {
+        return String::from("No curvature: the graph is a line\n");
+    }
+    let mut description = String::new();
+    for &(a, b, sign) in &curvature {
+        let concavity = match sign {
+            Sign::Positive => "concave up",
+            Sign::Negative => "concave down",
+            Sign::Zero => "straight",
+        };
+        description += &format!("({}, {}): {}\n", bound(a), bound(b), concavity);
+    }
+    let inflections: Vec<f64> = curvature
+        .windows(2)
+        .filter(|pair| pair[0].2 != pair[1].2)
+        .map(|pair| pair[0].1)
+        .collect();
+    if inflections.is_empty() {
+        description += "No inflection point\n";
+    }
+    for x in inflections {
+        description += &format!(
+            "Inflection point at X = {}: {}\n",
+            float::shortest(x as f32),
+            float::shortest(numeric::eval(coefficients, x) as f32)
+        );
+    }
+    description
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quadratic_without_real_roots_keeps_its_sign() {
+        assert_eq!(
+            describe(&[1.0, 0.0, 1.0]),
+            "Discriminant: -4\nThe polynomial is positive for every X\n"
+        );
+        assert_eq!(
+            describe(&[-1.0, 2.0, -1.0]),
+            "Discriminant: 0\nThe polynomial is negative for every X but X = 1, where it is zero\n"
+        );
+        assert_eq!(
+            describe(&[-3.0]),
+            "The polynomial is negative for every X\n"
+        );
+        assert_eq!(describe(&[]), "The polynomial is zero for every X\n");
+    }
+
+    #[test]
+    fn intervals_between_roots() {
+        // (X + 3)(X - 1)(X - 2)
+        let signs: Vec<Sign> = intervals(&[6.0, -7.0, 0.0, 1.0])
+            .iter()
+            .map(|&(_, _, sign)| sign)
+            .collect();
+        assert_eq!(
+            signs,
+            [
+                Sign::Negative,
+                Sign::Positive,
+                Sign::Negative,
+                Sign::Positive
+            ]
+        );
+        let description = describe(&[-4.0, 0.0, 1.0]);
+        assert!(description.ends_with(
+            "changes sign:\n(-inf, -2): positive\n(-2, 2): negative\n(2, inf): positive\n"
+        ));
+        // (X - 1)^2 * (X + 2) only changes sign at -2
+        let description = describe(&[2.0, -3.0, 0.0, 1.0]);
+        assert!(description.contains("(-2, 1): positive\n(1, inf): positive\n"));
+    }
+
+    #[test]
+    fn extrema_from_the_derivative() {
+        // X^3 - 3 * X, a maximum of 2 at -1 and a minimum of -2 at 1
+        assert_eq!(
+            describe_extrema(&[0.0, -3.0, 0.0, 1.0]),
+            "Local maximum at X = -1: 2\nLocal minimum at X = 1: -2\n"
+        );
+        assert_eq!(extrema(&[1.0, 0.0, 0.0, 1.0])[0].2, Critical::Stationary);
+        assert_eq!(
+            describe_extrema(&[-4.0, 0.0, -1.0]),
+            "Local maximum at X = 0: -4\n"
+        );
+        assert_eq!(describe_extrema(&[1.0, 2.0]), "No local extremum\n");
+    }
+
+    #[test]
+    fn curvature_changes_at_inflection_points() {
+        // X^3 - 3 * X bends at 0
+        assert_eq!(
+            describe_curvature(&[0.0, -3.0, 0.0, 1.0]),
+            "(-inf, 0): concave down\n(0, inf): concave up\nInflection point at X = 0: 0\n"
+        );
+        // X^4 has a flat point at 0 but keeps its concavity
+        assert!(describe_curvature(&[0.0, 0.0, 0.0, 0.0, 1.0]).ends_with("No inflection point\n"));
+        assert_eq!(
+            describe_curvature(&[-4.0, 0.0, 1.0]),
+            "(-inf, inf): concave up\nNo inflection point\n"
+        );
+        assert_eq!(
+            describe_curvature(&[1.0, 2.0]),
+            "No curvature: the graph is a line\n"
+        );
+    }
+}
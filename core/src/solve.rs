@@ -0,0 +1,693 @@
+use crate::complex::{self, Complex};
+use crate::decimal::{self, Rounding};
+use crate::event::{Event, Observer};
+use crate::expr::{self, Expr, Limits};
+use crate::format::Style;
+use crate::method::{self, Method};
+use crate::numeric::{self, Work};
+use crate::report::{self, Report};
+use crate::resultant;
+use crate::steps;
+use crate::warnings;
+use crate::{Edit, Poly};
+use computor_parse::diagnostics::Diagnostic;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// Past this many entries the memo of a solver starts over, keeping the
+/// memory used by a batch bounded.
+const CAPACITY: usize = 10_000;
+
+/// Everything that changes how one equation is solved, passed along with
+/// it rather than read from the command line, so that callers with
+/// different settings can solve side by side.
+///
+/// ```text
+/// SolveOptions::new().method(Method::DurandKerner).complex(true).precision(3)
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SolveOptions {
+    precision: Option<usize>,
+    rounding: Rounding,
+    epsilon: f64,
+    method: Method,
+    complex: bool,
+    exact: bool,
+    steps: bool,
+    style: Style,
+    limits: Limits,
+}
+
+impl Default for SolveOptions {
+    fn default() -> SolveOptions {
+        SolveOptions {
+            precision: None,
+            rounding: Rounding::HalfUp,
+            epsilon: 1e-7,
+            method: Method::Auto,
+            complex: false,
+            exact: true,
+            steps: false,
+            style: Style::default(),
+            limits: expr::DEFAULT_LIMITS,
+        }
+    }
+}
+
+impl SolveOptions {
+    pub fn new() -> SolveOptions {
+        SolveOptions::default()
+    }
+
+    /// Also give the roots as text with `digits` decimal places.
+    pub fn precision(mut self, digits: usize) -> SolveOptions {
+        self.precision = Some(digits);
+        self
+    }
+
+    pub fn rounding(mut self, rounding: Rounding) -> SolveOptions {
+        self.rounding = rounding;
+        self
+    }
+
+    /// Relative to the magnitude: roots closer than this are one root, and
+    /// an imaginary part under it makes a root real.
+    pub fn epsilon(mut self, epsilon: f64) -> SolveOptions {
+        self.epsilon = epsilon;
+        self
+    }
+
+    pub fn method(mut self, method: Method) -> SolveOptions {
+        self.method = method;
+        self
+    }
+
+    /// Also find the roots off the real axis.
+    pub fn complex(mut self, complex: bool) -> SolveOptions {
+        self.complex = complex;
+        self
+    }
+
+    /// Solve degree 1 and 2 from the decimals as written, rounding them
+    /// correctly, rather than in floating point.
+    pub fn exact(mut self, exact: bool) -> SolveOptions {
+        self.exact = exact;
+        self
+    }
+
+    /// Record how the roots were found.
+    pub fn steps(mut self, steps: bool) -> SolveOptions {
+        self.steps = steps;
+        self
+    }
+
+    /// How the reduced form and the steps of a report are written.
+    pub fn style(mut self, style: Style) -> SolveOptions {
+        self.style = style;
+        self
+    }
+
+    /// Those the equation is parsed within.
+    pub fn limits(mut self, limits: Limits) -> SolveOptions {
+        self.limits = limits;
+        self
+    }
+
+    pub fn get_precision(&self) -> Option<usize> {
+        self.precision
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct Solution {
+    /// The method that ran, never auto.
+    pub method: Method,
+    /// In increasing order.
+    pub roots: Vec<f64>,
+    /// Whether the roots are exact rather than approximated, as they are
+    /// from a closed form.
+    pub exact: bool,
+    /// The roots with the precision asked, empty without one.
+    pub rounded: Vec<String>,
+    /// The roots off the real axis, empty unless asked for. Each conjugate
+    /// pair is together, the root above the axis first.
+    pub complex: Vec<Complex>,
+    pub steps: Vec<String>,
+    /// Stopped before the end, the roots being those found so far.
+    pub cancelled: bool,
+}
+
+fn close(a: f64, b: f64, epsilon: f64) -> bool {
+    (a - b).abs() <= epsilon * a.abs().max(b.abs()).max(1.0)
+}
+
+/// Sorts the estimates and merges those of one root. Distinct roots are at
+/// least `separation` apart, so estimates closer than half of it are of one
+/// root even past epsilon, like the cluster around a repeated root.
+fn merge_close(mut roots: Vec<f64>, epsilon: f64, separation: f64) -> Vec<f64> {
+    roots.sort_by(f64::total_cmp);
+    roots.dedup_by(|a, b| close(*a, *b, epsilon) || (*a - *b).abs() < separation / 2.0);
+    roots
+}
+
+/// Solves the reduced polynomial, coefficients by ascending degree, the way
+/// the options say. A method that does not apply finds no root. The
+/// iterative methods stop once `cancel` is raised from another thread, and
+/// tell the observer of their progress and of the roots found.
+pub fn solve(
+    coefficients: &[f32],
+    options: &SolveOptions,
+    cancel: &AtomicBool,
+    observer: Option<Observer>,
+) -> Solution {
+    let mut work = Work {
+        cancel: Some(cancel),
+        observer,
+        ..Work::default()
+    };
+    let wide: Vec<f64> = coefficients.iter().map(|&c| c as f64).collect();
+    // above degree 2, factoring out X^k or into binomials and quadratics
+    // may still leave closed forms
+    let poly = Poly::from_coefficients(coefficients.to_vec());
+    let factored = options.method == Method::Auto
+        && options.exact
+        && poly.get_degree() > 2
+        && poly.has_exact_roots();
+    let (method, reason) = match options.method {
+        _ if factored => (
+            Method::ClosedForm,
+            String::from("its factors have closed forms"),
+        ),
+        Method::Auto => method::choose(&wide),
+        method => (method, String::from("as asked")),
+    };
+    let exact = match options.exact {
+        true => decimal::exact_roots(coefficients).filter(|_| method == Method::ClosedForm),
+        false => None,
+    };
+    let roots: Vec<f64> = match &exact {
+        Some(exact) => exact.iter().map(|root| root.to_f64()).collect(),
+        None if factored => poly.real_roots().iter().map(|&root| root as f64).collect(),
+        None => method::solve_counting(method, &wide, &mut work).unwrap_or_default(),
+    };
+    let separation = match (options.exact, &exact) {
+        (true, None) => resultant::separation_bound(coefficients).unwrap_or(0.0),
+        _ => 0.0,
+    };
+    let roots = merge_close(roots, options.epsilon, separation);
+    let rounded = match (options.precision, &exact) {
+        (None, _) => vec![],
+        (Some(digits), Some(exact)) => {
+            let mut exact = exact.clone();
+            exact.sort_by(|a, b| a.to_f64().total_cmp(&b.to_f64()));
+            exact
+                .iter()
+                .map(|root| root.round(digits, options.rounding))
+                .collect()
+        }
+        (Some(digits), None) => roots
+            .iter()
+            .map(|&root| decimal::round_approximate(root as f32, digits, options.rounding))
+            .collect(),
+    };
+    let complex = match options.complex {
+        true => {
+            // the closed forms of the factors are real
+            let from = match factored {
+                true => method::choose(&wide).0,
+                false => method,
+            };
+            let found = method::complex_roots_counting(from, &wide, &mut work);
+            // no more than the degree the real roots leave
+            let pairs = wide.len().saturating_sub(1 + roots.len()) / 2;
+            complex::conjugate_pairs(&found, options.epsilon)
+                .into_iter()
+                .take(pairs)
+                .flat_map(|z| [z, Complex::new(z.re, -z.im)])
+                .collect()
+        }
+        false => vec![],
+    };
+    let mut steps = vec![];
+    if options.steps {
+        steps.push(format!("Method: {} ({})", method.name(), reason));
+        match coefficients.len() {
+            2 if method == Method::ClosedForm => steps.extend(steps::rearrangement(coefficients)),
+            3 if method == Method::ClosedForm => steps.push(steps::quadratic_formula(coefficients)),
+            _ => {}
+        }
+    }
+    let solution = Solution {
+        rounded,
+        complex,
+        steps,
+        cancelled: work.cancelled(),
+        ..Solution::new(method, roots, method == Method::ClosedForm)
+    };
+    announce(&solution, observer);
+    solution
+}
+
+impl Solution {
+    /// Found without steps, complex roots or rounding.
+    pub fn new(method: Method, roots: Vec<f64>, exact: bool) -> Solution {
+        Solution {
+            method,
+            roots,
+            exact,
+            rounded: vec![],
+            complex: vec![],
+            steps: vec![],
+            cancelled: false,
+        }
+    }
+
+    /// The root above the axis of each conjugate pair, by increasing real
+    /// part.
+    pub fn upper_complex(&self) -> Vec<Complex> {
+        self.complex.iter().step_by(2).copied().collect()
+    }
+}
+
+fn announce(solution: &Solution, observer: Option<Observer>) {
+    if let Some(observer) = observer {
+        for &value in &solution.roots {
+            observer(&Event::Root { value });
+        }
+    }
+}
+
+/// Options and memoized solutions shared by every equation solved with it.
+/// It is `Send + Sync`, so one solver can serve many threads.
+#[derive(Debug)]
+pub struct Solver {
+    options: SolveOptions,
+    /// By `Poly::canonical_form`, compared in full: a hash collision would
+    /// hand out the roots of another equation.
+    solutions: Mutex<HashMap<Vec<i128>, Solution>>,
+    memoize: bool,
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+}
+
+impl Solver {
+    pub fn new(options: SolveOptions) -> Solver {
+        Solver {
+            options,
+            solutions: Mutex::new(HashMap::new()),
+            memoize: true,
+            hits: AtomicUsize::new(0),
+            misses: AtomicUsize::new(0),
+        }
+    }
+
+    /// Solves every equation, so that the roots do not depend on which of
+    /// the equivalent equations was solved first.
+    pub fn without_memo(options: SolveOptions) -> Solver {
+        Solver {
+            memoize: false,
+            ..Solver::new(options)
+        }
+    }
+
+    /// The solutions memoized and those solved so far.
+    pub fn hits_and_misses(&self) -> (usize, usize) {
+        (
+            self.hits.load(Ordering::Relaxed),
+            self.misses.load(Ordering::Relaxed),
+        )
+    }
+
+    /// `solve` with the options of the solver. Equivalent equations, as told
+    /// by `Poly::canonical_form`, are solved once unless steps are recorded,
+    /// since the steps show the coefficients. A cancelled solution is
+    /// partial and not memoized, nor are equations whose canonical form
+    /// overflows. A memoized solution is announced to the observer at once,
+    /// without iterations.
+    pub fn solve(
+        &self,
+        coefficients: &[f32],
+        cancel: &AtomicBool,
+        observer: Option<Observer>,
+    ) -> Solution {
+        let key = Poly {
+            coefficients: coefficients.to_vec(),
+        }
+        .canonical_form();
+        let Some(key) = key.filter(|_| self.memoize && !self.options.steps) else {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+            return solve(coefficients, &self.options, cancel, observer);
+        };
+        if let Some(solution) = self
+            .solutions
+            .lock()
+            .ok()
+            .and_then(|memo| memo.get(&key).cloned())
+        {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            announce(&solution, observer);
+            return solution;
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let solution = solve(coefficients, &self.options, cancel, observer);
+        if solution.cancelled {
+            return solution;
+        }
+        if let Ok(mut memo) = self.solutions.lock() {
+            if memo.len() >= CAPACITY {
+                memo.clear();
+            }
+            memo.insert(key, solution.clone());
+        }
+        solution
+    }
+}
+
+/// Everything known about one equation in a single report, for programs
+/// embedding the solver: the reduced form once `edits` are applied, the
+/// discriminant exact and as a float, the steps when recorded, the real
+/// roots with their residual, the complex ones when asked for and every
+/// warning. Solved until `cancel` is raised, `observer` being told of each
+/// stage.
+pub fn solve_all(
+    input: &str,
+    options: &SolveOptions,
+    edits: &[Edit],
+    cancel: &AtomicBool,
+    observer: Option<Observer>,
+) -> Result<Report, Vec<Diagnostic>> {
+    Solver::without_memo(*options).solve_all(input, edits, cancel, observer)
+}
+
+impl Solver {
+    /// `solve_all` with the options and the memo of the solver.
+    pub fn solve_all(
+        &self,
+        input: &str,
+        edits: &[Edit],
+        cancel: &AtomicBool,
+        observer: Option<Observer>,
+    ) -> Result<Report, Vec<Diagnostic>> {
+        let emit = |event| {
+            if let Some(observer) = observer {
+                observer(&event);
+            }
+        };
+        let (left, right) = expr::check_equation_with(input, &self.options.limits)?;
+        emit(Event::Parsed {
+            equation: format!("{} = {}", left, right),
+        });
+        let expanded = Expr::Sub(Box::new(left.clone()), Box::new(right.clone())).expand();
+        let mut poly = match expanded {
+            Ok(coefficients) => Poly::from_coefficients(coefficients),
+            Err(error) => {
+                let end = input.chars().count();
+                let message = String::from("the equation does not reduce to a polynomial");
+                return Err(vec![Diagnostic::new(error, message, 0..end)]);
+            }
+        };
+        emit(Event::Reduced {
+            coefficients: poly.coefficients().to_vec(),
+        });
+        let warnings = warnings::check(input, poly.coefficients(), &self.options.limits);
+        let expanded = poly.coefficients().to_vec();
+        for &edit in edits {
+            poly.apply(edit);
+        }
+        if poly.coefficients() != expanded {
+            emit(Event::Reduced {
+                coefficients: poly.coefficients().to_vec(),
+            });
+        }
+        let coefficients = poly.coefficients_f64();
+        let solution = self.solve(poly.coefficients(), cancel, observer);
+        let exact = solution.exact;
+        let roots = solution
+            .roots
+            .iter()
+            .map(|&value| report::Root {
+                value: value as f32,
+                residual: numeric::eval(&coefficients, value).abs() as f32,
+                exact,
+            })
+            .collect();
+        let style = self.options.style;
+        let mut steps = match self.options.steps {
+            true => steps::expansion(&left, &right, style).unwrap_or_default(),
+            false => vec![],
+        };
+        steps.extend(solution.steps.iter().cloned());
+        if self.options.steps {
+            steps.extend(steps::unsolvability(poly.coefficients()));
+        }
+        let exact_discriminant =
+            resultant::discriminant(poly.coefficients()).map(|(n, d)| match d {
+                1 => n.to_string(),
+                _ => format!("{}/{}", n, d),
+            });
+        let complex_roots = solution
+            .upper_complex()
+            .iter()
+            .map(|z| report::ComplexPair {
+                re: z.re as f32,
+                im: z.im as f32,
+                text: z.plus_minus(),
+                latex: z.plus_minus_latex(),
+            })
+            .collect();
+        let solutions = solution.roots.iter().map(|&root| root as f32).collect();
+        Ok(Report {
+            exact,
+            rounded: solution.rounded,
+            exact_discriminant,
+            cancelled: solution.cancelled,
+            complex_roots,
+            warnings,
+            steps,
+            roots,
+            ..poly.report_with_roots(style, solutions)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static NEVER: AtomicBool = AtomicBool::new(false);
+
+    #[test]
+    fn options_change_the_solution() {
+        // X^2 - 2 = 0
+        let coefficients = [-2.0, 0.0, 1.0];
+        let solution = solve(
+            &coefficients,
+            &SolveOptions::new().precision(3),
+            &NEVER,
+            None,
+        );
+        assert_eq!(solution.method, Method::ClosedForm);
+        assert_eq!(solution.rounded, ["≈ -1.414", "≈ 1.414"]);
+        assert!(solution.complex.is_empty() && solution.steps.is_empty());
+        let options = SolveOptions::new().method(Method::DurandKerner).steps(true);
+        let solution = solve(&coefficients, &options, &NEVER, None);
+        assert_eq!(solution.method, Method::DurandKerner);
+        assert!((solution.roots[1] - 2f64.sqrt()).abs() < 1e-6);
+        assert_eq!(solution.steps, ["Method: durand-kerner (as asked)"]);
+        // 2 * X = 5, the float root rounded from its shortest decimal like
+        // the exact one
+        let options = SolveOptions::new().precision(0).exact(false);
+        assert_eq!(solve(&[-5.0, 2.0], &options, &NEVER, None).rounded, ["≈ 3"]);
+        let even = options.rounding(Rounding::HalfEven);
+        assert_eq!(solve(&[-5.0, 2.0], &even, &NEVER, None).rounded, ["≈ 2"]);
+        assert_eq!(
+            solve(&[-5.0, 2.0], &options.exact(true), &NEVER, None).rounded,
+            ["≈ 3"]
+        );
+    }
+
+    #[test]
+    fn solve_all_bundles_the_report() {
+        let options = SolveOptions::new().steps(true);
+        let edits = [Edit::PositiveLead];
+        let report = solve_all("0 * X^3 + 4 = X^2", &options, &edits, &NEVER, None).unwrap();
+        assert_eq!(report.reduced_form, "-4 * X^0 + 1 * X^2 = 0");
+        assert_eq!(report.exact_discriminant.as_deref(), Some("16"));
+        assert_eq!(
+            report.steps[2],
+            "Method: closed-form (degree 2 has a closed form)"
+        );
+        assert_eq!(report.roots[1].value, 2.0);
+        assert_eq!(report.roots[0].residual, 0.0);
+        assert_eq!(report.warnings[0].category, warnings::Category::ZeroTerm);
+        let errors = solve_all("X^2 + = 4", &options, &[], &NEVER, None).unwrap_err();
+        assert_eq!(errors[0].error.code(), "E003");
+        let limits = Limits {
+            degree: 1,
+            ..expr::DEFAULT_LIMITS
+        };
+        let errors = solve_all("X^2 = 4", &options.limits(limits), &[], &NEVER, None).unwrap_err();
+        assert_eq!(errors[0].error.code(), "E009");
+    }
+
+    #[test]
+    fn report_and_roots_agree_on_exactness() {
+        let options = SolveOptions::new();
+        // X (X - 2)(X + 2), from factoring out X
+        let report = solve_all("X^3 - 4 * X = 0", &options, &[], &NEVER, None).unwrap();
+        assert_eq!(report.solutions, [-2.0, 0.0, 2.0]);
+        assert!(report.exact && report.roots.iter().all(|root| root.exact));
+        let report = solve_all("X^3 + X + 1 = 0", &options, &[], &NEVER, None).unwrap();
+        assert_eq!(report.solutions.len(), 1);
+        assert!(!report.exact && !report.roots[0].exact);
+        let float = options.exact(false);
+        let report = solve_all("X^3 - 4 * X = 0", &float, &[], &NEVER, None).unwrap();
+        assert!(!report.exact && report.roots.iter().all(|root| !root.exact));
+    }
+
+    #[test]
+    fn estimates_closer_than_the_separation_are_merged() {
+        let estimates = vec![1.0, 1.00001, 2.0];
+        assert_eq!(merge_close(estimates.clone(), 1e-7, 0.0).len(), 3);
+        assert_eq!(merge_close(estimates, 1e-7, 0.5), [1.0, 2.0]);
+        assert_eq!(merge_close(vec![1.0, 1.0 + 1e-9], 1e-7, 0.0), [1.0]);
+    }
+
+    #[test]
+    fn solver_is_shared_across_threads() {
+        fn shareable<T: Send + Sync>(_: &T) {}
+        let solver = Solver::new(SolveOptions::new().precision(2));
+        shareable(&solver);
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                // X^2 = 4, then scaled by 2
+                scope.spawn(|| {
+                    assert_eq!(
+                        solver.solve(&[-4.0, 0.0, 1.0], &NEVER, None).rounded,
+                        ["-2.00", "2.00"]
+                    );
+                    solver.solve(&[-8.0, 0.0, 2.0], &NEVER, None);
+                });
+            }
+        });
+        let (hits, misses) = solver.hits_and_misses();
+        assert_eq!(hits + misses, 8);
+        // each second equation is equivalent to the first of its thread
+        assert!(hits >= 4);
+        let solver = Solver::new(SolveOptions::new().steps(true));
+        solver.solve(&[-4.0, 0.0, 1.0], &NEVER, None);
+        solver.solve(&[-4.0, 0.0, 1.0], &NEVER, None);
+        assert_eq!(solver.hits_and_misses(), (0, 2));
+        let solver = Solver::without_memo(SolveOptions::new());
+        solver.solve(&[-4.0, 0.0, 1.0], &NEVER, None);
+        solver.solve(&[-4.0, 0.0, 1.0], &NEVER, None);
+        assert_eq!(solver.hits_and_misses(), (0, 2));
+    }
+
+    #[test]
+    fn cancelled_solution_is_partial() {
+        // (X - 1)(X - 2)(X - 3), solved by bracketing
+        let coefficients = [-6.0, 11.0, -6.0, 1.0];
+        let cancel = AtomicBool::new(true);
+        let options = SolveOptions::new().complex(true);
+        let solution = solve(&coefficients, &options, &cancel, None);
+        assert!(solution.cancelled);
+        assert!(solution.roots.is_empty());
+        let solver = Solver::new(options);
+        assert!(solver.solve(&coefficients, &cancel, None).cancelled);
+        let solution = solver.solve(&coefficients, &NEVER, None);
+        assert!(!solution.cancelled);
+        assert_eq!(solution.roots.len(), 3);
+        // the closed forms do not iterate
+        assert_eq!(solve(&[-2.0, 1.0], &options, &cancel, None).roots, [2.0]);
+    }
+
+    #[test]
+    fn observer_sees_progress_and_roots() {
+        let events = Mutex::new(vec![]);
+        let observer = |event: &Event| events.lock().unwrap().push(event.clone());
+        // (X - 1)^5 converges slowly
+        let coefficients = [-1.0, 5.0, -10.0, 10.0, -5.0, 1.0];
+        let options = SolveOptions::new().method(Method::DurandKerner);
+        solve(&coefficients, &options, &NEVER, Some(&observer));
+        let solver = Solver::new(SolveOptions::new());
+        solver.solve(&[-4.0, 0.0, 1.0], &NEVER, Some(&observer));
+        // memoized, still announced
+        solver.solve(&[-8.0, 0.0, 2.0], &NEVER, Some(&observer));
+        let events = events.into_inner().unwrap();
+        let iterations: Vec<usize> = events
+            .iter()
+            .filter_map(|event| match event {
+                Event::Iteration { iterations } => Some(*iterations),
+                _ => None,
+            })
+            .collect();
+        assert!(!iterations.is_empty());
+        assert!(iterations
+            .iter()
+            .all(|i| i % crate::event::PROGRESS_EVERY == 0));
+        let roots: Vec<&Event> = events
+            .iter()
+            .skip_while(|event| matches!(event, Event::Iteration { .. }))
+            .collect();
+        let root = |value| Event::Root { value };
+        assert_eq!(roots, [&root(-2.0), &root(2.0), &root(-2.0), &root(2.0)]);
+    }
+
+    #[test]
+    fn complex_roots_and_epsilon() {
+        // (X - 1)(X^2 + 1)
+        let coefficients = [-1.0, 1.0, -1.0, 1.0];
+        let solution = solve(
+            &coefficients,
+            &SolveOptions::new().complex(true),
+            &NEVER,
+            None,
+        );
+        assert_eq!(solution.roots.len(), 1);
+        assert_eq!(solution.complex.len(), 2);
+        // X^2 + 2 * X + 5 in closed form
+        let solution = solve(
+            &[5.0, 2.0, 1.0],
+            &SolveOptions::new().complex(true),
+            &NEVER,
+            None,
+        );
+        assert_eq!(
+            solution.complex,
+            [Complex::new(-1.0, 2.0), Complex::new(-1.0, -2.0)]
+        );
+        // X^2 - 2.000001 * X + 1.000001 has roots 1 and 1.000001
+        let coefficients = [1.000001, -2.000001, 1.0];
+        assert_eq!(
+            solve(&coefficients, &SolveOptions::new(), &NEVER, None)
+                .roots
+                .len(),
+            2
+        );
+        let options = SolveOptions::new().epsilon(1e-3);
+        assert_eq!(solve(&coefficients, &options, &NEVER, None).roots.len(), 1);
+    }
+
+    #[test]
+    fn complex_roots_from_every_method() {
+        let complex = |coefficients: &[f32], method| {
+            let options = SolveOptions::new().complex(true).method(method);
+            solve(coefficients, &options, &NEVER, None).upper_complex()
+        };
+        // (X - 1)^3 has no root off the axis, whatever copies Durand-Kerner finds
+        for method in [Method::Auto, Method::Bracketing, Method::DurandKerner] {
+            assert!(complex(&[-1.0, 3.0, -3.0, 1.0], method).is_empty());
+        }
+        // X^4 - 16 by substitution: ±2 and ±2i
+        let roots = complex(&[-16.0, 0.0, 0.0, 0.0, 1.0], Method::Substitution);
+        assert_eq!(roots.len(), 1);
+        assert!((roots[0] - Complex::new(0.0, 2.0)).norm() < 1e-12);
+        // (X^2 + 1)^2 (X - 2), the pair found once
+        let roots = complex(&[-2.0, 1.0, -4.0, 2.0, -2.0, 1.0], Method::DurandKerner);
+        assert_eq!(roots.len(), 1);
+        assert!((roots[0] - Complex::new(0.0, 1.0)).norm() < 1e-9);
+    }
+}
@@ -0,0 +1,246 @@
+use crate::expr::Expr;
+use crate::float;
+use crate::format::{format_polynomial, Format, Order, Style};
+use crate::ParseError;
+use std::fmt;
+
+/// Prints the equation as parsed, then each side fully expanded, before the
+/// reduced form is printed by `Poly::print`.
+pub fn print_expansion(left: &Expr, right: &Expr, style: Style) -> Result<(), ParseError> {
+    for line in expansion(left, right, style)? {
+        println!("{}", line);
+    }
+    Ok(())
+}
+
+/// The lines of `print_expansion`.
+pub fn expansion(left: &Expr, right: &Expr, style: Style) -> Result<Vec<String>, ParseError> {
+    Ok(vec![
+        format!("Parsed: {} = {}", left, right),
+        format!(
+            "Expanded: {} = {}",
+            format_polynomial(&left.expand()?, style),
+            format_polynomial(&right.expand()?, style)
+        ),
+    ])
+}
+
+/// Isolates `X` in `aX + b = 0` one operation at a time, keeping exact
+/// fractions when both coefficients are integers.
+pub fn print_rearrangement(coefficients: &[f32]) {
+    for line in rearrangement(coefficients) {
+        println!("{}", line);
+    }
+}
+
+/// The lines of `print_rearrangement`.
+pub fn rearrangement(coefficients: &[f32]) -> Vec<String> {
+    let (b, a) = (coefficients[0], coefficients[1]);
+    let pretty = Style {
+        format: Format::Pretty,
+        order: Order::Descending,
+    };
+    let mut lines = vec![String::from("Rearrangement:")];
+    lines.push(format!("{} = 0", format_polynomial(coefficients, pretty)));
+    if b != 0.0 {
+        lines.push(format!(
+            "{} = {}",
+            format_polynomial(&[0.0, a], pretty),
+            float::shortest(-b)
+        ));
+    }
+    if a != 1.0 {
+        lines.push(format!("X = {}", fraction(-b, a)));
+    }
+    lines.push(format!("X = {}", float::shortest(-b / a)));
+    lines
+}
+
+/// The quadratic formula with `a`, `b` and `c` substituted but nothing
+/// evaluated beyond the signs and `b²`.
+pub fn quadratic_formula(coefficients: &[f32]) -> String {
+    let (c, b, a) = (coefficients[0], coefficients[1], coefficients[2]);
+    let sign = if a * c > 0.0 { '-' } else { '+' };
+    format!(
+        "X = ({} ± √({} {} 4·{}·{})) / (2·{})",
+        float::shortest(-b + 0.0),
+        float::shortest(b * b),
+        sign,
+        float::shortest(a.abs()),
+        float::shortest(c.abs()),
+        Operand(a)
+    )
+}
+
+/// What the discriminant of `aX² + bX + c` measures, its value with the
+/// coefficients substituted, and what that means for the parabola and the
+/// X-axis.
+pub fn discriminant_explanation(coefficients: &[f32]) -> Vec<String> {
+    let (c, b, a) = (coefficients[0], coefficients[1], coefficients[2]);
+    let discriminant = b * b - 4.0 * a * c;
+    let meaning = match discriminant {
+        d if d > 0.0 => "Δ > 0: the parabola crosses the X-axis at two points, two real solutions",
+        0.0 => "Δ = 0: the parabola touches the X-axis at its vertex, one real solution",
+        _ => "Δ < 0: the parabola never meets the X-axis, no real solution",
+    };
+    vec![
+        String::from("Discriminant:"),
+        String::from(
+            "Δ = b² - 4·a·c is what the square root of the quadratic formula is taken of,",
+        ),
+        String::from(
+            "so its sign tells how many times the parabola y = aX² + bX + c meets the X-axis.",
+        ),
+        format!(
+            "Δ = {}² - 4·{}·{} = {}",
+            Operand(b),
+            Operand(a),
+            Operand(c),
+            float::shortest(discriminant + 0.0)
+        ),
+        String::from(meaning),
+    ]
+}
+
+/// The short proof that the reduced equation has no real solution, when it
+/// is a nonzero constant or a quadratic with a negative discriminant, by
+/// completing the square. Empty otherwise.
+pub fn unsolvability(coefficients: &[f32]) -> Vec<String> {
+    match coefficients[..] {
+        [c] => vec![
+            String::from("Proof that there is no solution:"),
+            format!("The equation reduces to {} = 0,", float::shortest(c)),
+            format!("and {} ≠ 0 whatever X is.", float::shortest(c)),
+        ],
+        [c, b, a] if b * b - 4.0 * a * c < 0.0 => {
+            let discriminant = b * b - 4.0 * a * c;
+            let shift = b / (2.0 * a) + 0.0;
+            let rest = float::shortest(-discriminant / (4.0 * a * a));
+            vec![
+                String::from("Proof that there is no real solution:"),
+                format!(
+                    "Δ = {}² - 4·{}·{} = {} < 0",
+                    Operand(b),
+                    Operand(a),
+                    Operand(c),
+                    float::shortest(discriminant)
+                ),
+                format!(
+                    "{}·X² + {}·X + {} = {}·((X + {})² + {})",
+                    Operand(a),
+                    Operand(b),
+                    Operand(c),
+                    Operand(a),
+                    Operand(shift),
+                    rest
+                ),
+                format!(
+                    "A square is never negative and {} = -Δ / (4·a²) > 0, so the bracket is positive and the left side never 0 for a real X.",
+                    rest
+                ),
+                String::from("The two solutions are complex, rerun with --complex to see them."),
+            ]
+        }
+        _ => vec![],
+    }
+}
+
+fn fraction(numerator: f32, denominator: f32) -> String {
+    if numerator.fract() != 0.0 || denominator.fract() != 0.0 {
+        return format!("{} / {}", float::shortest(numerator), Operand(denominator));
+    }
+    let (mut numerator, mut denominator) = (numerator as i64, denominator as i64);
+    if denominator < 0 {
+        numerator = -numerator;
+        denominator = -denominator;
+    }
+    let divisor = crate::gcd(numerator as i128, denominator as i128).max(1) as i64;
+    format!("{}/{}", numerator / divisor, denominator / divisor)
+}
+
+struct Operand(f32);
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.0 < 0.0 {
+            write!(f, "({})", float::shortest(self.0))
+        } else {
+            write!(f, "{}", float::shortest(self.0))
+        }
+    }
+}
+
+/// Substitutes each root into both sides of the equation as it was written,
+/// so that a mistake in the reduction would show up as a large residual.
+pub fn print_residuals(left: &Expr, right: &Expr, roots: &[f32]) {
+    if roots.is_empty() {
+        return;
+    }
+    println!("Residuals in the original equation:");
+    for &root in roots {
+        let (left, right) = (left.eval(root as f64), right.eval(root as f64));
+        println!(
+            "X = {}: {} - {} = {}",
+            float::shortest(root),
+            float::shortest(left as f32),
+            float::shortest(right as f32),
+            float::shortest_scientific((left - right) as f32)
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exact_fractions() {
+        assert_eq!(fraction(-1.0, 4.0), "-1/4");
+        assert_eq!(fraction(6.0, -4.0), "-3/2");
+        assert_eq!(fraction(0.0, 3.0), "0/1");
+        assert_eq!(fraction(1.5, -2.0), "1.5 / (-2)");
+    }
+
+    #[test]
+    fn substituted_formula() {
+        assert_eq!(
+            quadratic_formula(&[5.0, 4.0, -9.3]),
+            "X = (-4 ± √(16 + 4·9.3·5)) / (2·(-9.3))"
+        );
+        assert_eq!(
+            quadratic_formula(&[2.0, -3.0, 1.0]),
+            "X = (3 ± √(9 - 4·1·2)) / (2·1)"
+        );
+        assert_eq!(
+            quadratic_formula(&[0.0, 0.0, 1.0]),
+            "X = (0 ± √(0 + 4·1·0)) / (2·1)"
+        );
+    }
+
+    #[test]
+    fn proofs_of_no_solution() {
+        assert_eq!(
+            unsolvability(&[3.0]),
+            [
+                "Proof that there is no solution:",
+                "The equation reduces to 3 = 0,",
+                "and 3 ≠ 0 whatever X is."
+            ]
+        );
+        let proof = unsolvability(&[5.0, -2.0, 1.0]);
+        assert_eq!(proof[1], "Δ = (-2)² - 4·1·5 = -16 < 0");
+        assert_eq!(proof[2], "1·X² + (-2)·X + 5 = 1·((X + (-1))² + 4)");
+        assert!(unsolvability(&[-1.0, 0.0, 1.0]).is_empty());
+        assert!(unsolvability(&[1.0, 1.0]).is_empty());
+        assert!(unsolvability(&[]).is_empty());
+    }
+
+    #[test]
+    fn discriminant_explained() {
+        let lines = discriminant_explanation(&[5.0, 4.0, -9.3]);
+        assert_eq!(lines[3], "Δ = 4² - 4·(-9.3)·5 = 202");
+        assert!(lines[4].starts_with("Δ > 0: the parabola crosses"));
+        assert!(discriminant_explanation(&[1.0, -2.0, 1.0])[4].starts_with("Δ = 0"));
+        assert!(discriminant_explanation(&[1.0, 0.0, 1.0])[4].starts_with("Δ < 0"));
+    }
+}
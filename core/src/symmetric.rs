@@ -0,0 +1,85 @@
+use crate::complex::Complex;
+use crate::float;
+use crate::numeric;
+
+/// `e1, ..., en` of the complex roots, from Vieta's formulas:
+/// `ek = (-1)^k a(n-k) / an`.
+pub fn elementary(coefficients: &[f64]) -> Vec<f64> {
+    let Some((&lead, rest)) = coefficients.split_last() else {
+        return vec![];
+    };
+    rest.iter()
+        .rev()
+        .enumerate()
+        .map(|(i, c)| if i % 2 == 0 { -c / lead } else { c / lead })
+        .collect()
+}
+
+/// `p1, ..., p(count)`, the sums of the `k`-th powers of the roots, by
+/// Newton's identities `pk = e1 p(k-1) - e2 p(k-2) + ... + (-1)^(k-1) k ek`,
+/// where `ek` is zero above the degree.
+pub fn power_sums(coefficients: &[f64], count: usize) -> Vec<f64> {
+    let e = elementary(coefficients);
+    let e = |i: usize| e.get(i - 1).copied().unwrap_or(0.0);
+    let sign = |i: usize| if i % 2 == 1 { 1.0 } else { -1.0 };
+    let mut p: Vec<f64> = vec![];
+    for k in 1..=count {
+        let mut sum = sign(k) * k as f64 * e(k);
+        for i in 1..k {
+            sum += sign(i) * e(i) * p[k - i - 1];
+        }
+        p.push(sum);
+    }
+    p
+}
+
+/// Both families, each power sum next to the one of the numeric roots to
+/// check them against.
+pub fn describe(coefficients: &[f64]) -> String {
+    let mut description = String::from("Elementary symmetric functions of the roots:\n");
+    for (k, e) in elementary(coefficients).iter().enumerate() {
+        // + 0.0 prints -0 as 0
+        description += &format!("e{} = {}\n", k + 1, float::shortest((e + 0.0) as f32));
+    }
+    let degree = coefficients.len().saturating_sub(1);
+    let roots = numeric::durand_kerner(coefficients);
+    description += "Power sums of the roots, then from the numeric roots:\n";
+    for (k, p) in power_sums(coefficients, degree).iter().enumerate() {
+        let numeric = roots.iter().fold(Complex::from(0.0), |sum, &z| {
+            sum + (0..=k).fold(Complex::from(1.0), |power, _| power * z)
+        });
+        description += &format!(
+            "p{} = {} ({})\n",
+            k + 1,
+            float::shortest((p + 0.0) as f32),
+            numeric
+        );
+    }
+    description
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vieta_and_newton_identities() {
+        // (X - 1)(X - 2)(X + 3): roots sum to 0, pairs to -7, product -6
+        let coefficients = [6.0, -7.0, 0.0, 1.0];
+        assert_eq!(elementary(&coefficients), [0.0, -7.0, -6.0]);
+        // 1 + 2 - 3, 1 + 4 + 9, 1 + 8 - 27, 1 + 16 + 81
+        assert_eq!(power_sums(&coefficients, 4), [0.0, 14.0, -18.0, 98.0]);
+        // 2 * X^2 - 6 * X + 4 has roots 1 and 2
+        assert_eq!(elementary(&[4.0, -6.0, 2.0]), [3.0, 2.0]);
+        assert_eq!(power_sums(&[4.0, -6.0, 2.0], 3), [3.0, 5.0, 9.0]);
+        // X^2 + 1: i^2 + (-i)^2
+        assert_eq!(power_sums(&[1.0, 0.0, 1.0], 2), [0.0, -2.0]);
+    }
+
+    #[test]
+    fn power_sums_match_numeric_roots() {
+        let description = describe(&[6.0, -7.0, 0.0, 1.0]);
+        assert!(description.contains("e2 = -7\n"));
+        assert!(description.contains("p2 = 14 (14)\n"));
+    }
+}
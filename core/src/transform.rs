@@ -0,0 +1,70 @@
+use crate::float;
+
+/// A change of the roots done on the coefficients alone, without solving.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Transform {
+    /// `1 / r`, the coefficients reversed. A root at 0 has no reciprocal
+    /// and is lost.
+    Reciprocal,
+    /// `-r`, the signs of the odd powers flipped.
+    Negate,
+    /// `k * r`, the coefficient of `X^i` multiplied by `k^(n - i)`.
+    Scale(f32),
+}
+
+impl Transform {
+    pub fn apply(self, coefficients: &[f64]) -> Vec<f64> {
+        let degree = coefficients.len().saturating_sub(1);
+        let mut transformed: Vec<f64> = match self {
+            Transform::Reciprocal => {
+                let first = coefficients.iter().position(|&c| c != 0.0).unwrap_or(0);
+                coefficients[first..].iter().rev().copied().collect()
+            }
+            Transform::Negate => coefficients
+                .iter()
+                .enumerate()
+                .map(|(i, c)| if i % 2 == 1 { -c } else { *c })
+                .collect(),
+            Transform::Scale(k) => coefficients
+                .iter()
+                .enumerate()
+                .map(|(i, c)| c * (k as f64).powi((degree - i) as i32))
+                .collect(),
+        };
+        while transformed.last() == Some(&0.0) {
+            transformed.pop();
+        }
+        transformed
+    }
+
+    /// The new roots in terms of an old root `r`.
+    pub fn roots(self) -> String {
+        match self {
+            Transform::Reciprocal => String::from("1/r"),
+            Transform::Negate => String::from("-r"),
+            Transform::Scale(k) => format!("{}r", float::shortest(k)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transformed_roots() {
+        // (X - 2)(X - 4), and roots 1/2 and 1/4 for 8X^2 - 6X + 1
+        let coefficients = [8.0, -6.0, 1.0];
+        assert_eq!(Transform::Reciprocal.apply(&coefficients), [1.0, -6.0, 8.0]);
+        assert_eq!(Transform::Negate.apply(&coefficients), [8.0, 6.0, 1.0]);
+        // roots 6 and 12
+        assert_eq!(
+            Transform::Scale(3.0).apply(&coefficients),
+            [72.0, -18.0, 1.0]
+        );
+        // the root at 0 of X^2 - X is lost
+        assert_eq!(Transform::Reciprocal.apply(&[0.0, -1.0, 1.0]), [1.0, -1.0]);
+        assert_eq!(Transform::Scale(0.0).apply(&[-5.0, 1.0]), [0.0, 1.0]);
+        assert_eq!(Transform::Scale(0.5).roots(), "0.5r");
+    }
+}
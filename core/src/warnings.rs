@@ -0,0 +1,193 @@
+use crate::expr::{self, Limits, Token};
+use crate::float;
+
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+/// Above this a coefficient squared, as in the discriminant, overflows an
+/// `f32`.
+const OVERFLOW_RISK: f32 = 1e18;
+
+/// What is suspicious about an equation that still parses.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub enum Category {
+    /// The highest powers cancel out between the sides.
+    DegenerateLead,
+    /// A number has more digits than an `f32` keeps.
+    PrecisionLoss,
+    /// A coefficient is large enough for solving to overflow.
+    OverflowRisk,
+    /// A term written with a zero coefficient.
+    ZeroTerm,
+}
+
+pub const CATEGORIES: [(&str, Category); 4] = [
+    ("degenerate-lead", Category::DegenerateLead),
+    ("precision-loss", Category::PrecisionLoss),
+    ("overflow-risk", Category::OverflowRisk),
+    ("zero-term", Category::ZeroTerm),
+];
+
+impl Category {
+    /// Stable like `ParseError::code`, numbered apart from the errors.
+    pub fn code(self) -> &'static str {
+        match self {
+            Category::DegenerateLead => "W001",
+            Category::PrecisionLoss => "W002",
+            Category::OverflowRisk => "W003",
+            Category::ZeroTerm => "W004",
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        CATEGORIES
+            .iter()
+            .find(|(_, category)| *category == self)
+            .map_or("", |(name, _)| name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Warning {
+    pub category: Category,
+    pub message: String,
+}
+
+impl Warning {
+    fn new(category: Category, message: String) -> Warning {
+        Warning { category, message }
+    }
+
+    /// `warning[W001]: message [category]`, or `error` when warnings are
+    /// denied.
+    pub fn render(&self, deny: bool) -> String {
+        let level = if deny { "error" } else { "warning" };
+        format!(
+            "{}[{}]: {} [{}]",
+            level,
+            self.category.code(),
+            self.message,
+            self.category.name()
+        )
+    }
+}
+
+/// `{"code": "W001", "category": "degenerate-lead", "message": ...}`.
+impl Serialize for Warning {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut warning = serializer.serialize_struct("Warning", 3)?;
+        warning.serialize_field("code", self.category.code())?;
+        warning.serialize_field("category", self.category.name())?;
+        warning.serialize_field("message", &self.message)?;
+        warning.end()
+    }
+}
+
+fn degree(coefficients: &[f32]) -> i32 {
+    coefficients.len() as i32 - 1
+}
+
+/// The warnings for an equation and its reduced coefficients, in the order
+/// of the categories, the equation being parsed within `limits`.
+pub fn check(line: &str, coefficients: &[f32], limits: &Limits) -> Vec<Warning> {
+    let mut warnings = vec![];
+    if let Ok((left, right)) = expr::parse_equation_with(line, limits) {
+        let written = match (left.expand(), right.expand()) {
+            (Ok(left), Ok(right)) => degree(&left).max(degree(&right)),
+            _ => -1,
+        };
+        // an identity, every term cancelling, is not a degree that drops
+        let zero = coefficients.iter().all(|&c| c == 0.0);
+        if written > degree(coefficients).max(0) && !zero {
+            let message = format!(
+                "the X^{} terms cancel out, the degree drops to {}",
+                written,
+                degree(coefficients).max(0)
+            );
+            warnings.push(Warning::new(Category::DegenerateLead, message));
+        }
+    }
+    let chars: Vec<char> = line.chars().collect();
+    let (tokens, _) = expr::tokenize(line);
+    for (token, span) in &tokens {
+        if let Token::Number(value) = token {
+            let literal: String = chars[span.clone()].iter().collect();
+            let Ok(exact) = literal.parse::<f64>() else {
+                continue;
+            };
+            if exact.to_string() != float::shortest(*value) {
+                let message = format!(
+                    "{} is rounded to {} in single precision",
+                    literal,
+                    float::shortest(*value)
+                );
+                warnings.push(Warning::new(Category::PrecisionLoss, message));
+            }
+        }
+    }
+    if coefficients.iter().any(|c| c.abs() > OVERFLOW_RISK) {
+        let message = format!(
+            "a coefficient is above {}, solving may overflow",
+            float::shortest_scientific(OVERFLOW_RISK)
+        );
+        warnings.push(Warning::new(Category::OverflowRisk, message));
+    }
+    for (i, window) in tokens.windows(3).enumerate() {
+        let term = match window {
+            [(Token::Number(zero), _), (Token::Star, _), (Token::X, _)] if *zero == 0.0 => Some(i),
+            [(Token::Number(zero), _), (Token::X, _), _] if *zero == 0.0 => Some(i),
+            _ => None,
+        };
+        if let Some(i) = term {
+            let message = format!(
+                "the term at column {} has a zero coefficient and is ignored",
+                tokens[i].1.start + 1
+            );
+            warnings.push(Warning::new(Category::ZeroTerm, message));
+        }
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn categories(line: &str) -> Vec<Category> {
+        let (left, right) = expr::parse_equation(line).unwrap();
+        let coefficients = expr::Expr::Sub(Box::new(left), Box::new(right))
+            .expand()
+            .unwrap();
+        check(line, &coefficients, &expr::DEFAULT_LIMITS)
+            .iter()
+            .map(|warning| warning.category)
+            .collect()
+    }
+
+    #[test]
+    fn each_category() {
+        assert!(categories("5 * X^0 + 4 * X^1 - 9.3 * X^2 = 1 * X^0").is_empty());
+        assert_eq!(categories("X^2 + X = X^2 + 1"), [Category::DegenerateLead]);
+        assert!(categories("X = X").is_empty());
+        assert!(categories("X^2 + 1 = X^2 + 1").is_empty());
+        assert_eq!(
+            categories("3.14159265358979 * X = 1"),
+            [Category::PrecisionLoss]
+        );
+        assert_eq!(
+            categories("100000000000000000000 * X = 1"),
+            [Category::OverflowRisk]
+        );
+        assert_eq!(categories("0 * X^3 + X = 1"), [Category::ZeroTerm]);
+    }
+
+    #[test]
+    fn rendered_with_category() {
+        let warning = &check("0 * X^2 + X = 1", &[-1.0, 1.0], &expr::DEFAULT_LIMITS)[0];
+        assert_eq!(
+            warning.render(false),
+            "warning[W004]: the term at column 1 has a zero coefficient and is ignored [zero-term]"
+        );
+        assert!(warning.render(true).starts_with("error[W004]: "));
+    }
+}
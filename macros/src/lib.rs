@@ -0,0 +1,137 @@
+//! `poly!`, equations parsed when the crate is built.
+
+use computor_parse::{diagnostics, expr, ParseError};
+use proc_macro::{Literal, TokenStream, TokenTree};
+
+/// The text of a string literal, escapes resolved.
+fn string_literal(literal: &Literal) -> Option<String> {
+    literal_text(&literal.to_string())
+}
+
+/// The value of a string literal from its source, `"..."` with the escapes
+/// of Rust or raw `r#"..."#`. None for other literals.
+fn literal_text(source: &str) -> Option<String> {
+    if let Some(raw) = source.strip_prefix('r') {
+        let hashes = raw.len() - raw.trim_start_matches('#').len();
+        let quoted = raw.get(hashes..raw.len().checked_sub(hashes)?)?;
+        let text = quoted.strip_prefix('"')?.strip_suffix('"')?;
+        return Some(String::from(text));
+    }
+    unescape(source.strip_prefix('"')?.strip_suffix('"')?)
+}
+
+fn unescape(text: &str) -> Option<String> {
+    let mut unescaped = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next()? {
+            'n' => unescaped.push('\n'),
+            'r' => unescaped.push('\r'),
+            't' => unescaped.push('\t'),
+            '0' => unescaped.push('\0'),
+            c @ ('\\' | '\'' | '"') => unescaped.push(c),
+            'x' => {
+                let digits: String = chars.by_ref().take(2).collect();
+                let code = u8::from_str_radix(&digits, 16)
+                    .ok()
+                    .filter(|&code| code <= 0x7f)?;
+                unescaped.push(code as char);
+            }
+            'u' if chars.next()? == '{' => {
+                let digits: String = chars.by_ref().take_while(|&c| c != '}').collect();
+                let code = u32::from_str_radix(&digits.replace('_', ""), 16).ok()?;
+                unescaped.push(char::from_u32(code)?);
+            }
+            // the line continues after the leading whitespace of the next
+            '\n' => while chars.next_if(|c| c.is_whitespace()).is_some() {},
+            _ => return None,
+        }
+    }
+    Some(unescaped)
+}
+
+/// The coefficients of the equation, by ascending degree, or the rendered
+/// diagnostics of why it does not parse.
+fn coefficients(line: &str) -> Result<Vec<f32>, String> {
+    let (left, right) = expr::check_equation(line).map_err(|diagnostics| {
+        diagnostics::render(line, &diagnostics) + "Error parsing the polynomial equation"
+    })?;
+    let coefficients = expr::Expr::Sub(Box::new(left), Box::new(right))
+        .expand()
+        .map_err(|error: ParseError| {
+            format!("Error {} parsing the polynomial equation", error.code())
+        })?;
+    match coefficients.iter().all(|c| c.is_finite()) {
+        true => Ok(coefficients),
+        false => Err(String::from("A coefficient overflows")),
+    }
+}
+
+fn compile_error(message: &str) -> TokenStream {
+    format!("compile_error!({:?})", message).parse().unwrap()
+}
+
+/// `poly!("5 * X^2 - 3 = 0")` is the `&'static [f32]` of the reduced
+/// equation by ascending degree, a constant for `Reduced::of` or
+/// `Poly::from_coefficients`, the typos reported when building like the
+/// CLI reports them.
+#[proc_macro]
+pub fn poly(input: TokenStream) -> TokenStream {
+    let tokens: Vec<TokenTree> = input.into_iter().collect();
+    let line = match &tokens[..] {
+        [TokenTree::Literal(literal)] => string_literal(literal),
+        _ => None,
+    };
+    let Some(line) = line else {
+        return compile_error("poly! takes one string literal, the equation");
+    };
+    match coefficients(&line) {
+        Ok(coefficients) => {
+            let coefficients: Vec<String> = coefficients
+                .iter()
+                .map(|c| format!("{:?}_f32", c + 0.0))
+                .collect();
+            // typed even when empty
+            format!("(&[{}] as &'static [f32])", coefficients.join(", "))
+                .parse()
+                .unwrap()
+        }
+        Err(message) => compile_error(&message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn equations_at_build_time() {
+        assert_eq!(coefficients("5 * X^2 - 3 = 0"), Ok(vec![-3.0, 0.0, 5.0]));
+        assert_eq!(coefficients("X = X"), Ok(vec![]));
+        let error = coefficients("5 * X^2 + = 0").unwrap_err();
+        assert!(error.ends_with("Error parsing the polynomial equation"));
+        let error = coefficients("1 / (X - X) = 0").unwrap_err();
+        assert!(error.starts_with("error[E006]: division by zero"));
+    }
+
+    #[test]
+    fn literals_follow_rust_escapes() {
+        assert_eq!(literal_text(r#""X\t= 1""#).as_deref(), Some("X\t= 1"));
+        assert_eq!(literal_text(r#"r"X = 1""#).as_deref(), Some("X = 1"));
+        assert_eq!(
+            literal_text(r##"r#"X = "1""#"##).as_deref(),
+            Some("X = \"1\"")
+        );
+        assert_eq!(literal_text(r#""\x58 = \u{31}""#).as_deref(), Some("X = 1"));
+        assert_eq!(literal_text("\"X = \\\n    1\"").as_deref(), Some("X = 1"));
+        // JSON escapes that Rust does not have, and other literals
+        assert_eq!(literal_text(r#""X = \/1""#), None);
+        assert_eq!(literal_text(r#""\u0058 = 1""#), None);
+        assert_eq!(literal_text("1.5"), None);
+        assert_eq!(literal_text("b\"X = 1\""), None);
+    }
+}
@@ -0,0 +1,184 @@
+use crate::ParseError;
+use std::ops::Range;
+
+/// A parse error located in the input line. Spans count characters, not
+/// bytes, so they can be used directly to underline the input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub error: ParseError,
+    pub message: String,
+    pub span: Range<usize>,
+    pub help: Option<String>,
+}
+
+/// Characters that are not part of the grammar but that users often type,
+/// with what they most likely meant.
+const LOOKALIKES: &[(char, &str)] = &[
+    ('x', "the unknown is written with an uppercase `X`"),
+    (
+        '√',
+        "roots are not supported, only integer powers such as `X^2`",
+    ),
+    ('²', "did you mean `^2`?"),
+    ('³', "did you mean `^3`?"),
+    ('×', "did you mean `*`?"),
+    ('·', "did you mean `*`?"),
+    ('−', "did you mean `-`?"),
+    (',', "use `.` as the decimal separator"),
+];
+
+impl Diagnostic {
+    pub fn new(error: ParseError, message: String, span: Range<usize>) -> Diagnostic {
+        Diagnostic {
+            error,
+            message,
+            span,
+            help: None,
+        }
+    }
+}
+
+/// `{"errors": [...]}`, each with its code, message and character span, for
+/// the JSON output.
+pub fn to_json(diagnostics: &[Diagnostic]) -> String {
+    let errors: Vec<serde_json::Value> = diagnostics
+        .iter()
+        .map(|diagnostic| {
+            serde_json::json!({
+                "code": diagnostic.error.code(),
+                "message": diagnostic.message,
+                "start": diagnostic.span.start,
+                "end": diagnostic.span.end,
+            })
+        })
+        .collect();
+    serde_json::to_string_pretty(&serde_json::json!({ "errors": errors })).unwrap_or_default()
+        + "\n"
+}
+
+/// Guesses what the user meant from the characters around the span. Takes
+/// the line already split into characters, so that suggesting for every
+/// diagnostic of a line stays linear.
+pub fn suggest(chars: &[char], span: &Range<usize>) -> Option<String> {
+    let before: String = chars[..span.start.min(chars.len())]
+        .iter()
+        .rev()
+        .skip_while(|c| **c == ' ')
+        .take_while(|c| c.is_ascii_digit() || **c == '.' || **c == '^' || **c == 'X')
+        .collect::<Vec<&char>>()
+        .into_iter()
+        .rev()
+        .collect();
+    if before.ends_with('^') {
+        return Some(String::from("exponent missing after `^`"));
+    }
+    let c = *chars.get(span.start)?;
+    let digits: String = chars[span.start + 1..]
+        .iter()
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    if c == 'x' && (!digits.is_empty() || !before.is_empty()) {
+        let coefficient = if before.is_empty() {
+            String::new()
+        } else {
+            format!("{} * ", before)
+        };
+        let power = if digits.is_empty() {
+            String::new()
+        } else {
+            format!("^{}", digits)
+        };
+        return Some(format!("did you mean `{}X{}`?", coefficient, power));
+    }
+    if c.is_ascii_digit() && before.ends_with('X') {
+        let exponent: String = chars[span.start..]
+            .iter()
+            .take_while(|c| c.is_ascii_digit())
+            .collect();
+        return Some(format!("did you mean `X^{}`?", exponent));
+    }
+    LOOKALIKES
+        .iter()
+        .find(|(lookalike, _)| *lookalike == c)
+        .map(|(_, help)| help.to_string())
+}
+
+/// Renders the diagnostics in the style of rustc, each one underlining its
+/// span in the input.
+pub fn render(line: &str, diagnostics: &[Diagnostic]) -> String {
+    let mut output = String::new();
+    for diagnostic in diagnostics {
+        let width = diagnostic
+            .span
+            .end
+            .saturating_sub(diagnostic.span.start)
+            .max(1);
+        output += &format!(
+            "error[{}]: {}\n",
+            diagnostic.error.code(),
+            diagnostic.message
+        );
+        output += "  |\n";
+        output += &format!("  | {}\n", line);
+        output += &format!(
+            "  | {}{}\n",
+            " ".repeat(diagnostic.span.start),
+            "^".repeat(width)
+        );
+        if let Some(help) = &diagnostic.help {
+            output += &format!("  = help: {}\n", help);
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn suggest(line: &str, span: &Range<usize>) -> Option<String> {
+        super::suggest(&line.chars().collect::<Vec<char>>(), span)
+    }
+
+    #[test]
+    fn suggest_common_typos() {
+        assert_eq!(suggest("x2 = 0", &(0..1)).unwrap(), "did you mean `X^2`?");
+        assert_eq!(suggest("5x = 0", &(1..2)).unwrap(), "did you mean `5 * X`?");
+        assert_eq!(suggest("X2 = 0", &(1..2)).unwrap(), "did you mean `X^2`?");
+        assert_eq!(
+            suggest("X^ = 0", &(3..4)).unwrap(),
+            "exponent missing after `^`"
+        );
+        assert_eq!(
+            suggest("X^", &(2..3)).unwrap(),
+            "exponent missing after `^`"
+        );
+        assert!(suggest("√X = 2", &(0..1)).unwrap().starts_with("roots"));
+        assert_eq!(suggest("X + ) = 2", &(4..5)), None);
+    }
+
+    #[test]
+    fn errors_as_json() {
+        let diagnostic = Diagnostic::new(
+            ParseError::EqualSignError,
+            String::from("expected `=` between the two sides"),
+            3..4,
+        );
+        let json: serde_json::Value = serde_json::from_str(&to_json(&[diagnostic])).unwrap();
+        assert_eq!(json["errors"][0]["code"], "E001");
+        assert_eq!(json["errors"][0]["end"], 4);
+    }
+
+    #[test]
+    fn render_underlines_span() {
+        let diagnostic = Diagnostic::new(
+            ParseError::ParseNumError,
+            String::from("invalid number `1.2.3`"),
+            4..9,
+        );
+        assert_eq!(
+            render("2 + 1.2.3 = 0", &[diagnostic]),
+            "error[E002]: invalid number `1.2.3`\n  |\n  | 2 + 1.2.3 = 0\n  |     ^^^^^\n"
+        );
+    }
+}
@@ -0,0 +1,36 @@
+/// Why an equation does not parse, each with a stable code.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum ParseError {
+    EqualSignError,
+    ParseNumError,
+    SyntaxError,
+    InvalidExponent,
+    NegativeExponent,
+    DivisionByZero,
+    InvalidDivisor,
+    StrictSyntaxError,
+    /// Over one of the `expr::Limits`.
+    LimitExceeded,
+    /// A coefficient past the range of `f32`.
+    Overflow,
+}
+
+impl ParseError {
+    /// Stable across releases, for frontends that translate the messages.
+    /// New errors take the next free number.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ParseError::EqualSignError => "E001",
+            ParseError::ParseNumError => "E002",
+            ParseError::SyntaxError => "E003",
+            ParseError::InvalidExponent => "E004",
+            ParseError::NegativeExponent => "E005",
+            ParseError::DivisionByZero => "E006",
+            ParseError::InvalidDivisor => "E007",
+            ParseError::StrictSyntaxError => "E008",
+            ParseError::LimitExceeded => "E009",
+            ParseError::Overflow => "E010",
+        }
+    }
+}
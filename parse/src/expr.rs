@@ -0,0 +1,1525 @@
+use crate::diagnostics::{suggest, Diagnostic};
+use crate::float;
+use crate::ParseError;
+use std::fmt;
+use std::ops::Range;
+
+/// A sum this small next to its terms is rounding error, see `add`.
+const CANCELLATION: f32 = 4.0 * f32::EPSILON;
+
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Token {
+    Number(f32),
+    X,
+    Y,
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Caret,
+    LeftParen,
+    RightParen,
+    Equal,
+    Unknown(char),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Expr {
+    Num(f32),
+    Var,
+    /// The second variable, only parsed for equations in two variables.
+    VarY,
+    Neg(Box<Expr>),
+    Add(Box<Expr>, Box<Expr>),
+    Sub(Box<Expr>, Box<Expr>),
+    Mul(Box<Expr>, Box<Expr>),
+    Div(Box<Expr>, Box<Expr>),
+    Pow(Box<Expr>, Box<Expr>),
+}
+
+/// Bounds on the input, checked before and while parsing so that untrusted
+/// lines cannot exhaust the memory or the stack. The `_with` parsing
+/// functions take them, the others use `DEFAULT_LIMITS`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Limits {
+    /// In characters.
+    pub length: usize,
+    /// The `+` and `-` separated terms, counted over both sides.
+    pub terms: usize,
+    /// Parentheses, signs and exponents nested in one another.
+    pub depth: usize,
+    /// Of the expanded polynomial and of every step of its expansion, and
+    /// the largest exponent, checked before anything is expanded.
+    pub degree: usize,
+}
+
+pub const DEFAULT_LIMITS: Limits = Limits {
+    length: 10_000,
+    terms: 1_000,
+    depth: 64,
+    degree: 1_000,
+};
+
+/// Splits the line into tokens with their character span. Consecutive signs
+/// are folded into one, negative when there is an odd number of `-`.
+/// Percents like `50%` and mixed numbers like `1 1/2` are read as the
+/// number they stand for, which the grammar of the subject does not allow.
+/// Malformed numbers and those too large for `f32` are reported and
+/// replaced by zero so that parsing can go on; unknown characters are left for the parser to report.
+pub fn tokenize(line: &str) -> (Vec<(Token, Range<usize>)>, Vec<Diagnostic>) {
+    let chars: Vec<char> = line.chars().collect();
+    let mut tokens = vec![];
+    let mut diagnostics = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        let token = match chars[i] {
+            ' ' => {
+                i += 1;
+                continue;
+            }
+            c if c.is_ascii_digit() || c == '.' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                let number: String = chars[start..i].iter().collect();
+                let mut value = match number.parse::<f32>() {
+                    Ok(value) if value.is_infinite() => {
+                        diagnostics.push(Diagnostic::new(
+                            ParseError::Overflow,
+                            format!("the number `{}` is too large", number),
+                            start..i,
+                        ));
+                        0.0
+                    }
+                    Ok(value) => value,
+                    Err(_) => {
+                        diagnostics.push(Diagnostic::new(
+                            ParseError::ParseNumError,
+                            format!("invalid number `{}`", number),
+                            start..i,
+                        ));
+                        0.0
+                    }
+                };
+                if chars.get(i) == Some(&'%') {
+                    value /= 100.0;
+                    i += 1;
+                } else if let Some((fraction, end)) = proper_fraction(&chars, &number, i) {
+                    value += fraction;
+                    i = end;
+                }
+                tokens.push((Token::Number(value), start..i));
+                continue;
+            }
+            '+' | '-' => {
+                // Fold a whole chain like `- -+3` into a single sign
+                let start = i;
+                let mut negative = false;
+                let mut end = i;
+                while i < chars.len() && matches!(chars[i], '+' | '-' | ' ') {
+                    if chars[i] != ' ' {
+                        negative ^= chars[i] == '-';
+                        end = i + 1;
+                    }
+                    i += 1;
+                }
+                let sign = if negative { Token::Minus } else { Token::Plus };
+                tokens.push((sign, start..end));
+                continue;
+            }
+            'X' => Token::X,
+            'Y' => Token::Y,
+            '*' => Token::Star,
+            '/' => Token::Slash,
+            '^' => Token::Caret,
+            '(' => Token::LeftParen,
+            ')' => Token::RightParen,
+            '=' => Token::Equal,
+            c => Token::Unknown(c),
+        };
+        tokens.push((token, i..i + 1));
+        i += 1;
+    }
+    (tokens, diagnostics)
+}
+
+/// The `n/d` with `n < d` after the whole part of a mixed number, when the
+/// whole part ending at `i` is an integer followed by spaces, with the
+/// value and the end of the fraction.
+fn proper_fraction(chars: &[char], whole: &str, i: usize) -> Option<(f32, usize)> {
+    let integer = |from: usize| {
+        let end = (from..chars.len())
+            .find(|&j| !chars[j].is_ascii_digit())
+            .unwrap_or(chars.len());
+        let digits: String = chars[from..end].iter().collect();
+        digits.parse::<u32>().ok().map(|n| (n, end))
+    };
+    if !whole.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let from = (i..chars.len()).find(|&j| chars[j] != ' ')?;
+    if from == i {
+        return None;
+    }
+    let (numerator, slash) = integer(from)?;
+    if chars.get(slash) != Some(&'/') {
+        return None;
+    }
+    let (denominator, end) = integer(slash + 1)?;
+    if numerator >= denominator || chars.get(end) == Some(&'.') {
+        return None;
+    }
+    Some((numerator as f32 / denominator as f32, end))
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Token::Number(number) => write!(f, "{}", float::shortest(*number)),
+            Token::X => write!(f, "X"),
+            Token::Y => write!(f, "Y"),
+            Token::Plus => write!(f, "+"),
+            Token::Minus => write!(f, "-"),
+            Token::Star => write!(f, "*"),
+            Token::Slash => write!(f, "/"),
+            Token::Caret => write!(f, "^"),
+            Token::LeftParen => write!(f, "("),
+            Token::RightParen => write!(f, ")"),
+            Token::Equal => write!(f, "="),
+            Token::Unknown(c) => write!(f, "{}", c),
+        }
+    }
+}
+
+/// Recursive descent over the grammar
+///
+/// ```text
+/// equation   := expression '=' expression
+/// expression := term (('+' | '-') term)*
+/// term       := unary (('*' | '/') unary | implicit product)*
+/// unary      := ('+' | '-') unary | power
+/// power      := primary ('^' unary)?
+/// primary    := number | 'X' | 'Y' | '(' expression ')'
+/// ```
+///
+/// where an implicit product is a factor starting with `X`, `Y` or `(`, as
+/// in `4X` or `2(X + 1)`. Divisors and exponents must reduce to constants,
+/// and `Y` is only accepted when parsing in two variables.
+///
+/// Errors are collected rather than returned at once: a term that fails to
+/// parse is recorded, skipped up to the next top-level `+`, `-` or `=`, and
+/// parsing resumes from there.
+struct Parser {
+    tokens: Vec<(Token, Range<usize>)>,
+    position: usize,
+    end: usize,
+    diagnostics: Vec<Diagnostic>,
+    two_variables: bool,
+    limits: Limits,
+    depth: usize,
+}
+
+impl Parser {
+    /// A line over the length or term limit is not tokenized at all: the
+    /// parser starts at its end with the single diagnostic.
+    fn within(line: &str, limits: Limits) -> Parser {
+        let end = line.chars().count();
+        let mut parser = Parser {
+            tokens: vec![],
+            position: 0,
+            end,
+            diagnostics: vec![],
+            two_variables: false,
+            limits,
+            depth: 0,
+        };
+        if end > limits.length {
+            let message = format!("the input is longer than {} characters", limits.length);
+            parser.diagnostics.push(Diagnostic::new(
+                ParseError::LimitExceeded,
+                message,
+                limits.length..end,
+            ));
+            return parser;
+        }
+        let (tokens, diagnostics) = tokenize(line);
+        let terms = 1 + tokens
+            .iter()
+            .filter(|(token, _)| matches!(token, Token::Plus | Token::Minus))
+            .count();
+        if terms > limits.terms {
+            let message = format!("the input has more than {} terms", limits.terms);
+            parser
+                .diagnostics
+                .push(Diagnostic::new(ParseError::LimitExceeded, message, 0..end));
+            return parser;
+        }
+        parser.tokens = tokens;
+        parser.diagnostics = diagnostics;
+        parser
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(token, _)| token)
+    }
+
+    fn advance(&mut self) -> Option<Token> {
+        let token = self.peek().cloned();
+        self.position += 1;
+        token
+    }
+
+    fn span(&self) -> Range<usize> {
+        match self.tokens.get(self.position) {
+            Some((_, span)) => span.clone(),
+            None => self.end..self.end + 1,
+        }
+    }
+
+    fn error(&mut self, error: ParseError, message: String) -> ParseError {
+        let span = self.span();
+        self.diagnostics
+            .push(Diagnostic::new(error.clone(), message, span));
+        error
+    }
+
+    /// Reports an invalid operand spanning from `start` to the last token
+    /// parsed, unless an error was already found inside the operand.
+    fn report(&mut self, reported: usize, error: ParseError, message: &str, start: usize) {
+        if self.diagnostics.len() == reported {
+            let end = self.tokens[self.position - 1].1.end;
+            self.diagnostics
+                .push(Diagnostic::new(error, String::from(message), start..end));
+        }
+    }
+
+    fn unexpected(&mut self, expected: &str) -> ParseError {
+        let message = match self.peek() {
+            Some(Token::Unknown(c)) => format!("unexpected character `{}`", c),
+            Some(Token::Y) if !self.two_variables => String::from("unexpected character `Y`"),
+            Some(token) => format!("expected {}, found `{}`", expected, token),
+            None => format!("expected {}, found end of input", expected),
+        };
+        let conic = self.peek() == Some(&Token::Y) && !self.two_variables;
+        let error = self.error(ParseError::SyntaxError, message);
+        if let (true, Some(diagnostic)) = (conic, self.diagnostics.last_mut()) {
+            diagnostic.help = Some(String::from(
+                "an equation in X and Y is classified by `./computor conic`",
+            ));
+        }
+        error
+    }
+
+    fn synchronize(&mut self) {
+        let mut depth = 0;
+        while let Some(token) = self.peek() {
+            match token {
+                Token::LeftParen => depth += 1,
+                Token::RightParen if depth > 0 => depth -= 1,
+                Token::Plus | Token::Minus if depth == 0 => return,
+                Token::Equal => return,
+                _ => {}
+            }
+            self.advance();
+        }
+    }
+
+    /// Records a limit diagnostic over the whole line when expanding `expr`
+    /// would go past the degree limit.
+    fn check_degree(&mut self, expr: &Expr) {
+        if expr.degree_within(self.limits.degree).is_none() {
+            let message = format!("the degree would be over {}", self.limits.degree);
+            self.diagnostics.push(Diagnostic::new(
+                ParseError::LimitExceeded,
+                message,
+                0..self.end,
+            ));
+        }
+    }
+
+    /// Records an overflow diagnostic over the whole line when a coefficient
+    /// of the expansion of `expr` is past the range of `f32`, as in
+    /// `10^39 * X`.
+    fn check_overflow(&mut self, expr: &Expr) {
+        let expanded = match self.two_variables {
+            true => expr.expand_in_two_variables().map(drop),
+            false => expr.expand().map(drop),
+        };
+        if expanded == Err(ParseError::Overflow) {
+            self.diagnostics.push(Diagnostic::new(
+                ParseError::Overflow,
+                String::from("a coefficient is too large once expanded"),
+                0..self.end,
+            ));
+        }
+    }
+
+    fn equation(&mut self) -> (Expr, Expr) {
+        let sides = self.sides();
+        self.check_degree(&sides.0);
+        self.check_degree(&sides.1);
+        // only expanded once everything else is right
+        if self.diagnostics.is_empty() {
+            let (left, right) = (sides.0.clone(), sides.1.clone());
+            self.check_overflow(&Expr::Sub(Box::new(left), Box::new(right)));
+        }
+        sides
+    }
+
+    fn sides(&mut self) -> (Expr, Expr) {
+        let left = self.recovering_expression();
+        if self.peek() != Some(&Token::Equal) {
+            self.error(
+                ParseError::EqualSignError,
+                String::from("expected `=` between the two sides"),
+            );
+            return (left, Expr::Num(0.0));
+        }
+        self.advance();
+        let right = self.recovering_expression();
+        while self.peek() == Some(&Token::Equal) {
+            self.error(
+                ParseError::EqualSignError,
+                String::from("an equation has exactly one `=`"),
+            );
+            self.advance();
+            self.recovering_expression();
+        }
+        (left, right)
+    }
+
+    fn recovering_expression(&mut self) -> Expr {
+        let mut expr = self.recovering_term();
+        loop {
+            expr = match self.peek() {
+                Some(Token::Plus) => {
+                    self.advance();
+                    Expr::Add(Box::new(expr), Box::new(self.recovering_term()))
+                }
+                Some(Token::Minus) => {
+                    self.advance();
+                    Expr::Sub(Box::new(expr), Box::new(self.recovering_term()))
+                }
+                None | Some(Token::Equal) => return expr,
+                Some(_) => {
+                    self.unexpected("an operator");
+                    self.synchronize();
+                    expr
+                }
+            }
+        }
+    }
+
+    fn recovering_term(&mut self) -> Expr {
+        match self.term() {
+            Ok(expr) => expr,
+            Err(_) => {
+                self.synchronize();
+                Expr::Num(0.0)
+            }
+        }
+    }
+
+    fn expression(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.term()?;
+        while let Some(token) = self.peek() {
+            expr = match token {
+                Token::Plus => {
+                    self.advance();
+                    Expr::Add(Box::new(expr), Box::new(self.term()?))
+                }
+                Token::Minus => {
+                    self.advance();
+                    Expr::Sub(Box::new(expr), Box::new(self.term()?))
+                }
+                _ => break,
+            }
+        }
+        Ok(expr)
+    }
+
+    fn term(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.unary()?;
+        while let Some(token) = self.peek() {
+            expr = match token {
+                Token::Star => {
+                    self.advance();
+                    Expr::Mul(Box::new(expr), Box::new(self.unary()?))
+                }
+                Token::Slash => {
+                    self.advance();
+                    let start = self.span().start;
+                    let reported = self.diagnostics.len();
+                    let divisor = self.unary()?;
+                    if let Err(error) = divisor.constant_divisor() {
+                        let message = match error {
+                            ParseError::DivisionByZero => "division by zero",
+                            _ => "only division by a constant is supported",
+                        };
+                        self.report(reported, error, message, start);
+                    }
+                    Expr::Div(Box::new(expr), Box::new(divisor))
+                }
+                Token::X | Token::LeftParen => Expr::Mul(Box::new(expr), Box::new(self.power()?)),
+                Token::Y if self.two_variables => {
+                    Expr::Mul(Box::new(expr), Box::new(self.power()?))
+                }
+                _ => break,
+            }
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Expr, ParseError> {
+        if self.depth == self.limits.depth {
+            let message = format!("nested deeper than {} levels", self.limits.depth);
+            return Err(self.error(ParseError::LimitExceeded, message));
+        }
+        self.depth += 1;
+        let expr = self.nested_unary();
+        self.depth -= 1;
+        expr
+    }
+
+    fn nested_unary(&mut self) -> Result<Expr, ParseError> {
+        match self.peek() {
+            Some(Token::Plus) => {
+                self.advance();
+                self.unary()
+            }
+            Some(Token::Minus) => {
+                self.advance();
+                Ok(Expr::Neg(Box::new(self.unary()?)))
+            }
+            _ => self.power(),
+        }
+    }
+
+    fn power(&mut self) -> Result<Expr, ParseError> {
+        let base = self.primary()?;
+        if self.peek() != Some(&Token::Caret) {
+            return Ok(base);
+        }
+        self.advance();
+        let start = self.span().start;
+        let reported = self.diagnostics.len();
+        let exponent = self.unary()?;
+        // the exponent is expanded to read it
+        if exponent.degree_within(self.limits.degree).is_none() {
+            let message = format!("the degree would be over {}", self.limits.degree);
+            return Err(self.error(ParseError::LimitExceeded, message));
+        }
+        if let Err(error) = exponent.constant_exponent() {
+            let message = match error {
+                ParseError::NegativeExponent => "negative exponents are not supported",
+                _ => "the exponent must be a non-negative integer",
+            };
+            self.report(reported, error, message, start);
+        }
+        Ok(Expr::Pow(Box::new(base), Box::new(exponent)))
+    }
+
+    fn primary(&mut self) -> Result<Expr, ParseError> {
+        match self.peek() {
+            Some(Token::Number(number)) => {
+                let number = *number;
+                self.advance();
+                Ok(Expr::Num(number))
+            }
+            Some(Token::X) => {
+                self.advance();
+                Ok(Expr::Var)
+            }
+            Some(Token::Y) if self.two_variables => {
+                self.advance();
+                Ok(Expr::VarY)
+            }
+            Some(Token::LeftParen) => {
+                self.advance();
+                let expr = self.expression()?;
+                if self.peek() != Some(&Token::RightParen) {
+                    return Err(self.unexpected("`)`"));
+                }
+                self.advance();
+                Ok(expr)
+            }
+            _ => Err(self.unexpected("a number, `X` or `(`")),
+        }
+    }
+
+    fn finish<T>(mut self, line: &str, result: T) -> Result<T, Vec<Diagnostic>> {
+        if self.diagnostics.is_empty() {
+            return Ok(result);
+        }
+        if let Some(limit) = self
+            .diagnostics
+            .iter()
+            .find(|diagnostic| diagnostic.error == ParseError::LimitExceeded)
+        {
+            // the rest was skipped or cut short, only the limit is reliable
+            return Err(vec![limit.clone()]);
+        }
+        self.diagnostics
+            .sort_by_key(|diagnostic| diagnostic.span.start);
+        let chars: Vec<char> = line.chars().collect();
+        for diagnostic in self.diagnostics.iter_mut() {
+            if diagnostic.error == ParseError::SyntaxError && diagnostic.help.is_none() {
+                diagnostic.help = suggest(&chars, &diagnostic.span);
+            }
+        }
+        Err(self.diagnostics)
+    }
+}
+
+pub fn check_expression(line: &str) -> Result<Expr, Vec<Diagnostic>> {
+    check_expression_with(line, &DEFAULT_LIMITS)
+}
+
+pub fn check_expression_with(line: &str, limits: &Limits) -> Result<Expr, Vec<Diagnostic>> {
+    let mut parser = Parser::within(line, *limits);
+    let expr = parser.recovering_expression();
+    parser.check_degree(&expr);
+    if parser.diagnostics.is_empty() {
+        parser.check_overflow(&expr);
+    }
+    if parser.peek().is_some() {
+        parser.error(
+            ParseError::EqualSignError,
+            String::from("expected an expression, not an equation"),
+        );
+    }
+    parser.finish(line, expr)
+}
+
+/// Parses the whole equation, reporting every error found along the way.
+pub fn check_equation(line: &str) -> Result<(Expr, Expr), Vec<Diagnostic>> {
+    check_equation_with(line, &DEFAULT_LIMITS)
+}
+
+pub fn check_equation_with(line: &str, limits: &Limits) -> Result<(Expr, Expr), Vec<Diagnostic>> {
+    let mut parser = Parser::within(line, *limits);
+    let sides = parser.equation();
+    parser.finish(line, sides)
+}
+
+/// Checks that the equation sticks to the subject grammar, where each side
+/// is a sum of `a * X^p` terms with a number `a` and an integer `p`.
+pub fn check_strict(line: &str) -> Vec<Diagnostic> {
+    check_strict_with(line, &DEFAULT_LIMITS)
+}
+
+pub fn check_strict_with(line: &str, limits: &Limits) -> Vec<Diagnostic> {
+    let parser = Parser::within(line, *limits);
+    if let Err(diagnostics) = parser.finish(line, ()) {
+        if diagnostics[0].error == ParseError::LimitExceeded {
+            return diagnostics;
+        }
+    }
+    let (tokens, mut diagnostics) = tokenize(line);
+    let end = line.chars().count();
+    let chars: Vec<char> = line.chars().collect();
+    for (token, span) in &tokens {
+        let literal: String = chars[span.clone()].iter().collect();
+        if let (Token::Number(value), true) = (token, literal.contains(['%', ' '])) {
+            let mut diagnostic = Diagnostic::new(
+                ParseError::StrictSyntaxError,
+                format!("`{}` is only accepted outside of strict mode", literal),
+                span.clone(),
+            );
+            diagnostic.help = Some(format!("did you mean `{}`?", float::shortest(*value)));
+            diagnostics.push(diagnostic);
+        }
+    }
+    let sides: Vec<&[(Token, Range<usize>)]> =
+        tokens.split(|(token, _)| *token == Token::Equal).collect();
+    if sides.len() != 2 {
+        diagnostics.push(Diagnostic::new(
+            ParseError::EqualSignError,
+            String::from("expected exactly one `=`"),
+            0..end,
+        ));
+        return diagnostics;
+    }
+    for side in sides {
+        let mut terms = vec![];
+        let mut start = 0;
+        for (i, (token, _)) in side.iter().enumerate().skip(1) {
+            if matches!(token, Token::Plus | Token::Minus) {
+                terms.push(&side[start..i]);
+                start = i;
+            }
+        }
+        terms.push(&side[start..]);
+        for term in terms {
+            let body = match term.first() {
+                Some((Token::Plus | Token::Minus, _)) => &term[1..],
+                _ => term,
+            };
+            if let [(Token::Number(_), _), (Token::Star, _), (Token::X, _), (Token::Caret, _), (Token::Number(p), _)] =
+                body
+            {
+                if p.fract() == 0.0 {
+                    continue;
+                }
+            }
+            let span = match (body.first(), body.last()) {
+                (Some((_, first)), Some((_, last))) => first.start..last.end,
+                _ => term
+                    .last()
+                    .map_or(end..end + 1, |(_, span)| span.end..span.end + 1),
+            };
+            let text: String = line.chars().skip(span.start).take(span.len()).collect();
+            let mut diagnostic = Diagnostic::new(
+                ParseError::StrictSyntaxError,
+                String::from("expected a term of the form `a * X^p`"),
+                span,
+            );
+            if let Ok(Ok(coefficients)) = check_expression(&text).map(|expr| expr.expand()) {
+                let terms: Vec<(usize, &f32)> = coefficients
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, c)| **c != 0.0)
+                    .collect();
+                diagnostic.help = match terms.as_slice() {
+                    [] => Some(String::from("did you mean `0 * X^0`?")),
+                    [(p, c)] => Some(format!(
+                        "did you mean `{} * X^{}`?",
+                        float::shortest(**c),
+                        p
+                    )),
+                    _ => None,
+                };
+            }
+            diagnostics.push(diagnostic);
+        }
+    }
+    diagnostics.sort_by_key(|diagnostic| diagnostic.span.start);
+    diagnostics
+}
+
+/// Parses an equation in `X` and `Y`, reporting every error found.
+pub fn check_equation_in_two_variables(line: &str) -> Result<(Expr, Expr), Vec<Diagnostic>> {
+    check_equation_in_two_variables_with(line, &DEFAULT_LIMITS)
+}
+
+pub fn check_equation_in_two_variables_with(
+    line: &str,
+    limits: &Limits,
+) -> Result<(Expr, Expr), Vec<Diagnostic>> {
+    let mut parser = Parser::within(line, *limits);
+    parser.two_variables = true;
+    let sides = parser.equation();
+    parser.finish(line, sides)
+}
+
+pub fn parse_equation(line: &str) -> Result<(Expr, Expr), ParseError> {
+    parse_equation_with(line, &DEFAULT_LIMITS)
+}
+
+pub fn parse_equation_with(line: &str, limits: &Limits) -> Result<(Expr, Expr), ParseError> {
+    check_equation_with(line, limits).map_err(|diagnostics| diagnostics[0].error.clone())
+}
+
+/// The best reading of an equation still being typed, for a live preview.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Partial {
+    /// The reduced polynomial of the longest prefix that parses once its
+    /// parentheses are closed, a missing right side being 0.
+    pub coefficients: Vec<f32>,
+    /// What may come next after the last token.
+    pub expected: &'static str,
+    /// The whole line parses as it is.
+    pub complete: bool,
+}
+
+fn expected_after(token: Option<&Token>) -> &'static str {
+    match token {
+        None | Some(Token::Plus | Token::Minus | Token::Star | Token::Slash) => {
+            "a number, `X` or `(`"
+        }
+        Some(Token::LeftParen | Token::Equal) => "a number, `X`, `(` or a sign",
+        Some(Token::Caret) => "an exponent",
+        Some(Token::Number(_)) => "`*`, `X`, an operator or `=`",
+        Some(Token::X | Token::Y) => "`^`, an operator or `=`",
+        Some(Token::RightParen) => "`^`, an operator or `=`",
+        Some(Token::Unknown(_)) => "a valid character in place of the last one",
+    }
+}
+
+/// Tolerates the incomplete end of a line being typed, like `5 * X^2 + 3 *`
+/// or `(X - 1`: drops the trailing tokens until the rest parses with its
+/// parentheses closed, and tells what the last token may be followed by.
+pub fn parse_partial(line: &str) -> Partial {
+    let (tokens, _) = tokenize(line);
+    let chars: Vec<char> = line.chars().collect();
+    let reduce = |text: &str| {
+        let sides = match text.contains('=') {
+            true => check_equation(text).ok(),
+            false => check_expression(text)
+                .ok()
+                .map(|left| (left, Expr::Num(0.0))),
+        };
+        sides.and_then(|(left, right)| Expr::Sub(Box::new(left), Box::new(right)).expand().ok())
+    };
+    let complete = reduce(line).is_some();
+    let mut coefficients = vec![];
+    for cut in (1..=tokens.len()).rev() {
+        let prefix = &tokens[..cut];
+        let open = prefix.iter().fold(0i32, |depth, (token, _)| match token {
+            Token::LeftParen => depth + 1,
+            Token::RightParen => depth - 1,
+            _ => depth,
+        });
+        let mut text: String = chars[..prefix[cut - 1].1.end].iter().collect();
+        text.push_str(&")".repeat(open.max(0) as usize));
+        if prefix[cut - 1].0 == Token::Equal {
+            text.push('0');
+        }
+        if let Some(reduced) = reduce(&text) {
+            coefficients = reduced;
+            break;
+        }
+    }
+    Partial {
+        coefficients,
+        expected: expected_after(tokens.last().map(|(token, _)| token)),
+        complete,
+    }
+}
+
+/// Indented view of the parsed equation, one node per line.
+pub fn equation_tree(left: &Expr, right: &Expr) -> String {
+    let mut output = String::from("=\n");
+    left.write_tree(&mut output, "", false);
+    right.write_tree(&mut output, "", true);
+    output
+}
+
+/// The parsed equation as a Graphviz graph.
+pub fn equation_dot(left: &Expr, right: &Expr) -> String {
+    let mut output = String::from("digraph {\n    n0 [label=\"=\"];\n");
+    let mut next = 1;
+    for side in [left, right] {
+        let id = side.write_dot(&mut output, &mut next);
+        output += &format!("    n0 -> n{};\n", id);
+    }
+    output += "}\n";
+    output
+}
+
+impl Expr {
+    /// Flattens the expression into coefficients by ascending degree, an
+    /// overflow at any step being an error.
+    pub fn expand(&self) -> Result<Vec<f32>, ParseError> {
+        let coefficients = match self {
+            Expr::Num(number) => vec![*number],
+            Expr::Var => vec![0.0, 1.0],
+            Expr::VarY => return Err(ParseError::SyntaxError),
+            Expr::Neg(expr) => expr.expand()?.iter().map(|c| -c).collect(),
+            Expr::Add(left, right) => add(&left.expand()?, &right.expand()?),
+            Expr::Sub(left, right) => {
+                let right: Vec<f32> = right.expand()?.iter().map(|c| -c).collect();
+                add(&left.expand()?, &right)
+            }
+            Expr::Mul(left, right) => mul(&left.expand()?, &right.expand()?),
+            Expr::Div(left, right) => {
+                let divisor = right.constant_divisor()?;
+                left.expand()?.iter().map(|c| c / divisor).collect()
+            }
+            Expr::Pow(base, exponent) => {
+                let exponent = exponent.constant_exponent()?;
+                let base = base.expand()?;
+                (0..exponent).try_fold(vec![1.0], |acc, _| finite(mul(&acc, &base)))?
+            }
+        };
+        finite(trim(coefficients))
+    }
+
+    /// Flattens an expression in `X` and `Y` into coefficients indexed by
+    /// the power of `X`, then by the power of `Y`.
+    pub fn expand_in_two_variables(&self) -> Result<Vec<Vec<f32>>, ParseError> {
+        let coefficients = match self {
+            Expr::Num(number) => vec![vec![*number]],
+            Expr::Var => vec![vec![], vec![1.0]],
+            Expr::VarY => vec![vec![0.0, 1.0]],
+            Expr::Neg(expr) => map2(&expr.expand_in_two_variables()?, |c| -c),
+            Expr::Add(left, right) => add2(
+                &left.expand_in_two_variables()?,
+                &right.expand_in_two_variables()?,
+            ),
+            Expr::Sub(left, right) => {
+                let right = map2(&right.expand_in_two_variables()?, |c| -c);
+                add2(&left.expand_in_two_variables()?, &right)
+            }
+            Expr::Mul(left, right) => mul2(
+                &left.expand_in_two_variables()?,
+                &right.expand_in_two_variables()?,
+            ),
+            Expr::Div(left, right) => {
+                let divisor = right.constant_divisor()?;
+                map2(&left.expand_in_two_variables()?, |c| c / divisor)
+            }
+            Expr::Pow(base, exponent) => {
+                let exponent = exponent.constant_exponent()?;
+                let base = base.expand_in_two_variables()?;
+                (0..exponent).try_fold(vec![vec![1.0]], |acc, _| finite2(mul2(&acc, &base)))?
+            }
+        };
+        finite2(coefficients)
+    }
+
+    /// Evaluates at `X = x`. There is no value for `Y`, which evaluates to NaN.
+    pub fn eval(&self, x: f64) -> f64 {
+        match self {
+            Expr::Num(number) => *number as f64,
+            Expr::Var => x,
+            Expr::VarY => f64::NAN,
+            Expr::Neg(expr) => -expr.eval(x),
+            Expr::Add(left, right) => left.eval(x) + right.eval(x),
+            Expr::Sub(left, right) => left.eval(x) - right.eval(x),
+            Expr::Mul(left, right) => left.eval(x) * right.eval(x),
+            Expr::Div(left, right) => left.eval(x) / right.eval(x),
+            Expr::Pow(base, exponent) => base.eval(x).powi(exponent.eval(x) as i32),
+        }
+    }
+
+    /// The highest degree reached while expanding, none past `limit` or
+    /// with an exponent past it. Only the exponents are expanded, each
+    /// after its own degree is checked.
+    fn degree_within(&self, limit: usize) -> Option<usize> {
+        let degree = match self {
+            Expr::Num(_) => 0,
+            Expr::Var | Expr::VarY => 1,
+            Expr::Neg(expr) => expr.degree_within(limit)?,
+            Expr::Add(left, right) | Expr::Sub(left, right) => {
+                left.degree_within(limit)?.max(right.degree_within(limit)?)
+            }
+            Expr::Mul(left, right) => left.degree_within(limit)? + right.degree_within(limit)?,
+            Expr::Div(left, right) => {
+                right.degree_within(limit)?;
+                left.degree_within(limit)?
+            }
+            Expr::Pow(base, exponent) => {
+                exponent.degree_within(limit)?;
+                // an invalid exponent is reported on its own
+                let exponent = exponent.constant_exponent().unwrap_or(0) as usize;
+                if exponent > limit {
+                    return None;
+                }
+                base.degree_within(limit)?.checked_mul(exponent)?
+            }
+        };
+        (degree <= limit).then_some(degree)
+    }
+
+    fn constant_exponent(&self) -> Result<u32, ParseError> {
+        match self.expand()?.as_slice() {
+            [] => Ok(0),
+            [n] if n.fract() != 0.0 => Err(ParseError::InvalidExponent),
+            [n] if *n < 0.0 => Err(ParseError::NegativeExponent),
+            [n] => Ok(*n as u32),
+            _ => Err(ParseError::InvalidExponent),
+        }
+    }
+
+    fn constant_divisor(&self) -> Result<f32, ParseError> {
+        match self.expand()?.as_slice() {
+            [] => Err(ParseError::DivisionByZero),
+            [c] => Ok(*c),
+            _ => Err(ParseError::InvalidDivisor),
+        }
+    }
+
+    /// Symbolic derivative with respect to `X`, keeping any factored form.
+    pub fn derivative(&self) -> Expr {
+        match self {
+            Expr::Num(_) | Expr::VarY => Expr::Num(0.0),
+            Expr::Var => Expr::Num(1.0),
+            Expr::Neg(expr) => Expr::Neg(Box::new(expr.derivative())),
+            Expr::Add(left, right) => {
+                Expr::Add(Box::new(left.derivative()), Box::new(right.derivative()))
+            }
+            Expr::Sub(left, right) => {
+                Expr::Sub(Box::new(left.derivative()), Box::new(right.derivative()))
+            }
+            Expr::Mul(left, right) => Expr::Add(
+                Box::new(Expr::Mul(Box::new(left.derivative()), right.clone())),
+                Box::new(Expr::Mul(left.clone(), Box::new(right.derivative()))),
+            ),
+            Expr::Div(left, right) => Expr::Div(Box::new(left.derivative()), right.clone()),
+            Expr::Pow(base, exponent) => Expr::Mul(
+                Box::new(Expr::Mul(
+                    exponent.clone(),
+                    Box::new(Expr::Pow(
+                        base.clone(),
+                        Box::new(Expr::Sub(exponent.clone(), Box::new(Expr::Num(1.0)))),
+                    )),
+                )),
+                Box::new(base.derivative()),
+            ),
+        }
+    }
+
+    /// Removes the neutral elements and folds the constants left behind by
+    /// `derivative`.
+    pub fn simplify(self) -> Expr {
+        match self {
+            Expr::Neg(expr) => match expr.simplify() {
+                Expr::Num(number) => Expr::Num(-number),
+                Expr::Neg(expr) => *expr,
+                expr => Expr::Neg(Box::new(expr)),
+            },
+            Expr::Add(left, right) => match (left.simplify(), right.simplify()) {
+                (Expr::Num(a), Expr::Num(b)) => Expr::Num(a + b),
+                (Expr::Num(zero), expr) | (expr, Expr::Num(zero)) if zero == 0.0 => expr,
+                (left, Expr::Neg(right)) => Expr::Sub(Box::new(left), right),
+                (left, right) => Expr::Add(Box::new(left), Box::new(right)),
+            },
+            Expr::Sub(left, right) => match (left.simplify(), right.simplify()) {
+                (Expr::Num(a), Expr::Num(b)) => Expr::Num(a - b),
+                (expr, Expr::Num(0.0)) => expr,
+                (Expr::Num(0.0), expr) => Expr::Neg(Box::new(expr)).simplify(),
+                (left, right) => Expr::Sub(Box::new(left), Box::new(right)),
+            },
+            Expr::Mul(left, right) => match (left.simplify(), right.simplify()) {
+                (Expr::Num(a), Expr::Num(b)) => Expr::Num(a * b),
+                (Expr::Num(zero), _) | (_, Expr::Num(zero)) if zero == 0.0 => Expr::Num(0.0),
+                (Expr::Num(one), expr) | (expr, Expr::Num(one)) if one == 1.0 => expr,
+                (Expr::Num(a), Expr::Mul(b, expr)) if matches!(*b, Expr::Num(_)) => {
+                    Expr::Mul(Box::new(Expr::Mul(Box::new(Expr::Num(a)), b)), expr).simplify()
+                }
+                (expr, Expr::Num(number)) => Expr::Mul(Box::new(Expr::Num(number)), Box::new(expr)),
+                (left, right) => Expr::Mul(Box::new(left), Box::new(right)),
+            },
+            Expr::Div(left, right) => match (left.simplify(), right.simplify()) {
+                (Expr::Num(a), Expr::Num(b)) if b != 0.0 => Expr::Num(a / b),
+                (Expr::Num(0.0), _) => Expr::Num(0.0),
+                (expr, Expr::Num(1.0)) => expr,
+                (Expr::Mul(a, expr), Expr::Num(b)) if b != 0.0 && matches!(*a, Expr::Num(_)) => {
+                    Expr::Mul(Box::new(Expr::Div(a, Box::new(Expr::Num(b)))), expr).simplify()
+                }
+                (left, right) => Expr::Div(Box::new(left), Box::new(right)),
+            },
+            Expr::Pow(base, exponent) => match (base.simplify(), exponent.simplify()) {
+                (_, Expr::Num(0.0)) => Expr::Num(1.0),
+                (base, Expr::Num(1.0)) => base,
+                (base, exponent) => Expr::Pow(Box::new(base), Box::new(exponent)),
+            },
+            expr => expr,
+        }
+    }
+
+    fn label(&self) -> String {
+        match self {
+            Expr::Num(number) => float::shortest(*number),
+            Expr::Var => String::from("X"),
+            Expr::VarY => String::from("Y"),
+            Expr::Neg(_) => String::from("neg"),
+            Expr::Add(..) => String::from("+"),
+            Expr::Sub(..) => String::from("-"),
+            Expr::Mul(..) => String::from("*"),
+            Expr::Div(..) => String::from("/"),
+            Expr::Pow(..) => String::from("^"),
+        }
+    }
+
+    fn children(&self) -> Vec<&Expr> {
+        match self {
+            Expr::Num(_) | Expr::Var | Expr::VarY => vec![],
+            Expr::Neg(expr) => vec![expr],
+            Expr::Add(left, right)
+            | Expr::Sub(left, right)
+            | Expr::Mul(left, right)
+            | Expr::Div(left, right)
+            | Expr::Pow(left, right) => vec![left, right],
+        }
+    }
+
+    fn write_tree(&self, output: &mut String, prefix: &str, last: bool) {
+        let (branch, indent) = if last {
+            ("└── ", "    ")
+        } else {
+            ("├── ", "│   ")
+        };
+        *output += &format!("{}{}{}\n", prefix, branch, self.label());
+        let prefix = format!("{}{}", prefix, indent);
+        let children = self.children();
+        for (i, child) in children.iter().enumerate() {
+            child.write_tree(output, &prefix, i == children.len() - 1);
+        }
+    }
+
+    fn write_dot(&self, output: &mut String, next: &mut usize) -> usize {
+        let id = *next;
+        *next += 1;
+        *output += &format!("    n{} [label=\"{}\"];\n", id, self.label());
+        for child in self.children() {
+            let child_id = child.write_dot(output, next);
+            *output += &format!("    n{} -> n{};\n", id, child_id);
+        }
+        id
+    }
+
+    fn precedence(&self) -> u8 {
+        match self {
+            Expr::Add(..) | Expr::Sub(..) => 1,
+            Expr::Mul(..) | Expr::Div(..) | Expr::Neg(..) => 2,
+            Expr::Pow(..) => 3,
+            Expr::Num(_) | Expr::Var | Expr::VarY => 4,
+        }
+    }
+}
+
+struct Operand<'a>(&'a Expr, u8);
+
+impl fmt::Display for Operand<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.0.precedence() < self.1 {
+            write!(f, "({})", self.0)
+        } else {
+            write!(f, "{}", self.0)
+        }
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Expr::Num(number) => write!(f, "{}", float::shortest(*number)),
+            Expr::Var => write!(f, "X"),
+            Expr::VarY => write!(f, "Y"),
+            Expr::Neg(expr) => write!(f, "-{}", Operand(expr, 3)),
+            Expr::Add(left, right) => write!(f, "{} + {}", left, Operand(right, 2)),
+            Expr::Sub(left, right) => write!(f, "{} - {}", left, Operand(right, 2)),
+            Expr::Mul(left, right) => write!(f, "{} * {}", Operand(left, 2), Operand(right, 3)),
+            Expr::Div(left, right) => write!(f, "{} / {}", Operand(left, 2), Operand(right, 3)),
+            Expr::Pow(base, exponent) => write!(f, "{}^{}", Operand(base, 4), Operand(exponent, 4)),
+        }
+    }
+}
+
+fn map2(p: &[Vec<f32>], f: impl Fn(f32) -> f32) -> Vec<Vec<f32>> {
+    p.iter()
+        .map(|row| row.iter().map(|&c| f(c)).collect())
+        .collect()
+}
+
+fn add2(left: &[Vec<f32>], right: &[Vec<f32>]) -> Vec<Vec<f32>> {
+    let mut sum = vec![vec![]; left.len().max(right.len())];
+    for (i, row) in sum.iter_mut().enumerate() {
+        let empty = vec![];
+        *row = add(
+            left.get(i).unwrap_or(&empty),
+            right.get(i).unwrap_or(&empty),
+        );
+    }
+    sum
+}
+
+fn mul2(left: &[Vec<f32>], right: &[Vec<f32>]) -> Vec<Vec<f32>> {
+    let mut product = vec![vec![]; (left.len() + right.len()).saturating_sub(1)];
+    for (i, a) in left.iter().enumerate() {
+        for (j, b) in right.iter().enumerate() {
+            product[i + j] = add(&product[i + j], &mul(a, b));
+        }
+    }
+    product
+}
+
+fn trim(mut coefficients: Vec<f32>) -> Vec<f32> {
+    while coefficients.last() == Some(&0.0) {
+        coefficients.pop();
+    }
+    coefficients
+}
+
+/// Errors on an infinite or NaN coefficient, which an overflow leaves.
+fn finite(coefficients: Vec<f32>) -> Result<Vec<f32>, ParseError> {
+    match coefficients.iter().all(|c| c.is_finite()) {
+        true => Ok(coefficients),
+        false => Err(ParseError::Overflow),
+    }
+}
+
+fn finite2(p: Vec<Vec<f32>>) -> Result<Vec<Vec<f32>>, ParseError> {
+    match p.iter().flatten().all(|c| c.is_finite()) {
+        true => Ok(p),
+        false => Err(ParseError::Overflow),
+    }
+}
+
+/// Terms cancelling out, as in `(X + 0.1)^2 = X^2 + 0.2 * X + 0.01`, leave
+/// a few units in the last place instead of zero. Keeping them would change
+/// the degree of the reduced form. An overflowed sum, which is never finite,
+/// is left for `finite`.
+fn add(left: &[f32], right: &[f32]) -> Vec<f32> {
+    let sum = (0..left.len().max(right.len()))
+        .map(|i| {
+            let a = left.get(i).copied().unwrap_or(0.0);
+            let b = right.get(i).copied().unwrap_or(0.0);
+            let sum = a + b;
+            if sum.is_finite() && sum.abs() <= CANCELLATION * a.abs().max(b.abs()) {
+                0.0
+            } else {
+                sum
+            }
+        })
+        .collect();
+    trim(sum)
+}
+
+fn mul(left: &[f32], right: &[f32]) -> Vec<f32> {
+    if left.is_empty() || right.is_empty() {
+        return vec![];
+    }
+    let mut product = vec![0.0; left.len() + right.len() - 1];
+    for (i, a) in left.iter().enumerate() {
+        for (j, b) in right.iter().enumerate() {
+            product[i + j] += a * b;
+        }
+    }
+    trim(product)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expand_factored_expression() {
+        let expr = check_expression("(X-1)^3 * (X+2)").unwrap();
+        assert_eq!(expr.expand(), Ok(vec![-2.0, 5.0, -3.0, -1.0, 1.0]));
+        let expr = check_expression("5 + 4X - 9.3 * X^2").unwrap();
+        assert_eq!(expr.expand(), Ok(vec![5.0, 4.0, -9.3]));
+    }
+
+    #[test]
+    fn expand_products_on_both_sides() {
+        let reduce = |line| {
+            let (left, right) = parse_equation(line).unwrap();
+            Expr::Sub(Box::new(left), Box::new(right)).expand()
+        };
+        assert_eq!(reduce("(X+1)^2 = (X-1)*(X+3)"), Ok(vec![4.0]));
+        assert_eq!(
+            reduce("((X - 1) * (X + 2))^2 = X^2 * (X + 1)"),
+            Ok(vec![4.0, -4.0, -4.0, 1.0, 1.0])
+        );
+        assert_eq!(reduce("2(X+1) = (X^2)^0 * X"), Ok(vec![2.0, 1.0]));
+        // an overflowed coefficient is not taken for a cancellation
+        assert_eq!(
+            parse_equation("X^2 * 10^38 * 10 = X"),
+            Err(ParseError::Overflow)
+        );
+        let max = || Box::new(Expr::Num(f32::MAX));
+        assert_eq!(Expr::Add(max(), max()).expand(), Err(ParseError::Overflow));
+        assert_eq!(add(&[f32::INFINITY], &[0.0]), [f32::INFINITY]);
+        // the rounding left by cancelling terms does not raise the degree
+        assert_eq!(
+            reduce("(X+0.1)^3 = X^3 + 0.3 * X^2 + 0.03 * X + 0.001"),
+            Ok(vec![])
+        );
+        assert_eq!(reduce("(0.1 * X + 0.2)^2 = 0.01 * X^2").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn differentiate_factored_expression() {
+        let expr = check_expression("(X-1)^3 * (X+2)").unwrap();
+        let derivative = expr.derivative().simplify();
+        assert_eq!(
+            derivative.to_string(),
+            "3 * (X - 1)^2 * (X + 2) + (X - 1)^3"
+        );
+        assert_eq!(derivative.expand(), Ok(vec![5.0, -6.0, -3.0, 4.0]));
+    }
+
+    #[test]
+    fn print_equation_tree() {
+        let (left, right) = parse_equation("5 + X^2 = -X").unwrap();
+        assert_eq!(
+            equation_tree(&left, &right),
+            "=\n├── +\n│   ├── 5\n│   └── ^\n│       ├── X\n│       └── 2\n└── neg\n    └── X\n"
+        );
+        assert_eq!(
+            equation_dot(&Expr::Var, &Expr::Num(1.0)),
+            "digraph {\n    n0 [label=\"=\"];\n    n1 [label=\"X\"];\n    n0 -> n1;\n    n2 [label=\"1\"];\n    n0 -> n2;\n}\n"
+        );
+    }
+
+    #[test]
+    fn collect_every_error() {
+        let diagnostics = check_equation("5 * # + 3 * * X + 1.2.3 = 2 = 1").unwrap_err();
+        let spans: Vec<Range<usize>> = diagnostics.iter().map(|d| d.span.clone()).collect();
+        assert_eq!(spans, vec![4..5, 12..13, 18..23, 28..29]);
+        assert_eq!(diagnostics[0].message, "unexpected character `#`");
+        assert_eq!(diagnostics[2].error, ParseError::ParseNumError);
+    }
+
+    #[test]
+    fn strict_subject_grammar() {
+        assert!(check_strict("5 * X^0 + 4 * X^1 - 9.3 * X^2 = 1 * X^0").is_empty());
+        assert!(check_strict("-5 * X^0 = 0 * X^0").is_empty());
+        let diagnostics = check_strict("5 + 4X + X^2 = X^2");
+        let spans: Vec<Range<usize>> = diagnostics.iter().map(|d| d.span.clone()).collect();
+        assert_eq!(spans, vec![0..1, 4..6, 9..12, 15..18]);
+        assert_eq!(
+            diagnostics[1].help.as_deref(),
+            Some("did you mean `4 * X^1`?")
+        );
+        assert_eq!(check_strict("5 * X^0 =").len(), 1);
+    }
+
+    #[test]
+    fn fold_sign_chains() {
+        let expr = check_expression("- -3 * X +-+2").unwrap();
+        assert_eq!(expr.expand(), Ok(vec![-2.0, 3.0]));
+        let expr = check_expression("5 * X ^ 2 - - X").unwrap();
+        assert_eq!(expr.expand(), Ok(vec![0.0, 1.0, 5.0]));
+        let (tokens, _) = tokenize("1 - - + 2");
+        assert_eq!(tokens[1], (Token::Plus, 2..7));
+    }
+
+    #[test]
+    fn partial_input_while_typing() {
+        let partial = parse_partial("5 * X^2 + 3 *");
+        assert_eq!(partial.coefficients, [3.0, 0.0, 5.0]);
+        assert_eq!(partial.expected, "a number, `X` or `(`");
+        assert!(!partial.complete);
+        let partial = parse_partial("2 * (X - 1");
+        assert_eq!(partial.coefficients, [-2.0, 2.0]);
+        assert_eq!(partial.expected, "`*`, `X`, an operator or `=`");
+        assert_eq!(parse_partial("(X + 1)").expected, "`^`, an operator or `=`");
+        let partial = parse_partial("X^2 = ");
+        assert_eq!(partial.coefficients, [0.0, 0.0, 1.0]);
+        assert_eq!(partial.expected, "a number, `X`, `(` or a sign");
+        let partial = parse_partial("X^");
+        assert_eq!(
+            (partial.coefficients, partial.expected),
+            (vec![0.0, 1.0], "an exponent")
+        );
+        let partial = parse_partial("X^2 = 4");
+        assert!(partial.complete);
+        assert_eq!(partial.coefficients, [-4.0, 0.0, 1.0]);
+        assert_eq!(parse_partial("").coefficients, Vec::<f32>::new());
+    }
+
+    #[test]
+    fn percents_and_mixed_numbers() {
+        let (tokens, _) = tokenize("50% * X = 1 1/2");
+        assert_eq!(tokens[0], (Token::Number(0.5), 0..3));
+        assert_eq!(tokens[4], (Token::Number(1.5), 10..15));
+        let expr = check_expression("-2  3/4 * X^2 + 12.5% * X").unwrap();
+        assert_eq!(expr.expand(), Ok(vec![0.0, 0.125, -2.75]));
+        // improper or decimal parts are not mixed numbers
+        assert!(check_expression("1 3/2").is_err());
+        assert!(check_expression("1.5 1/2").is_err());
+        assert!(check_expression("1 1/2.5").is_err());
+        assert_eq!(
+            check_expression("X / 2").unwrap().expand(),
+            Ok(vec![0.0, 0.5])
+        );
+        let diagnostics = check_strict("50% * X^1 = 1 1/2 * X^0");
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].help.as_deref(), Some("did you mean `0.5`?"));
+        assert_eq!(diagnostics[1].span, 12..17);
+    }
+
+    #[test]
+    fn reject_malformed_expressions() {
+        assert_eq!(
+            check_expression("(X + 1").unwrap_err()[0].error,
+            ParseError::SyntaxError
+        );
+        assert_eq!(
+            check_expression("X = 1").unwrap_err()[0].error,
+            ParseError::EqualSignError
+        );
+        let diagnostics = check_expression("X^X + 2 * X^1.5").unwrap_err();
+        assert_eq!(diagnostics[0].error, ParseError::InvalidExponent);
+        assert_eq!(diagnostics[1].span, 12..15);
+    }
+
+    #[test]
+    fn parenthesized_exponents() {
+        let expr = check_expression("X^(3) + 2 * X^(2+1) - X^(1.5 * 2)").unwrap();
+        assert_eq!(expr.expand(), Ok(vec![0.0, 0.0, 0.0, 2.0]));
+        let expr = check_expression("X^(2+1)").unwrap();
+        assert_eq!(expr.to_string(), "X^(2 + 1)");
+        assert_eq!(expr.derivative().simplify().to_string(), "3 * X^2");
+    }
+
+    #[test]
+    fn constant_arithmetic_in_coefficients() {
+        let (left, right) = check_equation("(3/4 + 0.5) * X^2 - 2^3 = 0").unwrap();
+        let reduced = Expr::Sub(Box::new(left), Box::new(right));
+        assert_eq!(reduced.expand(), Ok(vec![-8.0, 0.0, 1.25]));
+        let expr = check_expression("X^2 / 2").unwrap();
+        assert_eq!(expr.derivative().simplify().to_string(), "X");
+        let diagnostics = check_expression("1 / (2 - 2) + 3 / X").unwrap_err();
+        assert_eq!(diagnostics[0].error, ParseError::DivisionByZero);
+        assert_eq!(diagnostics[0].span, 4..11);
+        assert_eq!(diagnostics[1].error, ParseError::InvalidDivisor);
+    }
+
+    #[test]
+    fn evaluate_unreduced_expression() {
+        let expr = check_expression("(X - 1)^3 * (X + 2) / 2").unwrap();
+        assert_eq!(expr.eval(3.0), 20.0);
+        assert_eq!(expr.eval(1.0), 0.0);
+    }
+
+    #[test]
+    fn reject_negative_exponent() {
+        let diagnostics = check_equation("3 * X^-2 + X^(1 - 3) = 1").unwrap_err();
+        let spans: Vec<Range<usize>> = diagnostics.iter().map(|d| d.span.clone()).collect();
+        assert_eq!(spans, vec![6..8, 13..20]);
+        assert!(diagnostics
+            .iter()
+            .all(|d| d.error == ParseError::NegativeExponent));
+        let negative = Expr::Pow(Box::new(Expr::Var), Box::new(Expr::Num(-1.0)));
+        assert_eq!(negative.expand(), Err(ParseError::NegativeExponent));
+    }
+
+    #[test]
+    fn limits_on_untrusted_input() {
+        let limits = Limits {
+            length: 40,
+            terms: 4,
+            depth: 3,
+            degree: 6,
+        };
+        let check = |line: &str| {
+            let mut parser = Parser::within(line, limits);
+            let sides = parser.equation();
+            parser.finish(line, sides)
+        };
+        assert!(check("((X)) = 1").is_ok());
+        let diagnostics = check("(((X))) = 1").unwrap_err();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, "nested deeper than 3 levels");
+        let diagnostics = check("X + X + X + X + X = 1").unwrap_err();
+        assert_eq!(diagnostics[0].message, "the input has more than 4 terms");
+        let diagnostics = check(&format!("X = {}", "1".repeat(40))).unwrap_err();
+        assert_eq!(diagnostics[0].error, ParseError::LimitExceeded);
+        assert_eq!(diagnostics[0].span, 40..44);
+        // the default depth stops a stack overflow
+        let nested = format!("{}X{} = 1", "(".repeat(1_000), ")".repeat(1_000));
+        assert_eq!(parse_equation(&nested), Err(ParseError::LimitExceeded));
+        assert!(check("(X^2 + 1)^3 = X^6").is_ok());
+        let diagnostics = check("X^2 * X^5 = 1").unwrap_err();
+        assert_eq!(diagnostics[0].message, "the degree would be over 6");
+        // a constant to a large power is refused too
+        assert!(check("2^7 * X = 1").is_err());
+        assert!(check("X = X^(X^7 - X^7)").is_err());
+        // refused before anything is expanded
+        for line in ["X^100000 = 1", "(X + 1)^4000000000 = 0", "X^(X^9)^9999 = 1"] {
+            assert_eq!(parse_equation(line), Err(ParseError::LimitExceeded));
+        }
+        // the limits go with each call
+        assert!(check_equation_with("X^7 = 1", &limits).is_err());
+        assert!(check_equation("X^7 = 1").is_ok());
+        assert!(check_expression_with("X^7", &DEFAULT_LIMITS).is_ok());
+    }
+
+    #[test]
+    fn numbers_past_the_range_of_f32() {
+        let line = format!("{} * X = 1", "9".repeat(50));
+        let diagnostics = check_equation(&line).unwrap_err();
+        assert_eq!(diagnostics[0].error.code(), "E010");
+        assert_eq!(diagnostics[0].span, 0..50);
+        let diagnostics = check_equation("10^39 * X = 1").unwrap_err();
+        assert_eq!(diagnostics[0].error, ParseError::Overflow);
+        assert_eq!(diagnostics[0].span, 0..13);
+        assert!(check_expression("2 * 10^38 * 10").is_err());
+        assert!(check_equation("10^38 * X = 1").is_ok());
+    }
+
+    /// Worst cases for the tokenizer and the parser, as functions of a size.
+    type Input = fn(usize) -> String;
+
+    const ADVERSARIAL: [(&str, Input); 5] = [
+        ("sign chain", |n| format!("X {}1 = 0", "+-".repeat(n))),
+        ("nesting", |n| {
+            format!("{}X{} = 0", "(".repeat(n), ")".repeat(n))
+        }),
+        ("long number", |n| format!("{} * X = 0", "1".repeat(n))),
+        ("an error per term", |n| {
+            format!("X{} = 0", " + )".repeat(n))
+        }),
+        ("long sum", |n| format!("{}X = 0", "X + ".repeat(n))),
+    ];
+
+    const UNLIMITED: Limits = Limits {
+        length: usize::MAX,
+        terms: usize::MAX,
+        depth: usize::MAX,
+        degree: usize::MAX,
+    };
+
+    fn parse_time(line: &str) -> std::time::Duration {
+        let start = std::time::Instant::now();
+        let mut parser = Parser::within(line, UNLIMITED);
+        let sides = parser.equation();
+        let result = parser.finish(line, sides);
+        let elapsed = start.elapsed();
+        // dropping a deep tree recurses, and is not parsing
+        std::mem::forget(result);
+        elapsed
+    }
+
+    /// The best of several runs of each line, taken in turns so that a
+    /// change in the load of the machine slows both down.
+    fn parse_times(small: &str, large: &str) -> (std::time::Duration, std::time::Duration) {
+        (0..7).fold(
+            (std::time::Duration::MAX, std::time::Duration::MAX),
+            |(best_small, best_large), _| {
+                (
+                    best_small.min(parse_time(small)),
+                    best_large.min(parse_time(large)),
+                )
+            },
+        )
+    }
+
+    /// Parsing 8 times the input must take well under the 64 times of a
+    /// quadratic parser, the bound leaving room for the caches and the noise
+    /// of a linear one.
+    #[test]
+    fn parsing_is_linear_on_adversarial_input() {
+        // one stack frame per nesting level at least
+        let guard = std::thread::Builder::new().stack_size(1 << 27).spawn(|| {
+            for (name, input) in ADVERSARIAL {
+                let (small, large) = parse_times(&input(2_000), &input(16_000));
+                assert!(
+                    large < small * 32,
+                    "parsing {} grew from {:?} to {:?}",
+                    name,
+                    small,
+                    large
+                );
+            }
+        });
+        guard.unwrap().join().unwrap();
+    }
+
+    #[test]
+    fn adversarial_input_parses_correctly() {
+        // under the default limit of 1000 terms
+        let [signs, nesting, number, errors, sum] = ADVERSARIAL.map(|(_, input)| input(500));
+        // an even number of `-`
+        assert_eq!(
+            parse_equation(&signs).unwrap().0.expand(),
+            Ok(vec![1.0, 1.0])
+        );
+        assert_eq!(parse_equation(&nesting), Err(ParseError::LimitExceeded));
+        assert_eq!(tokenize(&number).1[0].error, ParseError::Overflow);
+        assert_eq!(parse_equation(&number), Err(ParseError::Overflow));
+        assert_eq!(check_equation(&errors).unwrap_err().len(), 500);
+        assert_eq!(
+            parse_equation(&sum).unwrap().0.expand(),
+            Ok(vec![0.0, 501.0])
+        );
+    }
+}
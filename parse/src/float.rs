@@ -0,0 +1,437 @@
+use std::cmp::Ordering;
+
+/// An unsigned integer of any size, little-endian in base 2^32, with just
+/// the operations needed to print floats and round exact roots.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Big(Vec<u32>);
+
+impl Big {
+    pub fn new(n: u64) -> Big {
+        Big::from_u128(n as u128)
+    }
+
+    pub fn from_u128(n: u128) -> Big {
+        let mut big = Big((0..4).map(|i| (n >> (32 * i)) as u32).collect());
+        big.trim();
+        big
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn is_odd(&self) -> bool {
+        self.bit(0)
+    }
+
+    fn bits(&self) -> usize {
+        self.0
+            .last()
+            .map_or(0, |top| 32 * self.0.len() - top.leading_zeros() as usize)
+    }
+
+    fn bit(&self, i: usize) -> bool {
+        self.0
+            .get(i / 32)
+            .is_some_and(|limb| limb >> (i % 32) & 1 == 1)
+    }
+
+    fn trim(&mut self) {
+        while self.0.last() == Some(&0) {
+            self.0.pop();
+        }
+    }
+
+    pub fn mul_small(&mut self, factor: u32) {
+        let mut carry = 0u64;
+        for limb in self.0.iter_mut() {
+            let product = *limb as u64 * factor as u64 + carry;
+            *limb = product as u32;
+            carry = product >> 32;
+        }
+        if carry > 0 {
+            self.0.push(carry as u32);
+        }
+    }
+
+    fn shift_left(&mut self, bits: usize) {
+        for _ in 0..bits / 32 {
+            self.0.insert(0, 0);
+        }
+        let bits = bits % 32;
+        if bits > 0 {
+            let mut carry = 0u32;
+            for limb in self.0.iter_mut() {
+                let shifted = ((*limb as u64) << bits) | carry as u64;
+                *limb = shifted as u32;
+                carry = (shifted >> 32) as u32;
+            }
+            if carry > 0 {
+                self.0.push(carry);
+            }
+        }
+        self.trim();
+    }
+
+    pub fn add(&self, other: &Big) -> Big {
+        let mut sum = vec![];
+        let mut carry = 0u64;
+        for i in 0..self.0.len().max(other.0.len()) {
+            let total =
+                *self.0.get(i).unwrap_or(&0) as u64 + *other.0.get(i).unwrap_or(&0) as u64 + carry;
+            sum.push(total as u32);
+            carry = total >> 32;
+        }
+        sum.push(carry as u32);
+        let mut sum = Big(sum);
+        sum.trim();
+        sum
+    }
+
+    /// Assumes `other` is not greater.
+    pub fn sub_assign(&mut self, other: &Big) {
+        let mut borrow = 0i64;
+        for i in 0..self.0.len() {
+            let mut difference = self.0[i] as i64 - *other.0.get(i).unwrap_or(&0) as i64 - borrow;
+            borrow = (difference < 0) as i64;
+            if difference < 0 {
+                difference += 1 << 32;
+            }
+            self.0[i] = difference as u32;
+        }
+        self.trim();
+    }
+
+    pub fn compare(&self, other: &Big) -> Ordering {
+        self.0
+            .len()
+            .cmp(&other.0.len())
+            .then_with(|| self.0.iter().rev().cmp(other.0.iter().rev()))
+    }
+
+    /// Replaces the value by the remainder, for a quotient below 10.
+    fn div_digit(&mut self, divisor: &Big) -> u8 {
+        let mut digit = 0;
+        while self.compare(divisor) != Ordering::Less {
+            self.sub_assign(divisor);
+            digit += 1;
+        }
+        digit
+    }
+
+    /// The quotient and the remainder, one bit at a time, for a nonzero
+    /// `divisor`.
+    pub fn div_rem(&self, divisor: &Big) -> (Big, Big) {
+        let (mut quotient, mut remainder) = (Big(vec![]), Big(vec![]));
+        for i in (0..self.bits()).rev() {
+            remainder.shift_left(1);
+            if self.bit(i) {
+                remainder = remainder.add(&Big::new(1));
+            }
+            quotient.shift_left(1);
+            if remainder.compare(divisor) != Ordering::Less {
+                remainder.sub_assign(divisor);
+                quotient = quotient.add(&Big::new(1));
+            }
+        }
+        (quotient, remainder)
+    }
+
+    /// The floor of the square root, digit by digit in base 2 from the top,
+    /// each step checking the next bit of the root with integers only.
+    pub fn isqrt(&self) -> Big {
+        let (mut root, mut remainder) = (Big(vec![]), Big(vec![]));
+        for pair in (0..self.bits().div_ceil(2)).rev() {
+            // remainder = 4 * remainder + the next two bits
+            remainder.shift_left(2);
+            let bits = 2 * self.bit(2 * pair + 1) as u64 + self.bit(2 * pair) as u64;
+            remainder = remainder.add(&Big::new(bits));
+            // the next bit is 1 when (4 * root + 1) fits in the remainder
+            let mut trial = root.clone();
+            trial.shift_left(2);
+            trial = trial.add(&Big::new(1));
+            root.shift_left(1);
+            if remainder.compare(&trial) != Ordering::Less {
+                remainder.sub_assign(&trial);
+                root = root.add(&Big::new(1));
+            }
+        }
+        root
+    }
+
+    /// In base 10.
+    pub fn to_decimal(&self) -> String {
+        let mut digits = vec![];
+        let mut n = self.clone();
+        while !n.is_zero() {
+            let mut remainder = 0u64;
+            for limb in n.0.iter_mut().rev() {
+                let value = (remainder << 32) | *limb as u64;
+                *limb = (value / 10) as u32;
+                remainder = value % 10;
+            }
+            n.trim();
+            digits.push(b'0' + remainder as u8);
+        }
+        if digits.is_empty() {
+            digits.push(b'0');
+        }
+        digits.reverse();
+        String::from_utf8(digits).unwrap_or_default()
+    }
+}
+
+/// The shortest decimal digits that read back as `x` and the position of
+/// the decimal point, `0.d1d2... * 10^k`, for a finite positive `x`. This
+/// is Steele and White's free-format algorithm, as refined by Burger and
+/// Dybvig, on exact integers.
+fn shortest_digits(x: f32) -> (Vec<u8>, i32) {
+    let bits = x.to_bits();
+    let (mantissa, exponent) = match (bits >> 23) & 0xff {
+        0 => ((bits & 0x7f_ffff) as u64, -149),
+        biased => (((bits & 0x7f_ffff) | 0x80_0000) as u64, biased as i32 - 150),
+    };
+    // A mantissa at a power of two has a closer neighbour below.
+    let uneven = mantissa == 0x80_0000 && exponent > -149;
+    let (mut r, mut s, mut high, mut low) = if exponent >= 0 {
+        let mut r = Big::new(mantissa << if uneven { 2 } else { 1 });
+        r.shift_left(exponent as usize);
+        let mut low = Big::new(1);
+        low.shift_left(exponent as usize);
+        let mut high = low.clone();
+        if uneven {
+            high.shift_left(1);
+        }
+        (r, Big::new(if uneven { 4 } else { 2 }), high, low)
+    } else {
+        let mut s = Big::new(if uneven { 4 } else { 2 });
+        s.shift_left((-exponent) as usize);
+        let r = Big::new(mantissa << if uneven { 2 } else { 1 });
+        (r, s, Big::new(if uneven { 2 } else { 1 }), Big::new(1))
+    };
+    // Values rounding to an even mantissa include the halfway points.
+    let inclusive = mantissa % 2 == 0;
+    let too_high = |value: &Big, s: &Big| match value.compare(s) {
+        Ordering::Greater => true,
+        Ordering::Equal => inclusive,
+        Ordering::Less => false,
+    };
+    let mut k = 0;
+    while too_high(&r.add(&high), &s) {
+        s.mul_small(10);
+        k += 1;
+    }
+    loop {
+        let mut scaled = r.add(&high);
+        scaled.mul_small(10);
+        if too_high(&scaled, &s) {
+            break;
+        }
+        r.mul_small(10);
+        high.mul_small(10);
+        low.mul_small(10);
+        k -= 1;
+    }
+    let mut digits = vec![];
+    loop {
+        r.mul_small(10);
+        high.mul_small(10);
+        low.mul_small(10);
+        let digit = r.div_digit(&s);
+        let below = match r.compare(&low) {
+            Ordering::Less => true,
+            Ordering::Equal => inclusive,
+            Ordering::Greater => false,
+        };
+        let above = too_high(&r.add(&high), &s);
+        match (below, above) {
+            (false, false) => digits.push(digit),
+            (true, false) => break digits.push(digit),
+            (false, true) => break digits.push(digit + 1),
+            (true, true) => {
+                let mut twice = r.clone();
+                twice.mul_small(2);
+                let up = twice.compare(&s) != Ordering::Less;
+                break digits.push(digit + up as u8);
+            }
+        }
+    }
+    (digits, k)
+}
+
+/// The value like `{}` of an `f32` writes it, without the standard float
+/// formatting: the shortest digits that read back the same, in plain
+/// positional notation. The bytes are the same on every platform.
+pub fn shortest(x: f32) -> String {
+    if x.is_nan() {
+        return String::from("NaN");
+    }
+    let sign = if x.is_sign_negative() { "-" } else { "" };
+    if x.is_infinite() {
+        return format!("{}inf", sign);
+    }
+    if x == 0.0 {
+        return format!("{}0", sign);
+    }
+    let (digits, k) = shortest_digits(x.abs());
+    let digits: String = digits.iter().map(|d| (b'0' + d) as char).collect();
+    let length = digits.len() as i32;
+    let text = if k <= 0 {
+        format!("0.{}{}", "0".repeat((-k) as usize), digits)
+    } else if k >= length {
+        format!("{}{}", digits, "0".repeat((k - length) as usize))
+    } else {
+        format!("{}.{}", &digits[..k as usize], &digits[k as usize..])
+    };
+    format!("{}{}", sign, text)
+}
+
+/// The value like `{:e}` of an `f32` writes it: the digits of `shortest`
+/// in scientific notation.
+pub fn shortest_scientific(x: f32) -> String {
+    if !x.is_finite() {
+        return shortest(x);
+    }
+    let sign = if x.is_sign_negative() { "-" } else { "" };
+    if x == 0.0 {
+        return format!("{}0e0", sign);
+    }
+    let (digits, k) = shortest_digits(x.abs());
+    let digits: String = digits.iter().map(|d| (b'0' + d) as char).collect();
+    let (first, rest) = digits.split_at(1);
+    let point = if rest.is_empty() { "" } else { "." };
+    format!("{}{}{}{}e{}", sign, first, point, rest, k - 1)
+}
+
+/// The value like `{:.decimals$e}` of an `f64` writes it: rounded half to
+/// even from its exact binary value to `decimals` places after the point,
+/// in scientific notation.
+pub fn scientific(x: f64, decimals: usize) -> String {
+    if !x.is_finite() {
+        return shortest(x as f32);
+    }
+    let sign = if x.is_sign_negative() { "-" } else { "" };
+    if x == 0.0 {
+        let point = if decimals > 0 { "." } else { "" };
+        return format!("{}0{}{}e0", sign, point, "0".repeat(decimals));
+    }
+    let bits = x.abs().to_bits();
+    let (mantissa, exponent) = match (bits >> 52) & 0x7ff {
+        0 => (bits & 0xf_ffff_ffff_ffff, -1074),
+        biased => ((bits & 0xf_ffff_ffff_ffff) | 1 << 52, biased as i32 - 1075),
+    };
+    // x is exactly value / unit
+    let (mut value, mut unit) = (Big::new(mantissa), Big::new(1));
+    match exponent >= 0 {
+        true => value.shift_left(exponent as usize),
+        false => unit.shift_left((-exponent) as usize),
+    }
+    // a first guess of the exponent, corrected from the digits it gives
+    let mut e = x.abs().log10().floor() as i32;
+    loop {
+        let (mut numerator, mut denominator) = (value.clone(), unit.clone());
+        let power = decimals as i32 - e;
+        let scaled = match power >= 0 {
+            true => &mut numerator,
+            false => &mut denominator,
+        };
+        for _ in 0..power.unsigned_abs() {
+            scaled.mul_small(10);
+        }
+        let (mut digits, mut remainder) = numerator.div_rem(&denominator);
+        remainder.mul_small(2);
+        let up = match remainder.compare(&denominator) {
+            Ordering::Greater => true,
+            Ordering::Equal => digits.is_odd(),
+            Ordering::Less => false,
+        };
+        if up {
+            digits = digits.add(&Big::new(1));
+        }
+        let digits = digits.to_decimal();
+        match digits.len().cmp(&(decimals + 1)) {
+            Ordering::Less => e -= 1,
+            Ordering::Greater => e += 1,
+            Ordering::Equal => {
+                let (first, rest) = digits.split_at(1);
+                let point = if rest.is_empty() { "" } else { "." };
+                break format!("{}{}{}{}e{}", sign, first, point, rest, e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_standard_formatting() {
+        let values = [
+            1.0,
+            -9.3,
+            0.1,
+            1e-7,
+            1e30,
+            16777216.0,
+            0.47513145,
+            f32::MIN_POSITIVE,
+            f32::MAX,
+            f32::from_bits(1),
+            -0.0,
+            f32::INFINITY,
+        ];
+        for x in values {
+            assert_eq!(shortest(x), x.to_string());
+        }
+        // a xorshift sequence, every bit pattern being as likely
+        let mut state = 7u32;
+        for _ in 0..20_000 {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            let x = f32::from_bits(state);
+            assert_eq!(shortest(x), x.to_string());
+            assert_eq!(shortest_scientific(x), format!("{:e}", x));
+        }
+    }
+
+    #[test]
+    fn matches_standard_scientific_formatting() {
+        for x in [0.0, -0.0, 1.0, 9.96, 0.05, 1e-320, f64::MAX, f64::NAN] {
+            for decimals in 0..4 {
+                assert_eq!(scientific(x, decimals), format!("{:.*e}", decimals, x));
+            }
+        }
+        assert_eq!(shortest_scientific(1e18), "1e18");
+        let mut state = 7u64;
+        for i in 0..5_000 {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            let x = f64::from_bits(state);
+            assert_eq!(scientific(x, i % 4), format!("{:.*e}", i % 4, x));
+        }
+    }
+
+    #[test]
+    fn big_arithmetic() {
+        let mut n = Big::new(u32::MAX as u64);
+        n.mul_small(10);
+        assert_eq!(n, Big::new(u32::MAX as u64 * 10));
+        n.shift_left(40);
+        n.sub_assign(&Big::new(1));
+        assert_eq!(n.compare(&Big::new(u64::MAX)), Ordering::Greater);
+        let mut seven = Big::new(47);
+        assert_eq!(seven.div_digit(&Big::new(6)), 7);
+        assert_eq!(seven, Big::new(5));
+        let n = Big::from_u128(u128::MAX);
+        assert_eq!(n.to_decimal(), u128::MAX.to_string());
+        let (quotient, remainder) = n.div_rem(&Big::new(1_000_000_007));
+        assert_eq!(quotient, Big::from_u128(u128::MAX / 1_000_000_007));
+        assert_eq!(remainder, Big::from_u128(u128::MAX % 1_000_000_007));
+        assert_eq!(n.isqrt(), Big::new(u64::MAX));
+        assert_eq!(Big::new(99).isqrt(), Big::new(9));
+        assert_eq!(Big::new(0).to_decimal(), "0");
+    }
+}
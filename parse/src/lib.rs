@@ -0,0 +1,10 @@
+//! The grammar of the equations: tokens, expressions, the diagnostics of
+//! what does not parse, and the text of their numbers.
+
+pub mod diagnostics;
+mod error;
+pub mod expr;
+pub mod float;
+pub mod suggest;
+
+pub use error::ParseError;
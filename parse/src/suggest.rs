@@ -0,0 +1,111 @@
+use crate::expr::{self, Token};
+use std::ops::Range;
+
+/// A completion of the equation being typed, inserted at the cursor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Suggestion {
+    pub text: String,
+    /// What it completes, shown next to it.
+    pub description: &'static str,
+}
+
+/// The degree of the term being typed, in the ascending order of the
+/// subject: the number of coefficients the terms before it on its side of
+/// the equation reduce to.
+fn next_degree(prefix: &str, tokens: &[(Token, Range<usize>)]) -> usize {
+    let chars: Vec<char> = prefix.chars().collect();
+    let side_start = tokens
+        .iter()
+        .rposition(|(token, _)| *token == Token::Equal)
+        .map_or(0, |i| tokens[i].1.end);
+    let term_start = tokens
+        .iter()
+        .rposition(|(token, _)| matches!(token, Token::Plus | Token::Minus | Token::Equal))
+        .map_or(0, |i| tokens[i].1.start);
+    if term_start < side_start {
+        return 0;
+    }
+    let before: String = chars[side_start..term_start].iter().collect();
+    expr::parse_partial(&before).coefficients.len()
+}
+
+/// The completions of `input` cut at the byte offset `cursor`: the next
+/// power of `X` in the subject's order, the operators that may follow, and
+/// `= 0` while there is no right side.
+pub fn suggest(input: &str, cursor: usize) -> Vec<Suggestion> {
+    let prefix = &input[..cursor];
+    let (tokens, _) = expr::tokenize(prefix);
+    let degree = next_degree(prefix, &tokens);
+    let power = format!("X^{}", degree);
+    let last = tokens.last().map(|(token, _)| token);
+    let before_last = tokens.len().checked_sub(2).map(|i| &tokens[i].0);
+    let mut suggestions = match (before_last, last) {
+        (_, None | Some(Token::Plus | Token::Minus | Token::LeftParen)) => vec![
+            (power.clone(), "the next power"),
+            (format!("1 * {}", power), "a coefficient and the next power"),
+        ],
+        (_, Some(Token::Equal)) => vec![
+            (String::from("0"), "the reduced form"),
+            (format!("1 * {}", power), "a coefficient and the next power"),
+        ],
+        (_, Some(Token::Star)) => vec![(power, "the next power")],
+        (_, Some(Token::Caret)) => vec![(degree.to_string(), "the next degree")],
+        (_, Some(Token::X)) => vec![(format!("^{}", degree), "the next degree")],
+        (Some(Token::Caret), Some(Token::Number(_))) | (_, Some(Token::RightParen)) => vec![
+            (String::from(" + "), "another term"),
+            (String::from(" - "), "another term"),
+        ],
+        (_, Some(Token::Number(_))) => vec![(format!(" * {}", power), "the next power")],
+        _ => vec![],
+    };
+    let has_right_side = tokens.iter().any(|(token, _)| *token == Token::Equal);
+    if !has_right_side && matches!(last, Some(Token::Number(_) | Token::X | Token::RightParen)) {
+        suggestions.push((String::from(" = 0"), "the right side of the reduced form"));
+    }
+    let spaced = prefix.ends_with(char::is_whitespace);
+    suggestions
+        .into_iter()
+        .map(|(text, description)| Suggestion {
+            text: match spaced {
+                true => text.trim_start().to_string(),
+                false => text,
+            },
+            description,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(input: &str) -> Vec<String> {
+        suggest(input, input.len())
+            .into_iter()
+            .map(|suggestion| suggestion.text)
+            .collect()
+    }
+
+    #[test]
+    fn completions_follow_the_subject_order() {
+        assert_eq!(texts(""), ["X^0", "1 * X^0"]);
+        assert_eq!(texts("5 * X^0 + 4"), [" * X^1", " = 0"]);
+        assert_eq!(texts("5 * X^0 + 4 * X"), ["^1", " = 0"]);
+        assert_eq!(texts("5 * X^0 + 4 * X^"), ["1"]);
+        assert_eq!(texts("5 * X^0 + 4 * X^1 - 9.3 * "), ["X^2"]);
+        assert_eq!(texts("5 * X^0 + 4 * X^1"), [" + ", " - ", " = 0"]);
+        assert_eq!(texts("5 * X^0 + 4 * X^1 "), ["+ ", "- ", "= 0"]);
+        assert_eq!(texts("X^2 = "), ["0", "1 * X^0"]);
+        assert_eq!(texts("X^2 = 1 * X^0 + "), ["X^1", "1 * X^1"]);
+        assert_eq!(texts("X^2 = 4"), [" * X^0"]);
+        assert_eq!(texts("X^2 = 4 @"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn completions_at_the_cursor() {
+        let input = "5 * X^0 +  = 0";
+        let suggestions = suggest(input, 10);
+        assert_eq!(suggestions[0].text, "X^1");
+        assert_eq!(suggestions[0].description, "the next power");
+    }
+}
@@ -0,0 +1,366 @@
+use crate::export;
+use crate::format::Style;
+use crate::method::Method;
+use crate::render::{Latex, OutputRenderer};
+use crate::report::Report;
+use crate::solve::Solver;
+use crate::stats::Stats;
+use crate::Poly;
+use indicatif::ProgressBar;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// How many lines may be read ahead of the last one written, per job. This
+/// bounds the memory used whatever the size of the input.
+const WINDOW: usize = 64;
+
+/// The files written for each equation under `--out-dir`, named after its
+/// line number: `001.json`, `001.tex`, and `001.svg` with `--plot`.
+#[derive(Debug, Clone)]
+pub struct Artifacts {
+    pub dir: PathBuf,
+    pub plot: bool,
+}
+
+impl Artifacts {
+    fn write(&self, number: usize, poly: &Poly, report: &Report) -> Result<(), String> {
+        let path = |extension| self.dir.join(format!("{:03}.{}", number, extension));
+        let json = path("json");
+        fs::write(&json, report.to_json())
+            .map_err(|error| format!("Could not write {}: {}", json.display(), error))?;
+        let tex = path("tex");
+        fs::write(&tex, Latex.render(report))
+            .map_err(|error| format!("Could not write {}: {}", tex.display(), error))?;
+        if self.plot {
+            let svg = path("svg");
+            let roots: Vec<f64> = report.solutions.iter().map(|&r| r as f64).collect();
+            export::write_svg(&svg.to_string_lossy(), &poly.coefficients_f64(), &roots)
+                .map_err(|error| format!("Could not write {}: {}", svg.display(), error))?;
+        }
+        Ok(())
+    }
+}
+
+/// How a batch is run, besides its input and output.
+pub struct Settings<'a> {
+    pub jobs: usize,
+    /// One record per line, with its header and truncation marker.
+    pub renderer: &'a dyn OutputRenderer,
+    pub style: Style,
+    /// Shared by the jobs, its memo only locked around lookups.
+    pub solver: &'a Solver,
+    pub artifacts: Option<Artifacts>,
+    /// Advanced once per equation.
+    pub progress: Option<ProgressBar>,
+    /// Set from the Ctrl-C handler: no more lines are read, and those
+    /// already read are written before the truncation marker.
+    pub interrupted: Option<&'a AtomicBool>,
+    /// Counts each equation, parsed or not.
+    pub stats: Option<&'a Mutex<Stats>>,
+}
+
+impl Settings<'_> {
+    pub fn is_interrupted(&self) -> bool {
+        self.interrupted
+            .is_some_and(|interrupted| interrupted.load(Ordering::SeqCst))
+    }
+
+    /// Prints on stderr without tearing the progress bar.
+    fn error(&self, number: usize, message: &str) {
+        let print = || eprintln!("line {}: {}", number + 1, message);
+        match &self.progress {
+            Some(progress) => progress.suspend(print),
+            None => print(),
+        }
+    }
+}
+
+/// The output for one input line, or the error for stderr. `number` counts
+/// lines from 0.
+fn solve(line: &str, number: usize, settings: &Settings) -> Result<String, String> {
+    let parsed = Poly::new(line);
+    if let Some(Ok(mut stats)) = settings.stats.map(Mutex::lock) {
+        match &parsed {
+            Ok(poly) => stats.record(poly, Method::Auto),
+            Err(error) => stats.record_error(error),
+        }
+    }
+    let poly = parsed
+        .map_err(|error| format!("Error parsing the polynomial equation [{}]", error.code()))?;
+    // the lines in flight are finished even once interrupted
+    let solution = settings
+        .solver
+        .solve(poly.coefficients(), &AtomicBool::new(false), None);
+    let roots = solution.roots.iter().map(|&root| root as f32).collect();
+    let mut report = poly.report_with_roots(settings.style, roots);
+    report.exact = solution.exact;
+    if let Some(artifacts) = &settings.artifacts {
+        // still printed, the files are an addition
+        if let Err(error) = artifacts.write(number + 1, &poly, &report) {
+            settings.error(number, &error);
+        }
+    }
+    Ok(settings.renderer.render_line(line, &report))
+}
+
+fn write_result(
+    writer: &mut impl Write,
+    number: usize,
+    result: Result<String, String>,
+    settings: &Settings,
+) -> io::Result<()> {
+    if let Some(progress) = &settings.progress {
+        progress.inc(1);
+    }
+    match result {
+        Ok(output) => writeln!(writer, "{}", output),
+        Err(error) => {
+            settings.error(number, &error);
+            Ok(())
+        }
+    }
+}
+
+/// Solves each line of the input, streaming the results in input order.
+/// Blank lines are skipped. With several jobs, lines are solved in parallel
+/// and the results reordered, the reader waiting whenever it gets too far
+/// ahead of the writer. Duplicate equations are solved once through the
+/// solver's memo. Returns the number of lines that failed to parse.
+///
+/// Once interrupted, the lines in flight are still written, then the
+/// truncation marker of the format.
+pub fn run(
+    input: impl BufRead,
+    writer: impl Write + Send,
+    settings: &Settings,
+) -> io::Result<usize> {
+    let mut writer = io::BufWriter::new(writer);
+    if let Some(header) = settings.renderer.header() {
+        writeln!(writer, "{}", header)?;
+    }
+    let lines = input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| line.as_ref().map_or(true, |line| !line.trim().is_empty()));
+    if settings.jobs <= 1 {
+        let mut failed = 0;
+        for (number, line) in lines {
+            if settings.is_interrupted() {
+                break;
+            }
+            let result = solve(&line?, number, settings);
+            failed += result.is_err() as usize;
+            write_result(&mut writer, number, result, settings)?;
+        }
+        write_end(&mut writer, settings)?;
+        return Ok(failed);
+    }
+    let window = settings.jobs * WINDOW;
+    let (line_sender, line_receiver) = sync_channel::<(usize, usize, String)>(window);
+    let (result_sender, result_receiver) = sync_channel(window);
+    let (credit_sender, credit_receiver) = sync_channel(window);
+    for _ in 0..window {
+        credit_sender.send(()).unwrap_or_default();
+    }
+    let line_receiver = Arc::new(Mutex::new(line_receiver));
+    thread::scope(|scope| {
+        for _ in 0..settings.jobs {
+            let line_receiver = Arc::clone(&line_receiver);
+            let result_sender = result_sender.clone();
+            scope.spawn(move || loop {
+                let next = line_receiver.lock().map(|receiver| receiver.recv());
+                let Ok(Ok((index, number, line))) = next else {
+                    break;
+                };
+                let result = solve(&line, number, settings);
+                if result_sender.send((index, number, result)).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(result_sender);
+        let writing =
+            scope.spawn(move || write_in_order(result_receiver, credit_sender, writer, settings));
+        let mut reading = Ok(());
+        for (index, (number, line)) in lines.enumerate() {
+            let line = match line {
+                Ok(line) => line,
+                Err(error) => {
+                    reading = Err(error);
+                    break;
+                }
+            };
+            if settings.is_interrupted() {
+                break;
+            }
+            // waits for the writer to catch up
+            if credit_receiver.recv().is_err() || line_sender.send((index, number, line)).is_err() {
+                break;
+            }
+        }
+        drop(line_sender);
+        let failed = writing.join().unwrap_or(Ok(0))?;
+        reading.map(|()| failed)
+    })
+}
+
+type Solved = (usize, usize, Result<String, String>);
+
+fn write_in_order(
+    results: Receiver<Solved>,
+    credits: SyncSender<()>,
+    mut writer: impl Write,
+    settings: &Settings,
+) -> io::Result<usize> {
+    let mut pending = BTreeMap::new();
+    let (mut next, mut failed) = (0, 0);
+    for (index, number, result) in results {
+        pending.insert(index, (number, result));
+        while let Some((number, result)) = pending.remove(&next) {
+            failed += result.is_err() as usize;
+            write_result(&mut writer, number, result, settings)?;
+            credits.send(()).unwrap_or_default();
+            next += 1;
+        }
+    }
+    write_end(&mut writer, settings)?;
+    Ok(failed)
+}
+
+fn write_end(writer: &mut impl Write, settings: &Settings) -> io::Result<()> {
+    if settings.is_interrupted() {
+        if let Some(marker) = settings.renderer.truncation_marker() {
+            writeln!(writer, "{}", marker)?;
+        }
+    }
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::{Csv, Json, Text};
+    use crate::report::CSV_HEADER;
+    use crate::solve::SolveOptions;
+
+    fn settings(jobs: usize, solver: &Solver) -> Settings<'_> {
+        Settings {
+            jobs,
+            renderer: &Text,
+            style: Style::default(),
+            solver,
+            artifacts: None,
+            progress: None,
+            interrupted: None,
+            stats: None,
+        }
+    }
+
+    fn batch(input: &str, jobs: usize, renderer: &dyn OutputRenderer) -> (String, usize) {
+        let mut written = vec![];
+        let solver = Solver::new(SolveOptions::new());
+        let settings = Settings {
+            renderer,
+            ..settings(jobs, &solver)
+        };
+        let failed = run(input.as_bytes(), &mut written, &settings).unwrap();
+        (String::from_utf8(written).unwrap(), failed)
+    }
+
+    #[test]
+    fn batch_keeps_input_order() {
+        let input: String = (1..=500).map(|i| format!("X = {}\n", i)).collect();
+        let expected: String = (1..=500).map(|i| format!("X = {}: {}\n", i, i)).collect();
+        assert_eq!(batch(&input, 1, &Text), (expected.clone(), 0));
+        assert_eq!(batch(&input, 4, &Text), (expected, 0));
+    }
+
+    #[test]
+    fn batch_solves_duplicates_once() {
+        let solver = Solver::new(SolveOptions::new());
+        let input = "X^2 = 4\n2 * X^2 = 8\nX = 1\nX^2 = 4\n";
+        run(input.as_bytes(), io::sink(), &settings(1, &solver)).unwrap();
+        assert_eq!(solver.hits_and_misses(), (2, 2));
+        let solver = Solver::without_memo(SolveOptions::new());
+        run(input.as_bytes(), io::sink(), &settings(2, &solver)).unwrap();
+        assert_eq!(solver.hits_and_misses(), (0, 4));
+    }
+
+    #[test]
+    fn batch_writes_numbered_artifacts() {
+        let dir = std::env::temp_dir().join(format!("computor-batch-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let solver = Solver::new(SolveOptions::new());
+        let progress = ProgressBar::hidden();
+        let settings = Settings {
+            artifacts: Some(Artifacts {
+                dir: dir.clone(),
+                plot: true,
+            }),
+            progress: Some(progress.clone()),
+            ..settings(2, &solver)
+        };
+        let input = "X^2 = 4\n\nX = 1\n";
+        run(input.as_bytes(), io::sink(), &settings).unwrap();
+        assert_eq!(progress.position(), 2);
+        let json = fs::read_to_string(dir.join("003.json")).unwrap();
+        assert!(json.contains("\"degree\": 1"));
+        assert!(dir.join("001.svg").exists());
+        let tex = fs::read_to_string(dir.join("001.tex")).unwrap();
+        assert!(tex.starts_with("\\begin{align*}\nx^{2} - 4 &= 0"));
+        assert!(!dir.join("002.json").exists());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn batch_counts_into_stats() {
+        let solver = Solver::new(SolveOptions::new());
+        let stats = Mutex::new(Stats::default());
+        let settings = Settings {
+            stats: Some(&stats),
+            ..settings(2, &solver)
+        };
+        let input = "X^2 = 4\nX^3 = 1\nX = 1\nX =\n";
+        run(input.as_bytes(), io::sink(), &settings).unwrap();
+        let stats = stats.into_inner().unwrap();
+        assert_eq!(stats.degrees.values().sum::<u64>(), 3);
+        assert_eq!(stats.methods["closed-form"], 2);
+        assert_eq!(stats.errors.values().sum::<u64>(), 1);
+    }
+
+    #[test]
+    fn interrupted_batch_ends_with_marker() {
+        let solver = Solver::new(SolveOptions::new());
+        let interrupted = AtomicBool::new(true);
+        let renderers: [(usize, &dyn OutputRenderer); 3] = [(1, &Json), (3, &Csv), (1, &Text)];
+        for (jobs, renderer) in renderers {
+            let settings = Settings {
+                renderer,
+                interrupted: Some(&interrupted),
+                ..settings(jobs, &solver)
+            };
+            let mut written = vec![];
+            run("X = 1\nX = 2\n".as_bytes(), &mut written, &settings).unwrap();
+            let written = String::from_utf8(written).unwrap();
+            match renderer.truncation_marker() {
+                Some(marker) => assert!(written.ends_with(&format!("{}\n", marker))),
+                None => assert_eq!(written, ""),
+            }
+            assert!(!written.contains("X = 2"));
+        }
+    }
+
+    #[test]
+    fn batch_skips_blank_and_counts_errors() {
+        let (output, failed) = batch("X^2 = 4\n\nX^ = 1\n", 2, &Csv);
+        assert_eq!(failed, 1);
+        assert_eq!(output.lines().count(), 2);
+        assert!(output.starts_with(CSV_HEADER));
+    }
+}
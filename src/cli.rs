@@ -0,0 +1,842 @@
+use crate::decimal::Rounding;
+use crate::domain::Domain;
+use crate::expr::{self, Limits};
+use crate::float;
+use crate::format::{Format, Order, Style};
+use crate::method::{Method, METHODS};
+use crate::plot::{Animation, Size};
+use crate::render::Renderers;
+use crate::solve::SolveOptions;
+use crate::transform::Transform;
+use crate::warnings::{Category, CATEGORIES};
+use crate::Edit;
+use std::ops::RangeInclusive;
+
+pub const USAGE: &str =
+    "Usage: ./computor [--strict] [--allow category]... [--deny-warnings] [--max-length n] [--max-terms n] [--max-depth n] [--max-degree n] [--deterministic] [--time] [--stats file.json] [--output text|json|csv|latex] [--format subject|pretty|unicode] [--pretty] [--order asc|desc] [--positive-lead] [--set degree:value] [--add degree:value] [--steps] [--rearrange] [--formula] [--explain-discriminant] [--complex] [--events] [--precision n [--rounding half-up|half-even]] [--epsilon e] [--float] [--continued-fraction n] [--residuals] [--plot | --plot-sides] [--argand] [--animate newton] [--width n] [--height n] [--plot-svg file] [--plot-png file [--dpi n]] [--export-gnuplot file] [--share] [--copy] [--analyze] [--factor] [--method auto|closed-form|substitution|bracketing|durand-kerner] [--compare-methods] [--sign] [--extrema] [--symmetric] [--count-roots a b] [--has-root a b] [--domain a..b] [--tree | --dot] \"5 * X^0 + 4 * X^1 - 9.3 * X^2 = 1 * X^0\"
+       ./computor diff \"(X - 1)^3 * (X + 2)\"
+       ./computor diff \"2 * X + 1 = 5\" \"X = 2\"
+       ./computor common \"X^2 - 1\" \"X^2 + 2 * X + 1\"
+       ./computor add|sub|mul \"X^2 - 1\" \"X + 1\"
+       ./computor shift \"X^2 - 1\" --by 2
+       ./computor transform \"X^2 - 3 * X + 2\" --reciprocal | --negate | --scale k
+       ./computor discriminant \"X^3 - 3 * X + 2\"
+       ./computor conic \"X^2 + Y^2 - 4 = 0\"
+       ./computor check-answer \"X^2 - 4 = 0\" --roots 2,-2 [--tolerance 1e-4]
+       ./computor verify --input eqs.txt --expected results.json [--tolerance 1e-6]
+       ./computor schema [output.json]
+       ./computor preview \"5 * X^2 + 3 *\"
+       ./computor batch <file | -> [--jobs n] [--deterministic] [--out-dir dir [--plot]] [--no-progress] [--stats file.json] [--output text|json|csv|latex]
+       ./computor grade <binary-path>
+       ./computor extract <file | ->
+       ./computor generate [--degree 2] [--count 10] [--integer-roots] [--seed n | --deterministic]
+       ./computor quiz [--degree 2] [--count 10] [--seed n | --deterministic]
+       ./computor repl [--format subject|pretty|unicode]
+       ./computor run <script | -> [--format subject|pretty|unicode]
+           then equations, p := X^2 - 4, p, solve p, eval p 2, plot p file.svg|png|gp,
+           delete p, list, undo, redo,
+           set format|order|precision|rounding value, history, save file, load file, quit";
+
+#[derive(Debug, Default, PartialEq)]
+pub enum Command {
+    #[default]
+    Solve,
+    /// The derivative of one expression, or the comparison of two
+    /// equations when a second positional argument is given.
+    Diff,
+    Conic,
+    /// Takes a second positional argument, the other polynomial, like the
+    /// arithmetic commands.
+    Common,
+    Add,
+    Sub,
+    Mul,
+    /// Needs `--by`.
+    Shift,
+    /// Needs one of `--reciprocal`, `--negate` and `--scale`.
+    Transform,
+    Discriminant,
+    CheckAnswer,
+    /// The positional argument is the file of equations, `-` for stdin.
+    Batch,
+    /// Like batch, needs `--expected`, the saved output of
+    /// `batch --output json`; `--input` can name the file of equations.
+    Verify,
+    /// The positional argument is the path of the binary to evaluate.
+    Grade,
+    /// The positional argument is the text to scan, `-` for stdin.
+    Extract,
+    /// Takes no positional argument.
+    Generate,
+    /// Takes no positional argument.
+    Quiz,
+    /// Takes no positional argument.
+    Repl,
+    /// The positional argument is the script of repl lines, `-` for stdin.
+    Run,
+    /// Prints the JSON schema, or checks the JSON output saved in the file
+    /// given as positional argument, `-` for stdin.
+    Schema,
+    /// The positional argument is an equation still being typed.
+    Preview,
+}
+
+/// The values of the options also set from the repl.
+pub const FORMATS: [(&str, Format); 3] = [
+    ("subject", Format::Subject),
+    ("pretty", Format::Pretty),
+    ("unicode", Format::Unicode),
+];
+pub const ORDERS: [(&str, Order); 2] = [("asc", Order::Ascending), ("desc", Order::Descending)];
+pub const ROUNDINGS: [(&str, Rounding); 2] = [
+    ("half-up", Rounding::HalfUp),
+    ("half-even", Rounding::HalfEven),
+];
+
+pub const DEFAULT_DEGREE: usize = 2;
+pub const DEFAULT_COUNT: usize = 10;
+
+/// Used by `generate` and `quiz` under `--deterministic` without `--seed`.
+pub const DETERMINISTIC_SEED: u64 = 42;
+
+pub const DEFAULT_TOLERANCE: f32 = 1e-4;
+
+/// The decimal places `--precision` rounds to at most, the exact digits
+/// taking a time quadratic in their number.
+pub const PRECISIONS: RangeInclusive<usize> = 0..=1_000;
+
+/// The degrees `--max-degree` accepts, no higher than the default one, an
+/// equation of degree 1000 already taking a minute to solve.
+pub const DEGREES: RangeInclusive<usize> = 0..=expr::DEFAULT_LIMITS.degree;
+
+/// The resolutions `--dpi` accepts, the largest image already taking
+/// hundreds of megabytes.
+pub const DPIS: RangeInclusive<usize> = 1..=2_400;
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Options {
+    pub command: Command,
+    pub equation: String,
+    pub second: String,
+    pub strict: bool,
+    pub deterministic: bool,
+    pub time: bool,
+    /// The name of a renderer in `Renderers`, text when not given.
+    pub output: Option<String>,
+    pub format: Format,
+    pub order: Option<Order>,
+    pub positive_lead: bool,
+    pub set_terms: Vec<(usize, f32)>,
+    pub add_terms: Vec<(usize, f32)>,
+    pub steps: bool,
+    pub rearrange: bool,
+    pub formula: bool,
+    pub explain_discriminant: bool,
+    pub complex: bool,
+    /// Solver events printed to stderr as they happen.
+    pub events: bool,
+    pub precision: Option<usize>,
+    pub rounding: Rounding,
+    pub continued_fraction: Option<usize>,
+    pub residuals: bool,
+    pub plot: bool,
+    pub plot_sides: bool,
+    pub argand: bool,
+    pub animate: Option<Animation>,
+    pub width: Option<usize>,
+    pub height: Option<usize>,
+    pub plot_svg: Option<String>,
+    pub plot_png: Option<String>,
+    pub dpi: Option<usize>,
+    pub export_gnuplot: Option<String>,
+    pub share: bool,
+    pub copy: bool,
+    pub analyze: bool,
+    /// Exact, so not with `--float`.
+    pub factor: bool,
+    pub method: Option<Method>,
+    pub compare_methods: bool,
+    pub sign: bool,
+    pub extrema: bool,
+    pub symmetric: bool,
+    pub tree: bool,
+    pub dot: bool,
+    pub count_roots: Option<(f32, f32)>,
+    pub has_root: Option<(f32, f32)>,
+    pub domain: Option<Domain>,
+    pub roots: Vec<f32>,
+    pub tolerance: Option<f32>,
+    pub by: Option<f32>,
+    pub transform: Option<Transform>,
+    pub degree: Option<usize>,
+    pub count: Option<usize>,
+    pub integer_roots: bool,
+    pub seed: Option<u64>,
+    pub jobs: Option<usize>,
+    pub out_dir: Option<String>,
+    pub no_progress: bool,
+    /// The local file counting degrees, parse errors and solvers used.
+    pub stats: Option<String>,
+    /// The warning categories not printed.
+    pub allowed: Vec<Category>,
+    /// Fail on the first warning left, for strict pipelines.
+    pub deny_warnings: bool,
+    pub max_length: Option<usize>,
+    pub max_terms: Option<usize>,
+    pub max_depth: Option<usize>,
+    pub max_degree: Option<usize>,
+    pub epsilon: Option<f32>,
+    /// Degree 1 and 2 solved in floating point, not from the decimals.
+    pub float: bool,
+    pub expected: Option<String>,
+}
+
+pub fn parse_args(args: Vec<String>) -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut equations = vec![];
+    let mut args = args.into_iter().peekable();
+    match args.peek().map(String::as_str) {
+        Some("diff") => options.command = Command::Diff,
+        Some("conic") => options.command = Command::Conic,
+        Some("common") => options.command = Command::Common,
+        Some("add") => options.command = Command::Add,
+        Some("sub") => options.command = Command::Sub,
+        Some("mul") => options.command = Command::Mul,
+        Some("shift") => options.command = Command::Shift,
+        Some("transform") => options.command = Command::Transform,
+        Some("discriminant") => options.command = Command::Discriminant,
+        Some("check-answer") => options.command = Command::CheckAnswer,
+        Some("batch") => options.command = Command::Batch,
+        Some("verify") => options.command = Command::Verify,
+        Some("grade") => options.command = Command::Grade,
+        Some("extract") => options.command = Command::Extract,
+        Some("generate") => options.command = Command::Generate,
+        Some("quiz") => options.command = Command::Quiz,
+        Some("repl") => options.command = Command::Repl,
+        Some("run") => options.command = Command::Run,
+        Some("schema") => options.command = Command::Schema,
+        Some("preview") => options.command = Command::Preview,
+        _ => (),
+    }
+    if options.command != Command::Solve {
+        args.next();
+    }
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--strict" => options.strict = true,
+            "--allow" => options
+                .allowed
+                .push(parse_choice(&arg, args.next(), &CATEGORIES)?),
+            "--deny-warnings" => options.deny_warnings = true,
+            "--max-length" => options.max_length = Some(parse_size(&arg, args.next())?),
+            "--max-terms" => options.max_terms = Some(parse_size(&arg, args.next())?),
+            "--max-depth" => options.max_depth = Some(parse_size(&arg, args.next())?),
+            "--max-degree" => options.max_degree = Some(parse_bounded(&arg, args.next(), DEGREES)?),
+            "--epsilon" => options.epsilon = Some(parse_number(&arg, args.next())?),
+            "--float" => options.float = true,
+            "--deterministic" => options.deterministic = true,
+            "--time" => options.time = true,
+            "--output" => options.output = Some(parse_output(&arg, args.next())?),
+            "--format" => {
+                options.format = parse_choice(&arg, args.next(), &FORMATS)?;
+            }
+            "--order" => {
+                options.order = Some(parse_choice(&arg, args.next(), &ORDERS)?);
+            }
+            "--pretty" => options.format = Format::Unicode,
+            "--positive-lead" => options.positive_lead = true,
+            "--set" => options.set_terms.push(parse_term(&arg, args.next())?),
+            "--add" => options.add_terms.push(parse_term(&arg, args.next())?),
+            "--steps" => options.steps = true,
+            "--rearrange" => options.rearrange = true,
+            "--formula" => options.formula = true,
+            "--explain-discriminant" => options.explain_discriminant = true,
+            "--complex" => options.complex = true,
+            "--events" => options.events = true,
+            "--precision" => {
+                options.precision = Some(parse_bounded(&arg, args.next(), PRECISIONS)?)
+            }
+            "--rounding" => {
+                options.rounding = parse_choice(&arg, args.next(), &ROUNDINGS)?;
+            }
+            "--continued-fraction" => {
+                options.continued_fraction = Some(parse_size(&arg, args.next())?)
+            }
+            "--residuals" => options.residuals = true,
+            "--plot" => options.plot = true,
+            "--plot-sides" => options.plot_sides = true,
+            "--argand" => options.argand = true,
+            "--animate" => {
+                let choices = [("newton", Animation::Newton)];
+                options.animate = Some(parse_choice(&arg, args.next(), &choices)?);
+            }
+            "--width" => options.width = Some(parse_size(&arg, args.next())?),
+            "--height" => options.height = Some(parse_size(&arg, args.next())?),
+            "--plot-svg" => options.plot_svg = Some(parse_path(&arg, args.next())?),
+            "--plot-png" => options.plot_png = Some(parse_path(&arg, args.next())?),
+            "--dpi" => options.dpi = Some(parse_bounded(&arg, args.next(), DPIS)?),
+            "--export-gnuplot" => options.export_gnuplot = Some(parse_path(&arg, args.next())?),
+            "--share" => options.share = true,
+            "--copy" => options.copy = true,
+            "--analyze" => options.analyze = true,
+            "--factor" => options.factor = true,
+            "--compare-methods" => options.compare_methods = true,
+            "--sign" => options.sign = true,
+            "--extrema" => options.extrema = true,
+            "--symmetric" => options.symmetric = true,
+            "--method" => options.method = Some(parse_choice(&arg, args.next(), &METHODS)?),
+            "--tree" => options.tree = true,
+            "--dot" => options.dot = true,
+            "--count-roots" => options.count_roots = Some(parse_interval(&arg, &mut args)?),
+            "--has-root" => options.has_root = Some(parse_interval(&arg, &mut args)?),
+            "--domain" => options.domain = Some(Domain::parse(&parse_path(&arg, args.next())?)?),
+            "--roots" => options.roots = parse_roots(&arg, &parse_path(&arg, args.next())?)?,
+            "--reciprocal" => set_transform(&mut options, Transform::Reciprocal)?,
+            "--negate" => set_transform(&mut options, Transform::Negate)?,
+            "--scale" => {
+                let k = parse_number(&arg, args.next())?;
+                if k == 0.0 {
+                    return Err(String::from(
+                        "Invalid value for --scale: 0, every root would be 0",
+                    ));
+                }
+                set_transform(&mut options, Transform::Scale(k))?
+            }
+            "--by" => options.by = Some(parse_number(&arg, args.next())?),
+            "--tolerance" => options.tolerance = Some(parse_number(&arg, args.next())?),
+            "--degree" => options.degree = Some(parse_size(&arg, args.next())?),
+            "--count" => options.count = Some(parse_size(&arg, args.next())?),
+            "--integer-roots" => options.integer_roots = true,
+            "--jobs" => options.jobs = Some(parse_size(&arg, args.next())?),
+            "--no-progress" => options.no_progress = true,
+            "--stats" => options.stats = Some(parse_path(&arg, args.next())?),
+            "--out-dir" => options.out_dir = Some(parse_path(&arg, args.next())?),
+            "--input" => equations.push(parse_path(&arg, args.next())?),
+            "--expected" => options.expected = Some(parse_path(&arg, args.next())?),
+            "--seed" => options.seed = Some(parse_size(&arg, args.next())? as u64),
+            flag if flag.starts_with("--") => return Err(format!("Unknown option: {}", flag)),
+            _ => equations.push(arg),
+        }
+    }
+    if matches!(
+        options.command,
+        Command::Generate | Command::Quiz | Command::Repl
+    ) {
+        if !equations.is_empty() {
+            return Err(String::from("Wrong numbers of arguments"));
+        }
+        return Ok(options);
+    }
+    if options.command == Command::Schema {
+        if equations.len() > 1 {
+            return Err(String::from("Wrong numbers of arguments"));
+        }
+        options.equation = equations.pop().unwrap_or_default();
+        return Ok(options);
+    }
+    if options.command == Command::Diff && equations.len() == 2 {
+        options.second = equations.remove(1);
+    }
+    if matches!(
+        options.command,
+        Command::Common | Command::Add | Command::Sub | Command::Mul
+    ) {
+        if equations.len() != 2 {
+            return Err(String::from("Wrong numbers of arguments"));
+        }
+        options.second = equations.remove(1);
+    }
+    if equations.len() != 1 {
+        return Err(String::from("Wrong numbers of arguments"));
+    }
+    if options.command == Command::Verify && options.expected.is_none() {
+        return Err(String::from("Missing --expected"));
+    }
+    if options.command == Command::Shift && options.by.is_none() {
+        return Err(String::from("Missing --by"));
+    }
+    if options.command == Command::Transform && options.transform.is_none() {
+        return Err(String::from("Missing --reciprocal, --negate or --scale"));
+    }
+    // both go to stdout, where they would break the record
+    if (options.tree || options.dot) && options.output_name() != "text" {
+        return Err(format!(
+            "--tree and --dot cannot be given with --output {}",
+            options.output_name()
+        ));
+    }
+    options.equation = equations.remove(0);
+    Ok(options)
+}
+
+impl Options {
+    pub fn output_name(&self) -> &str {
+        self.output.as_deref().unwrap_or("text")
+    }
+
+    /// The plot canvas, fitted to the terminal unless the output has to be
+    /// the same everywhere.
+    pub fn plot_size(&self) -> Size {
+        if self.deterministic {
+            Size::fixed(self.width, self.height)
+        } else {
+            Size::detect(self.width, self.height)
+        }
+    }
+
+    /// Pretty output reads best highest degree first, the subject format
+    /// keeps the ascending order of the subject examples.
+    pub fn style(&self) -> Style {
+        let order = match self.format {
+            Format::Subject => Order::Ascending,
+            Format::Pretty | Format::Unicode => Order::Descending,
+        };
+        Style {
+            format: self.format,
+            order: self.order.unwrap_or(order),
+        }
+    }
+
+    /// The flags that change how one equation is solved, as passed to
+    /// `solve::solve`.
+    /// `--set`, `--add` and `--positive-lead`, in that order.
+    pub fn edits(&self) -> Vec<Edit> {
+        let set = self.set_terms.iter().map(|&(d, value)| Edit::Set(d, value));
+        let add = self.add_terms.iter().map(|&(d, value)| Edit::Add(d, value));
+        let lead = self.positive_lead.then_some(Edit::PositiveLead);
+        set.chain(add).chain(lead).collect()
+    }
+
+    /// Those of `--max-length` and the like, the default ones for the
+    /// others.
+    pub fn limits(&self) -> Limits {
+        Limits {
+            length: self.max_length.unwrap_or(expr::DEFAULT_LIMITS.length),
+            terms: self.max_terms.unwrap_or(expr::DEFAULT_LIMITS.terms),
+            depth: self.max_depth.unwrap_or(expr::DEFAULT_LIMITS.depth),
+            degree: self.max_degree.unwrap_or(expr::DEFAULT_LIMITS.degree),
+        }
+    }
+
+    pub fn solve_options(&self) -> SolveOptions {
+        let mut options = SolveOptions::new()
+            .limits(self.limits())
+            .style(self.style())
+            .rounding(self.rounding)
+            .method(self.method.unwrap_or(Method::Auto))
+            .exact(!self.float)
+            .complex(self.complex)
+            .steps(self.steps);
+        if let Some(digits) = self.precision {
+            options = options.precision(digits);
+        }
+        if let Some(epsilon) = self.epsilon {
+            options = options.epsilon(epsilon as f64);
+        }
+        options
+    }
+}
+
+fn set_transform(options: &mut Options, transform: Transform) -> Result<(), String> {
+    if options.transform.is_some() {
+        return Err(String::from(
+            "Only one of --reciprocal, --negate and --scale can be given",
+        ));
+    }
+    options.transform = Some(transform);
+    Ok(())
+}
+
+/// The two numbers after the flag, a closed interval `[a, b]`.
+fn parse_interval(
+    flag: &str,
+    args: &mut impl Iterator<Item = String>,
+) -> Result<(f32, f32), String> {
+    let a = parse_number(flag, args.next())?;
+    let b = parse_number(flag, args.next())?;
+    if a > b {
+        return Err(format!(
+            "Empty interval for {}: [{}, {}]",
+            flag,
+            float::shortest(a),
+            float::shortest(b)
+        ));
+    }
+    Ok((a, b))
+}
+
+pub fn parse_choice<T: Copy>(
+    option: &str,
+    value: Option<String>,
+    choices: &[(&str, T)],
+) -> Result<T, String> {
+    let value = value.ok_or(format!("Missing value for {}", option))?;
+    match choices.iter().find(|(name, _)| *name == value) {
+        Some((_, choice)) => Ok(*choice),
+        None => Err(format!("Invalid value for {}: {}", option, value)),
+    }
+}
+
+fn parse_output(option: &str, value: Option<String>) -> Result<String, String> {
+    let value = value.ok_or(format!("Missing value for {}", option))?;
+    match Renderers::default().get(&value) {
+        Some(_) => Ok(value),
+        None => Err(format!("Invalid value for {}: {}", option, value)),
+    }
+}
+
+/// A finite number, `nan` and `inf` being refused like any other word.
+fn parse_number(option: &str, value: Option<String>) -> Result<f32, String> {
+    match value {
+        Some(value) => value
+            .parse::<f32>()
+            .ok()
+            .filter(|number| number.is_finite())
+            .ok_or_else(|| format!("Invalid value for {}: {}", option, value)),
+        None => Err(format!("Missing value for {}", option)),
+    }
+}
+
+/// Comma separated numbers, an empty list being written as an empty string.
+pub fn parse_roots(option: &str, value: &str) -> Result<Vec<f32>, String> {
+    value
+        .split(',')
+        .filter(|root| !root.trim().is_empty())
+        .map(|root| parse_number(option, Some(root.trim().to_string())))
+        .collect()
+}
+
+/// A `degree:value` pair, such as `2:-9.3` for `-9.3 * X^2`.
+fn parse_term(option: &str, value: Option<String>) -> Result<(usize, f32), String> {
+    let value = parse_path(option, value)?;
+    let invalid = || format!("Invalid value for {}: {}", option, value);
+    let (degree, coefficient) = value.split_once(':').ok_or_else(invalid)?;
+    match (degree.parse(), coefficient.parse::<f32>()) {
+        (Ok(degree), Ok(coefficient)) if coefficient.is_finite() => Ok((degree, coefficient)),
+        _ => Err(invalid()),
+    }
+}
+
+fn parse_path(option: &str, value: Option<String>) -> Result<String, String> {
+    value.ok_or(format!("Missing value for {}", option))
+}
+
+pub fn parse_size(option: &str, value: Option<String>) -> Result<usize, String> {
+    match value {
+        Some(value) => value
+            .parse::<usize>()
+            .map_err(|_| format!("Invalid value for {}: {}", option, value)),
+        None => Err(format!("Missing value for {}", option)),
+    }
+}
+
+/// A size within `range`.
+pub fn parse_bounded(
+    option: &str,
+    value: Option<String>,
+    range: RangeInclusive<usize>,
+) -> Result<usize, String> {
+    let size = parse_size(option, value)?;
+    match range.contains(&size) {
+        true => Ok(size),
+        false => Err(format!(
+            "Invalid value for {}: {}, expected {} to {}",
+            option,
+            size,
+            range.start(),
+            range.end()
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &[&str]) -> Vec<String> {
+        line.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_count_roots() {
+        let options = parse_args(args(&["--count-roots", "-1", "2.5", "X^2 = 1"])).unwrap();
+        assert_eq!(options.count_roots, Some((-1.0, 2.5)));
+        assert_eq!(options.equation, "X^2 = 1");
+        assert!(parse_args(args(&["--count-roots", "1", "X^2 = 1"])).is_err());
+        assert!(parse_args(args(&["--count-roots", "3", "2", "X^2 = 1"])).is_err());
+        assert_eq!(
+            parse_args(args(&["--count-roots", "nan", "1", "X^2 = 1"])),
+            Err(String::from("Invalid value for --count-roots: nan"))
+        );
+        assert!(parse_args(args(&["--has-root", "nan", "nan", "X^2 = 1"])).is_err());
+        assert!(parse_args(args(&["--has-root", "-inf", "1e39", "X^2 = 1"])).is_err());
+        assert!(parse_args(args(&["--set", "2:nan", "X^2 = 1"])).is_err());
+        let options = parse_args(args(&["--has-root", "0", "1", "X^2 = 1"])).unwrap();
+        assert_eq!(options.has_root, Some((0.0, 1.0)));
+        let options = parse_args(args(&["--domain", "0..", "X^2 = 1"])).unwrap();
+        assert_eq!(options.domain.unwrap().to_string(), "[0, ∞)");
+        assert!(parse_args(args(&["--domain", "X^2 = 1"])).is_err());
+    }
+
+    #[test]
+    fn parse_method() {
+        let options = parse_args(args(&["--method", "auto", "X^4 = 1"])).unwrap();
+        assert_eq!(options.method, Some(Method::Auto));
+        let options = parse_args(args(&["--method", "durand-kerner", "X^4 = 1"])).unwrap();
+        assert_eq!(options.method, Some(Method::DurandKerner));
+        assert!(parse_args(args(&["--method", "newton", "X^4 = 1"])).is_err());
+        assert!(
+            parse_args(args(&["--compare-methods", "X^4 = 1"]))
+                .unwrap()
+                .compare_methods
+        );
+        assert!(parse_args(args(&["--sign", "X^4 = 1"])).unwrap().sign);
+        assert!(parse_args(args(&["--factor", "X^4 = 1"])).unwrap().factor);
+        assert!(parse_args(args(&["--extrema", "X^4 = 1"])).unwrap().extrema);
+        assert!(
+            parse_args(args(&["--symmetric", "X^4 = 1"]))
+                .unwrap()
+                .symmetric
+        );
+    }
+
+    #[test]
+    fn parse_common_command() {
+        let options = parse_args(args(&["common", "X^2 - 1", "X + 1"])).unwrap();
+        assert_eq!(options.command, Command::Common);
+        assert_eq!(
+            (options.equation.as_str(), options.second.as_str()),
+            ("X^2 - 1", "X + 1")
+        );
+        assert!(parse_args(args(&["common", "X^2 - 1"])).is_err());
+        let options = parse_args(args(&["mul", "X - 1", "X + 1"])).unwrap();
+        assert_eq!(options.command, Command::Mul);
+        assert_eq!(options.second, "X + 1");
+    }
+
+    #[test]
+    fn parse_shift_command() {
+        let options = parse_args(args(&["shift", "X^2 - 1", "--by", "-2.5"])).unwrap();
+        assert_eq!(options.command, Command::Shift);
+        assert_eq!(options.by, Some(-2.5));
+        assert!(parse_args(args(&["shift", "X^2 - 1"])).is_err());
+        assert_eq!(
+            parse_args(args(&["shift", "X^2 - 1", "--by", "nan"])),
+            Err(String::from("Invalid value for --by: nan"))
+        );
+    }
+
+    #[test]
+    fn parse_transform_command() {
+        let options = parse_args(args(&["transform", "X^2 - 1", "--scale", "3"])).unwrap();
+        assert_eq!(options.command, Command::Transform);
+        assert_eq!(options.transform, Some(Transform::Scale(3.0)));
+        let line = ["transform", "X^2 - 1", "--negate", "--reciprocal"];
+        assert!(parse_args(args(&line)).is_err());
+        assert!(parse_args(args(&["transform", "X^2 - 1"])).is_err());
+        for k in ["0", "-0", "nan", "inf"] {
+            assert!(parse_args(args(&["transform", "X^2 - 1", "--scale", k])).is_err());
+        }
+    }
+
+    #[test]
+    fn parse_diff_of_two_equations() {
+        let options = parse_args(args(&["diff", "X = 1", "2 * X = 2"])).unwrap();
+        assert_eq!(options.command, Command::Diff);
+        assert_eq!(options.second, "2 * X = 2");
+        assert!(parse_args(args(&["diff", "X", "X", "X"])).is_err());
+    }
+
+    #[test]
+    fn parse_diff_command() {
+        let options = parse_args(args(&["diff", "(X - 1)^3"])).unwrap();
+        assert_eq!(options.command, Command::Diff);
+        assert_eq!(options.equation, "(X - 1)^3");
+        assert_eq!(
+            parse_args(args(&["X = 1"])).unwrap().command,
+            Command::Solve
+        );
+    }
+
+    #[test]
+    fn parse_check_answer() {
+        let line = ["check-answer", "X^2 - 4 = 0", "--roots", "2, -2"];
+        let options = parse_args(args(&line)).unwrap();
+        assert_eq!(options.command, Command::CheckAnswer);
+        assert_eq!(options.roots, vec![2.0, -2.0]);
+        assert_eq!(options.tolerance, None);
+        assert!(parse_args(args(&["check-answer", "X = 1", "--roots", "1,a"])).is_err());
+    }
+
+    #[test]
+    fn parse_extract() {
+        let options = parse_args(args(&["extract", "notes.md"])).unwrap();
+        assert_eq!(options.command, Command::Extract);
+        assert_eq!(options.equation, "notes.md");
+        assert!(parse_args(args(&["extract"])).is_err());
+        let options = parse_args(args(&["preview", "5 * X^2 +"])).unwrap();
+        assert_eq!(options.command, Command::Preview);
+        assert_eq!(options.equation, "5 * X^2 +");
+    }
+
+    #[test]
+    fn parse_generate() {
+        let line = [
+            "generate",
+            "--degree",
+            "3",
+            "--count",
+            "5",
+            "--integer-roots",
+        ];
+        let options = parse_args(args(&line)).unwrap();
+        assert_eq!(options.command, Command::Generate);
+        assert_eq!((options.degree, options.count), (Some(3), Some(5)));
+        assert!(options.integer_roots);
+        assert!(parse_args(args(&["generate", "X = 1"])).is_err());
+        assert_eq!(parse_args(args(&["repl"])).unwrap().command, Command::Repl);
+        let options = parse_args(args(&["batch", "-", "--out-dir", "results/"])).unwrap();
+        assert_eq!(options.out_dir.as_deref(), Some("results/"));
+        assert!(!options.no_progress);
+        let options = parse_args(args(&["batch", "-", "--stats", "stats.json"])).unwrap();
+        assert_eq!(options.stats.as_deref(), Some("stats.json"));
+        assert!(
+            parse_args(args(&["batch", "-", "--no-progress"]))
+                .unwrap()
+                .no_progress
+        );
+        let line = ["--allow", "zero-term", "--deny-warnings", "X = 1"];
+        let options = parse_args(args(&line)).unwrap();
+        assert_eq!(options.allowed, [Category::ZeroTerm]);
+        assert!(options.deny_warnings);
+        assert!(parse_args(args(&["--allow", "typos", "X = 1"])).is_err());
+        let options = parse_args(args(&["--max-depth", "8", "X = 1"])).unwrap();
+        assert_eq!((options.max_length, options.max_depth), (None, Some(8)));
+        let options = parse_args(args(&["--max-degree", "20", "X = 1"])).unwrap();
+        assert_eq!(options.max_degree, Some(20));
+        assert_eq!(options.limits().degree, 20);
+        assert_eq!(
+            options.solve_options(),
+            SolveOptions::new().limits(options.limits())
+        );
+        assert_eq!(
+            parse_args(args(&["--max-degree", "100000000", "X = 1"])),
+            Err(String::from(
+                "Invalid value for --max-degree: 100000000, expected 0 to 1000"
+            ))
+        );
+        let line = [
+            "--epsilon",
+            "1e-3",
+            "--float",
+            "--method",
+            "bracketing",
+            "X = 1",
+        ];
+        let options = parse_args(args(&line)).unwrap();
+        let expected = SolveOptions::new()
+            .epsilon(1e-3f32 as f64)
+            .exact(false)
+            .method(Method::Bracketing);
+        assert_eq!(options.solve_options(), expected);
+        let line = ["verify", "--input", "eqs.txt", "--expected", "results.json"];
+        let options = parse_args(args(&line)).unwrap();
+        assert_eq!(options.command, Command::Verify);
+        assert_eq!(options.equation, "eqs.txt");
+        assert_eq!(options.expected.as_deref(), Some("results.json"));
+        assert!(parse_args(args(&["verify", "--input", "eqs.txt"])).is_err());
+        let options = parse_args(args(&["run", "notes.cmp"])).unwrap();
+        assert_eq!(options.command, Command::Run);
+        assert_eq!(options.equation, "notes.cmp");
+    }
+
+    #[test]
+    fn parse_precision() {
+        let line = ["--precision", "3", "--rounding", "half-even", "X = 1"];
+        let options = parse_args(args(&line)).unwrap();
+        assert_eq!(options.precision, Some(3));
+        assert_eq!(options.rounding, Rounding::HalfEven);
+        assert_eq!(
+            parse_args(args(&["X = 1"])).unwrap().rounding,
+            Rounding::HalfUp
+        );
+        assert!(parse_args(args(&["--rounding", "down", "X = 1"])).is_err());
+        let error = parse_args(args(&["--precision", "1001", "X = 1"])).unwrap_err();
+        assert_eq!(
+            error,
+            "Invalid value for --precision: 1001, expected 0 to 1000"
+        );
+        let line = ["--continued-fraction", "6", "X^2 = 2"];
+        assert_eq!(parse_args(args(&line)).unwrap().continued_fraction, Some(6));
+    }
+
+    #[test]
+    fn parse_terms() {
+        let line = ["--set", "2:-9.3", "--add", "0:1", "--set", "1:4", "X = 1"];
+        let options = parse_args(args(&line)).unwrap();
+        assert_eq!(options.set_terms, vec![(2, -9.3), (1, 4.0)]);
+        assert_eq!(options.add_terms, vec![(0, 1.0)]);
+        assert!(parse_args(args(&["--set", "2", "X = 1"])).is_err());
+        assert!(parse_args(args(&["--add", "-1:2", "X = 1"])).is_err());
+    }
+
+    #[test]
+    fn parse_order() {
+        let options = parse_args(args(&["--order", "desc", "X = 1"])).unwrap();
+        assert_eq!(options.style().order, Order::Descending);
+        let options = parse_args(args(&["X = 1"])).unwrap();
+        assert_eq!(options.style().order, Order::Ascending);
+        let options = parse_args(args(&["--format", "pretty", "X = 1"])).unwrap();
+        assert_eq!(options.style().order, Order::Descending);
+        let options = parse_args(args(&["--format", "pretty", "--order", "asc", "X = 1"])).unwrap();
+        assert_eq!(options.style().order, Order::Ascending);
+        assert!(parse_args(args(&["--order", "up", "X = 1"])).is_err());
+    }
+
+    #[test]
+    fn parse_output() {
+        let options = parse_args(args(&["--output", "latex", "X = 1"])).unwrap();
+        assert_eq!(options.output_name(), "latex");
+        let options = parse_args(args(&["X = 1"])).unwrap();
+        assert_eq!(options.output_name(), "text");
+        let error = parse_args(args(&["--output", "yaml", "X = 1"])).unwrap_err();
+        assert_eq!(error, "Invalid value for --output: yaml");
+        let error = parse_args(args(&["--output", "json", "--dot", "X = 1"])).unwrap_err();
+        assert_eq!(error, "--tree and --dot cannot be given with --output json");
+        assert!(parse_args(args(&["--output", "text", "--tree", "X = 1"])).is_ok());
+    }
+
+    #[test]
+    fn deterministic_ignores_terminal() {
+        let options = parse_args(args(&["--deterministic", "--height", "9", "X = 1"])).unwrap();
+        assert!(options.deterministic);
+        assert_eq!(options.plot_size(), Size::fixed(None, Some(9)));
+    }
+
+    #[test]
+    fn parse_plot_size() {
+        let options = parse_args(args(&["--plot", "--width", "80", "X = 1"])).unwrap();
+        assert_eq!((options.width, options.height), (Some(80), None));
+        assert!(parse_args(args(&["--height", "-3", "X = 1"])).is_err());
+        let options = parse_args(args(&["--animate", "newton", "X = 1"])).unwrap();
+        assert_eq!(options.animate, Some(Animation::Newton));
+        let options =
+            parse_args(args(&["--plot-png", "out.png", "--dpi", "200", "X = 1"])).unwrap();
+        assert_eq!(options.plot_png.as_deref(), Some("out.png"));
+        assert_eq!(options.dpi, Some(200));
+        assert_eq!(
+            parse_args(args(&["--dpi", "0", "X = 1"])),
+            Err(String::from(
+                "Invalid value for --dpi: 0, expected 1 to 2400"
+            ))
+        );
+        assert!(parse_args(args(&["--dpi", "100000", "X = 1"])).is_err());
+        assert!(parse_args(args(&["X = 1", "--plot-svg"])).is_err());
+    }
+
+    #[test]
+    fn reject_unknown_option() {
+        assert!(parse_args(args(&["--foo", "X = 1"])).is_err());
+        assert!(parse_args(args(&["X = 1", "X = 2"])).is_err());
+    }
+}
@@ -0,0 +1,307 @@
+use crate::expr::Expr;
+use crate::float;
+use crate::ParseError;
+
+/// Relative tolerance under which an invariant counts as zero.
+const EPSILON: f64 = 1e-9;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Kind {
+    Circle,
+    Ellipse,
+    Parabola,
+    Hyperbola,
+    /// No real point, as `X^2 + Y^2 + 1 = 0`
+    ImaginaryEllipse,
+    Point,
+    IntersectingLines,
+    ParallelLines,
+    CoincidentLines,
+    /// No real point, as `X^2 + 1 = 0`
+    ImaginaryParallelLines,
+    /// No quadratic term left after reduction
+    NotQuadratic,
+}
+
+/// The reduced equation `AX^2 + BXY + CY^2 + DX + EY + F = 0`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conic {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub d: f64,
+    pub e: f64,
+    pub f: f64,
+}
+
+impl Conic {
+    /// Reduces both sides to a polynomial in `X` and `Y`, which must be of
+    /// total degree at most 2.
+    pub fn new(left: &Expr, right: &Expr) -> Result<Conic, ParseError> {
+        let equation = Expr::Sub(Box::new(left.clone()), Box::new(right.clone()));
+        let coefficients = equation.expand_in_two_variables()?;
+        let coefficient = |i: usize, j: usize| {
+            coefficients
+                .get(i)
+                .and_then(|row| row.get(j))
+                .map_or(0.0, |&c| c as f64)
+        };
+        for (i, row) in coefficients.iter().enumerate() {
+            for (j, c) in row.iter().enumerate() {
+                if i + j > 2 && *c != 0.0 {
+                    return Err(ParseError::InvalidExponent);
+                }
+            }
+        }
+        Ok(Conic {
+            a: coefficient(2, 0),
+            b: coefficient(1, 1),
+            c: coefficient(0, 2),
+            d: coefficient(1, 0),
+            e: coefficient(0, 1),
+            f: coefficient(0, 0),
+        })
+    }
+
+    fn scale(&self) -> f64 {
+        [self.a, self.b, self.c, self.d, self.e, self.f]
+            .iter()
+            .fold(0.0, |scale: f64, c| scale.max(c.abs()))
+    }
+
+    /// `B^2 - 4AC`, whose sign tells ellipses, parabolas and hyperbolas apart.
+    pub fn discriminant(&self) -> f64 {
+        self.b * self.b - 4.0 * self.a * self.c
+    }
+
+    /// Determinant of the symmetric matrix of the conic, zero when it
+    /// degenerates into lines or a point.
+    fn determinant(&self) -> f64 {
+        let (a, b, c) = (self.a, self.b / 2.0, self.c);
+        let (d, e, f) = (self.d / 2.0, self.e / 2.0, self.f);
+        a * (c * f - e * e) - b * (b * f - e * d) + d * (b * e - c * d)
+    }
+
+    pub fn kind(&self) -> Kind {
+        let scale = self.scale();
+        if [self.a, self.b, self.c]
+            .iter()
+            .all(|c| c.abs() <= EPSILON * scale)
+        {
+            return Kind::NotQuadratic;
+        }
+        let discriminant = self.discriminant();
+        let determinant = self.determinant();
+        let degenerate = determinant.abs() <= EPSILON * scale.powi(3);
+        if discriminant.abs() <= EPSILON * scale * scale {
+            if !degenerate {
+                return Kind::Parabola;
+            }
+            let k = self.d * self.d + self.e * self.e - 4.0 * (self.a + self.c) * self.f;
+            return match k {
+                k if k.abs() <= EPSILON * scale * scale => Kind::CoincidentLines,
+                k if k > 0.0 => Kind::ParallelLines,
+                _ => Kind::ImaginaryParallelLines,
+            };
+        }
+        match (discriminant < 0.0, degenerate) {
+            (true, true) => Kind::Point,
+            (true, false) if (self.a + self.c) * determinant > 0.0 => Kind::ImaginaryEllipse,
+            (true, false) if self.b == 0.0 && self.a == self.c => Kind::Circle,
+            (true, false) => Kind::Ellipse,
+            (false, true) => Kind::IntersectingLines,
+            (false, false) => Kind::Hyperbola,
+        }
+    }
+
+    /// The center of symmetry, for ellipses, hyperbolas and their
+    /// degenerate forms.
+    pub fn center(&self) -> Option<(f64, f64)> {
+        let denominator = 4.0 * self.a * self.c - self.b * self.b;
+        if denominator.abs() <= EPSILON * self.scale() * self.scale() {
+            return None;
+        }
+        Some((
+            (self.b * self.e - 2.0 * self.c * self.d) / denominator,
+            (self.b * self.d - 2.0 * self.a * self.e) / denominator,
+        ))
+    }
+
+    /// The angle in degrees from the X axis to the first axis of the conic,
+    /// zero when the axes are already those of the plane.
+    pub fn rotation(&self) -> f64 {
+        if self.b == 0.0 {
+            return 0.0;
+        }
+        (self.b.atan2(self.a - self.c) / 2.0).to_degrees()
+    }
+
+    /// The semi-axes along the first axis and the second one, ellipses and
+    /// hyperbolas only. Semi-axes of a hyperbola are returned as negative
+    /// along its conjugate axis.
+    pub fn semi_axes(&self) -> Option<(f64, f64)> {
+        let (x, y) = self.center()?;
+        let constant = self.f + (self.d * x + self.e * y) / 2.0;
+        let angle = self.rotation().to_radians();
+        let (cos, sin) = (angle.cos(), angle.sin());
+        let first = self.a * cos * cos + self.b * cos * sin + self.c * sin * sin;
+        let second = self.a + self.c - first;
+        let semi_axis = |eigenvalue: f64| {
+            let square = -constant / eigenvalue;
+            square.abs().sqrt().copysign(square)
+        };
+        Some((semi_axis(first), semi_axis(second)))
+    }
+
+    /// The vertex of a parabola whose axis is parallel to the X or Y axis.
+    pub fn vertex(&self) -> Option<(f64, f64)> {
+        if self.b != 0.0 {
+            return None;
+        }
+        if self.c == 0.0 && self.e != 0.0 {
+            let x = -self.d / (2.0 * self.a);
+            Some((x, -(self.a * x * x + self.d * x + self.f) / self.e))
+        } else if self.a == 0.0 && self.d != 0.0 {
+            let y = -self.e / (2.0 * self.c);
+            Some((-(self.c * y * y + self.e * y + self.f) / self.d, y))
+        } else {
+            None
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        let kind = self.kind();
+        let name = match kind {
+            Kind::Circle => "circle",
+            Kind::Ellipse => "ellipse",
+            Kind::Parabola => "parabola",
+            Kind::Hyperbola => "hyperbola",
+            Kind::ImaginaryEllipse => "imaginary ellipse, no real point",
+            Kind::Point => "degenerate, a single point",
+            Kind::IntersectingLines => "degenerate, two intersecting lines",
+            Kind::ParallelLines => "degenerate, two parallel lines",
+            Kind::CoincidentLines => "degenerate, a single line",
+            Kind::ImaginaryParallelLines => "degenerate, no real point",
+            Kind::NotQuadratic => "not a conic, there is no quadratic term",
+        };
+        let mut output = format!("Conic: {}\n", name);
+        let point = |(x, y): (f64, f64)| format!("({}, {})", x as f32 + 0.0, y as f32 + 0.0);
+        let rotation = self.rotation();
+        // primes name the axes of the conic when they are rotated
+        let (x, y) = if rotation == 0.0 {
+            ("X", "Y")
+        } else {
+            ("X'", "Y'")
+        };
+        match kind {
+            Kind::Circle | Kind::Ellipse | Kind::Hyperbola | Kind::Point => {
+                if let Some(center) = self.center() {
+                    output += &format!("Center: {}\n", point(center));
+                }
+            }
+            Kind::Parabola => {
+                if let Some(vertex) = self.vertex() {
+                    output += &format!("Vertex: {}\n", point(vertex));
+                }
+            }
+            _ => (),
+        }
+        if rotation != 0.0 && matches!(kind, Kind::Ellipse | Kind::Hyperbola | Kind::Parabola) {
+            output += &format!(
+                "Axes rotated by {} degrees\n",
+                float::shortest(rotation as f32)
+            );
+        }
+        match (kind, self.semi_axes()) {
+            (Kind::Circle, Some((r, _))) => {
+                output += &format!("Radius: {}\n", float::shortest(r as f32))
+            }
+            (Kind::Ellipse, Some((a, b))) => {
+                output += &format!(
+                    "Semi-axes: {} along {}, {} along {}\n",
+                    float::shortest(a as f32),
+                    x,
+                    float::shortest(b as f32),
+                    y
+                )
+            }
+            (Kind::Hyperbola, Some((a, b))) => {
+                let (a, b) = (
+                    float::shortest(a.abs() as f32),
+                    float::shortest(b.abs() as f32),
+                );
+                let transverse = if self.semi_axes().is_some_and(|(a, _)| a > 0.0) {
+                    x
+                } else {
+                    y
+                };
+                output += &format!("Semi-axes: {} along {}, {} along {}\n", a, x, b, y);
+                output += &format!("Transverse axis: {}\n", transverse);
+            }
+            _ => (),
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr;
+
+    fn conic(line: &str) -> Conic {
+        let (left, right) = expr::check_equation_in_two_variables(line).unwrap();
+        Conic::new(&left, &right).unwrap()
+    }
+
+    #[test]
+    fn classify_conics() {
+        assert_eq!(conic("X^2 + Y^2 - 4 = 0").kind(), Kind::Circle);
+        assert_eq!(conic("X^2 + 4Y^2 = 4").kind(), Kind::Ellipse);
+        assert_eq!(conic("Y = X^2").kind(), Kind::Parabola);
+        assert_eq!(conic("X * Y = 1").kind(), Kind::Hyperbola);
+        assert_eq!(conic("X^2 + Y^2 + 1 = 0").kind(), Kind::ImaginaryEllipse);
+        assert_eq!(conic("X^2 + Y^2 = 0").kind(), Kind::Point);
+        assert_eq!(conic("X^2 = Y^2").kind(), Kind::IntersectingLines);
+        assert_eq!(conic("X^2 = 1").kind(), Kind::ParallelLines);
+        assert_eq!(conic("(X - Y)^2 = 0").kind(), Kind::CoincidentLines);
+        assert_eq!(conic("X^2 + 1 = 0").kind(), Kind::ImaginaryParallelLines);
+        assert_eq!(conic("X + Y = 1").kind(), Kind::NotQuadratic);
+    }
+
+    #[test]
+    fn describe_geometry() {
+        assert_eq!(
+            conic("(X - 1)^2 + (Y + 2)^2 = 9").describe(),
+            "Conic: circle\nCenter: (1, -2)\nRadius: 3\n"
+        );
+        assert_eq!(
+            conic("X^2 / 4 - Y^2 / 9 = 1").describe(),
+            "Conic: hyperbola\nCenter: (0, 0)\nSemi-axes: 2 along X, 3 along Y\nTransverse axis: X\n"
+        );
+        assert_eq!(
+            conic("X^2 + 4Y^2 - 2X = 3").describe(),
+            "Conic: ellipse\nCenter: (1, 0)\nSemi-axes: 2 along X, 1 along Y\n"
+        );
+        assert_eq!(
+            conic("Y = X^2 - 2X").describe(),
+            "Conic: parabola\nVertex: (1, -1)\n"
+        );
+        assert_eq!(conic("X * Y = 1").rotation(), 45.0);
+        let (left, right) = expr::check_equation_in_two_variables("X^3 + Y = 0").unwrap();
+        assert!(Conic::new(&left, &right).is_err());
+    }
+
+    #[test]
+    fn y_only_in_two_variables() {
+        let diagnostics = expr::check_equation("X + Y = 0").unwrap_err();
+        assert_eq!(
+            diagnostics[0].help.as_deref(),
+            Some("an equation in X and Y is classified by `./computor conic`")
+        );
+        assert!(expr::check_equation("X + Z = 0").unwrap_err()[0]
+            .help
+            .is_none());
+        assert!(expr::check_equation_in_two_variables("2XY + 3Y(X - 1) = 0").is_ok());
+    }
+}
@@ -0,0 +1,103 @@
+use crate::float;
+use std::fmt;
+
+/// The closed interval the solutions must lie in, for physical constraints
+/// like a time that cannot be negative. An end left out is infinite.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Domain {
+    pub start: f32,
+    pub end: f32,
+}
+
+impl Domain {
+    /// `a..b`, `a..` or `..b`.
+    pub fn parse(text: &str) -> Result<Domain, String> {
+        let invalid = || format!("Invalid value for --domain: {}", text);
+        let (start, end) = text.split_once("..").ok_or_else(invalid)?;
+        let end_point = |end: &str, infinity: f32| match end.trim() {
+            "" => Ok(infinity),
+            end => end
+                .parse::<f32>()
+                .ok()
+                .filter(|end| end.is_finite())
+                .ok_or_else(invalid),
+        };
+        let domain = Domain {
+            start: end_point(start, f32::NEG_INFINITY)?,
+            end: end_point(end, f32::INFINITY)?,
+        };
+        if domain.start > domain.end {
+            return Err(format!("Empty interval for --domain: {}", domain));
+        }
+        Ok(domain)
+    }
+
+    /// Why `root` is outside the domain, `None` when it is inside.
+    pub fn exclusion(&self, root: f32) -> Option<String> {
+        if root < self.start {
+            Some(format!("below {}", float::shortest(self.start)))
+        } else if root > self.end {
+            Some(format!("above {}", float::shortest(self.end)))
+        } else {
+            None
+        }
+    }
+
+    /// The roots inside the domain, and the others with their reason.
+    pub fn split(&self, roots: &[f32]) -> (Vec<f32>, Vec<(f32, String)>) {
+        let mut inside = vec![];
+        let mut excluded = vec![];
+        for &root in roots {
+            match self.exclusion(root) {
+                Some(reason) => excluded.push((root, reason)),
+                None => inside.push(root),
+            }
+        }
+        (inside, excluded)
+    }
+}
+
+/// `[0, ∞)`, the infinite ends open.
+impl fmt::Display for Domain {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.start.is_finite() {
+            true => write!(f, "[{}", float::shortest(self.start))?,
+            false => write!(f, "(-∞")?,
+        }
+        match self.end.is_finite() {
+            true => write!(f, ", {}]", float::shortest(self.end)),
+            false => write!(f, ", ∞)"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsed_with_open_ends() {
+        let domain = Domain::parse("0..10").unwrap();
+        assert_eq!(domain.to_string(), "[0, 10]");
+        assert_eq!(Domain::parse("0..").unwrap().to_string(), "[0, ∞)");
+        assert_eq!(Domain::parse("..-1.5").unwrap().to_string(), "(-∞, -1.5]");
+        assert_eq!(Domain::parse("..").unwrap().to_string(), "(-∞, ∞)");
+        assert!(Domain::parse("1..0").is_err());
+        assert!(Domain::parse("0").is_err());
+        assert!(Domain::parse("a..1").is_err());
+    }
+
+    #[test]
+    fn roots_split_with_reasons() {
+        let domain = Domain::parse("0..10").unwrap();
+        let (inside, excluded) = domain.split(&[-1.0, 0.0, 10.0, 12.5]);
+        assert_eq!(inside, [0.0, 10.0]);
+        assert_eq!(
+            excluded,
+            [
+                (-1.0, String::from("below 0")),
+                (12.5, String::from("above 10"))
+            ]
+        );
+    }
+}
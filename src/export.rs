@@ -0,0 +1,140 @@
+use crate::numeric;
+use crate::plot;
+use plotters::coord::Shift;
+use plotters::prelude::*;
+
+/// Image width and height in inches, scaled by the DPI for bitmaps.
+const INCHES: (u32, u32) = (6, 4);
+/// SVG is resolution independent, so it is drawn at this fixed DPI.
+const SVG_DPI: usize = 100;
+pub const DEFAULT_DPI: usize = 100;
+const SAMPLES: usize = 500;
+
+/// The image size in pixels, an error when it does not fit in a `u32`.
+fn pixels(dpi: usize) -> Result<(u32, u32), String> {
+    let side = |inches: u32| u32::try_from(dpi).ok()?.checked_mul(inches);
+    match (side(INCHES.0), side(INCHES.1)) {
+        (Some(width), Some(height)) => Ok((width, height)),
+        _ => Err(format!("a resolution of {} dpi is too large", dpi)),
+    }
+}
+
+/// Draws the axes, the reduced polynomial in blue and its real roots in red.
+fn draw<DB: DrawingBackend>(
+    area: DrawingArea<DB, Shift>,
+    coefficients: &[f64],
+    roots: &[f64],
+    dpi: usize,
+) -> Result<(), String> {
+    let polynomial = |x| numeric::eval(coefficients, x);
+    let (x0, x1) = plot::x_range(roots);
+    let (y0, y1) = plot::y_range((x0, x1), SAMPLES, &[&polynomial]);
+    let stroke = (dpi / 50).max(1) as u32;
+    area.fill(&WHITE).map_err(|e| e.to_string())?;
+    let mut chart = ChartBuilder::on(&area)
+        .margin(dpi as u32 / 10)
+        .build_cartesian_2d(x0..x1, y0..y1)
+        .map_err(|e| e.to_string())?;
+    let axes = [vec![(x0, 0.0), (x1, 0.0)], vec![(0.0, y0), (0.0, y1)]];
+    for axis in axes {
+        chart
+            .draw_series(LineSeries::new(axis, BLACK.stroke_width(1)))
+            .map_err(|e| e.to_string())?;
+    }
+    let curve = (0..SAMPLES).map(|i| {
+        let x = x0 + (x1 - x0) * i as f64 / (SAMPLES - 1) as f64;
+        (x, polynomial(x))
+    });
+    chart
+        .draw_series(LineSeries::new(curve, BLUE.stroke_width(stroke)))
+        .map_err(|e| e.to_string())?;
+    chart
+        .draw_series(
+            roots
+                .iter()
+                .map(|&root| Circle::new((root, 0.0), 3 * stroke, RED.filled())),
+        )
+        .map_err(|e| e.to_string())?;
+    area.present().map_err(|e| e.to_string())
+}
+
+pub fn write_svg(path: &str, coefficients: &[f64], roots: &[f64]) -> Result<(), String> {
+    let area = SVGBackend::new(path, pixels(SVG_DPI)?).into_drawing_area();
+    draw(area, coefficients, roots, SVG_DPI)
+}
+
+pub fn write_png(
+    path: &str,
+    coefficients: &[f64],
+    roots: &[f64],
+    dpi: usize,
+) -> Result<(), String> {
+    let area = BitMapBackend::new(path, pixels(dpi)?).into_drawing_area();
+    draw(area, coefficients, roots, dpi)
+}
+
+/// A gnuplot script plotting the reduced polynomial with its real roots
+/// inlined as a data block. Coefficients are written with a decimal point so
+/// that gnuplot does not fall back to integer arithmetic.
+pub fn gnuplot_script(coefficients: &[f64], roots: &[f64]) -> String {
+    let (x0, x1) = plot::x_range(roots);
+    let terms: Vec<String> = coefficients
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(degree, c)| match degree {
+            0 => format!("({:?})", c),
+            1 => format!("({:?})*x", c),
+            _ => format!("({:?})*x**{}", c, degree),
+        })
+        .collect();
+    let function = if terms.is_empty() {
+        String::from("0")
+    } else {
+        terms.join(" + ")
+    };
+    let mut script = String::from("# Run with: gnuplot -persist <this file>\n");
+    script += "set xzeroaxis\nset yzeroaxis\nset samples 500\n";
+    script += &format!("f(x) = {}\n", function);
+    let mut plots = vec![String::from("f(x) title \"reduced polynomial\"")];
+    if !roots.is_empty() {
+        script += "$roots << EOD\n";
+        for root in roots {
+            script += &format!("{:?} 0\n", root);
+        }
+        script += "EOD\n";
+        plots.push(String::from("$roots with points pt 7 title \"solutions\""));
+    }
+    script += &format!("plot [{:?}:{:?}] {}\n", x0, x1, plots.join(", "));
+    script
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn export_images() {
+        let directory = std::env::temp_dir();
+        let svg = directory.join(format!("computor-export-{}.svg", std::process::id()));
+        let png = directory.join(format!("computor-export-{}.png", std::process::id()));
+        let coefficients = [-1.0, 0.0, 1.0];
+        write_svg(svg.to_str().unwrap(), &coefficients, &[-1.0, 1.0]).unwrap();
+        write_png(png.to_str().unwrap(), &coefficients, &[-1.0, 1.0], 50).unwrap();
+        let svg = fs::read_to_string(svg).unwrap();
+        assert!(svg.starts_with("<svg") && svg.contains("<circle"));
+        assert_eq!(&fs::read(png).unwrap()[1..4], b"PNG");
+        assert_eq!(pixels(200), Ok((1200, 800)));
+        assert!(pixels(usize::MAX).is_err());
+    }
+
+    #[test]
+    fn gnuplot_inlines_roots() {
+        let script = gnuplot_script(&[-1.0, 0.0, 1.0], &[-1.0, 1.0]);
+        assert!(script.contains("f(x) = (1.0)*x**2 + (0.0)*x + (-1.0)\n"));
+        assert!(script.contains("$roots << EOD\n-1.0 0\n1.0 0\nEOD\n"));
+        assert!(script.ends_with("plot [-3.0:3.0] f(x) title \"reduced polynomial\", $roots with points pt 7 title \"solutions\"\n"));
+        assert!(!gnuplot_script(&[1.0, 0.0, 1.0], &[]).contains("$roots"));
+    }
+}
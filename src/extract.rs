@@ -0,0 +1,59 @@
+use std::io::{self, BufRead};
+
+/// What an equation of the subject grammar is written with. Anything else,
+/// such as letters or backquotes, ends a candidate.
+fn is_equation_char(c: char) -> bool {
+    c.is_ascii_digit() || " .+-*/^()=X".contains(c)
+}
+
+/// The runs of equation characters with a single `=`, an `X` and something
+/// on both sides, e.g. `X^2 - 4 = 0` in "Solve X^2 - 4 = 0 for X.".
+pub fn candidates(line: &str) -> Vec<&str> {
+    line.split(|c| !is_equation_char(c))
+        .map(|run| {
+            run.trim_start_matches(|c| " .)".contains(c))
+                .trim_end_matches(|c| " .(".contains(c))
+        })
+        .filter(|run| run.contains('X') && run.matches('=').count() == 1)
+        .filter(|run| match run.split_once('=') {
+            Some((left, right)) => !left.trim().is_empty() && !right.trim().is_empty(),
+            None => false,
+        })
+        .collect()
+}
+
+/// Every candidate of the text with its line number, counting from 1.
+pub fn scan(input: impl BufRead) -> io::Result<Vec<(usize, String)>> {
+    let mut found = vec![];
+    for (i, line) in input.lines().enumerate() {
+        for candidate in candidates(&line?) {
+            found.push((i + 1, candidate.to_string()));
+        }
+    }
+    Ok(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_equations_in_prose() {
+        assert_eq!(candidates("Solve `X^2 - 4 = 0` for X."), ["X^2 - 4 = 0"]);
+        assert_eq!(
+            candidates("Since 2 * X = 6, we get X = 3."),
+            ["2 * X = 6", "X = 3"]
+        );
+        assert!(candidates("1 + 1 = 2 and X == 3").is_empty());
+        assert!(candidates("Let X = be the unknown").is_empty());
+    }
+
+    #[test]
+    fn numbers_lines() {
+        let text = "# Notes\n\nFirst X + 1 = 0.\nThen X^2 = 9 (two roots)\n";
+        assert_eq!(
+            scan(text.as_bytes()).unwrap(),
+            vec![(3, String::from("X + 1 = 0")), (4, String::from("X^2 = 9"))]
+        );
+    }
+}
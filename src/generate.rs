@@ -0,0 +1,64 @@
+use crate::format::{format_polynomial, Style};
+use crate::random::Random;
+use crate::Poly;
+
+const MAX_ROOT: i64 = 9;
+const MAX_COEFFICIENT: i64 = 9;
+const MAX_LEAD: i64 = 5;
+
+/// Writes one side in the subject format, `0 * X^0` when it has no term.
+fn side(coefficients: &[f32]) -> String {
+    if coefficients.iter().all(|&c| c == 0.0) {
+        String::from("0 * X^0")
+    } else {
+        format_polynomial(coefficients, Style::default())
+    }
+}
+
+/// A random equation of the given degree. With `integer_roots`, the
+/// polynomial is built from integer roots so that it factors nicely.
+/// Some terms are then moved to the right side, negated.
+pub fn equation(random: &mut Random, degree: usize, integer_roots: bool) -> String {
+    let poly = if integer_roots {
+        let roots: Vec<f32> = (0..degree)
+            .map(|_| random.range(-MAX_ROOT, MAX_ROOT) as f32)
+            .collect();
+        Poly::from_roots(random.nonzero(-MAX_LEAD, MAX_LEAD) as f32, &roots)
+    } else {
+        let mut coefficients: Vec<f32> = (0..degree)
+            .map(|_| random.range(-MAX_COEFFICIENT, MAX_COEFFICIENT) as f32)
+            .collect();
+        coefficients.push(random.nonzero(-MAX_COEFFICIENT, MAX_COEFFICIENT) as f32);
+        Poly::from_coefficients(coefficients)
+    };
+    let mut left = poly.coefficients().to_vec();
+    let mut right = vec![0.0; left.len()];
+    for degree in 0..left.len() {
+        if random.range(0, 2) == 0 {
+            right[degree] = -left[degree];
+            left[degree] = 0.0;
+        }
+    }
+    format!("{} = {}", side(&left), side(&right))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_equations_parse_to_degree() {
+        let mut random = Random::new(7);
+        for degree in 0..5 {
+            for integer_roots in [false, true] {
+                let line = equation(&mut random, degree, integer_roots);
+                let poly = Poly::new(&line).unwrap();
+                assert_eq!(poly.get_degree(), degree as i32, "{}", line);
+                if integer_roots && degree == 2 {
+                    let roots = poly.real_roots();
+                    assert!(roots.iter().all(|r| r.fract() == 0.0), "{}", line);
+                }
+            }
+        }
+    }
+}
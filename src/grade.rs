@@ -0,0 +1,119 @@
+use crate::format::Style;
+use crate::report::Report;
+use crate::Poly;
+use std::process::Command;
+
+/// The examples of the subject plus the special cases evaluators check.
+const CORPUS: &[&str] = &[
+    "5 * X^0 + 4 * X^1 - 9.3 * X^2 = 1 * X^0",
+    "5 * X^0 + 4 * X^1 = 4 * X^0",
+    "8 * X^0 - 6 * X^1 + 0 * X^2 - 5.6 * X^3 = 3 * X^0",
+    "6 * X^0 + 11 * X^1 + 5 * X^2 = 1 * X^0 + 1 * X^1",
+    "1 * X^0 + 2 * X^1 + 1 * X^2 = 0 * X^0",
+    "5 * X^0 + 3 * X^1 + 3 * X^2 = 1 * X^0 + 0 * X^1",
+    "4 * X^0 - 1 * X^2 = 0 * X^0",
+    "42 * X^0 = 42 * X^0",
+    "10 * X^0 = 15 * X^0",
+    "1 * X^0 + 2 * X^1 = 4 * X^1",
+];
+
+const TOLERANCE: f32 = 1e-3;
+
+/// Every number in the text, including fractions such as `-1/2`.
+fn numbers(text: &str) -> Vec<f32> {
+    text.split(|c: char| c.is_whitespace() || c == ',' || c == ':' || c == '=' || c == ';')
+        .filter_map(|word| {
+            let word = word.trim_end_matches('.');
+            match word.split_once('/') {
+                Some((n, d)) => Some(n.parse::<f32>().ok()? / d.parse::<f32>().ok()?),
+                None => word.parse().ok(),
+            }
+        })
+        .collect()
+}
+
+/// Checks the degree and, when there are real solutions, that each of them
+/// appears after the degree line. The wording and the layout are free, and
+/// cases without a real solution only check the degree since complex
+/// solutions are printed in too many ways.
+pub fn compare(expected: &Report, output: &str) -> Result<(), String> {
+    let lines: Vec<&str> = output.lines().collect();
+    let line = lines
+        .iter()
+        .position(|line| line.to_lowercase().contains("degree"))
+        .ok_or(String::from("no degree line"))?;
+    match numbers(lines[line]).first() {
+        Some(&degree) if degree == expected.degree as f32 => (),
+        Some(degree) => return Err(format!("degree {}, expected {}", degree, expected.degree)),
+        None => return Err(String::from("no degree found")),
+    }
+    if expected.degree > 2 {
+        return Ok(());
+    }
+    let printed = numbers(&lines[line + 1..].join("\n"));
+    for solution in &expected.solutions {
+        let found = printed
+            .iter()
+            .any(|n| (n - solution).abs() <= TOLERANCE * solution.abs().max(1.0));
+        if !found {
+            return Err(format!("solution {} missing", solution));
+        }
+    }
+    Ok(())
+}
+
+/// Runs the binary on each equation of the corpus and compares its stdout to
+/// the results of this crate.
+pub fn grade(binary: &str) -> Vec<(&'static str, Result<(), String>)> {
+    CORPUS
+        .iter()
+        .map(|&equation| {
+            let expected = match Poly::new(equation) {
+                Ok(poly) => poly.report(Style::default()),
+                Err(_) => {
+                    return (
+                        equation,
+                        Err(String::from("corpus equation does not parse")),
+                    )
+                }
+            };
+            let result = match Command::new(binary).arg(equation).output() {
+                Ok(output) => compare(&expected, &String::from_utf8_lossy(&output.stdout)),
+                Err(error) => Err(format!("could not run {}: {}", binary, error)),
+            };
+            (equation, result)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expected(equation: &str) -> Report {
+        Poly::new(equation).unwrap().report(Style::default())
+    }
+
+    #[test]
+    fn numbers_in_any_notation() {
+        assert_eq!(numbers("X = -1/2, or 3."), vec![-0.5, 3.0]);
+        assert_eq!(numbers("Polynomial degree: 2"), vec![2.0]);
+    }
+
+    #[test]
+    fn compare_with_tolerance() {
+        let quadratic = expected("X^2 = 4");
+        let output = "Reduced form: X^2 - 4 = 0\nDegree: 2\nSolutions: -2.00001 and 2\n";
+        assert_eq!(compare(&quadratic, output), Ok(()));
+        let output = "Polynomial degree: 2\n2\n";
+        assert_eq!(
+            compare(&quadratic, output),
+            Err(String::from("solution -2 missing"))
+        );
+        let output = "Polynomial degree: 1\n2\n-2\n";
+        assert!(compare(&quadratic, output).is_err());
+        assert!(compare(&quadratic, "2\n-2\n").is_err());
+        let complex = expected("X^2 = -1");
+        assert_eq!(compare(&complex, "Polynomial degree: 2\ni\n-i\n"), Ok(()));
+    }
+}
@@ -1,331 +1,1526 @@
-use std::{collections::HashMap, env};
+mod batch;
+mod cli;
+mod conic;
+mod domain;
+mod export;
+mod extract;
+mod generate;
+mod grade;
+mod plot;
+mod quiz;
+mod repl;
+mod schema;
+mod stats;
+mod timing;
+mod units;
+mod verify;
 
-#[derive(Debug, PartialEq)]
-enum ParseError {
-    EqualSignError,
-    ParseNumError,
-}
+use computor_core::{
+    complex, cyclotomic, decimal, event, factor, float, format, fraction, hints, method, numeric,
+    random, render, report, resultant, sign, solve, steps, symmetric, transform, warnings, Edit,
+    Poly,
+};
+use computor_parse::{diagnostics, expr, suggest, ParseError};
+use diagnostics::Diagnostic;
+use domain::Domain;
+use event::{Event, Observer};
+use format::{format_polynomial, Style};
+use indicatif::{ProgressBar, ProgressStyle};
+use report::Report;
+use solve::SolveOptions;
+use std::env;
+use std::fs;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::process::{self, ExitCode};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+const FRAME_DELAY: Duration = Duration::from_millis(700);
 
-struct Poly {
-    coefficients: Vec<f32>,
+/// What the command line prints about a polynomial, and the edits asked
+/// with its options.
+trait Commands {
+    fn edit(&mut self, options: &cli::Options);
+    fn print_analysis(&self, exact: bool);
+    fn print_factors(&self, style: Style);
+    fn print_method(&self, method: method::Method);
+    fn print_root_count(&self, a: f32, b: f32);
+    fn print_rounded(&self, options: &SolveOptions);
+    fn print_continued_fractions(&self, count: usize);
+    fn print(&self, style: Style);
+    fn print_solved(&self, style: Style, roots: &[f32]);
+    fn print_solutions(&self, style: Style, roots: &[f32]);
+    fn print_polinomial(&self, style: Style);
 }
 
-impl Poly {
-    pub fn new(line: &str) -> Result<Poly, ParseError> {
-        let coefficients = parse(line)?;
-        Ok(Poly { coefficients })
+impl Commands for Poly {
+    /// The changes asked with `--set`, `--add` and `--positive-lead`.
+    fn edit(&mut self, options: &cli::Options) {
+        for edit in options.edits() {
+            self.apply(edit);
+        }
+    }
+
+    /// The separation bound, computed from the decimals as written, only
+    /// when `exact`.
+    fn print_analysis(&self, exact: bool) {
+        if self.get_degree() < 1 {
+            return;
+        }
+        let coefficients = self.coefficients_f64();
+        println!(
+            "Cauchy root bound: {}",
+            float::shortest(numeric::cauchy_bound(&coefficients) as f32)
+        );
+        println!("Height: {}", float::shortest(self.height()));
+        println!("L2 norm: {}", float::shortest(self.l2_norm()));
+        println!(
+            "Mahler measure (estimate): {}",
+            float::shortest(self.mahler_measure())
+        );
+        if let Some(bound) = resultant::separation_bound(self.coefficients()).filter(|_| exact) {
+            println!(
+                "Root separation bound (Mignotte): {}",
+                float::shortest_scientific(bound as f32)
+            );
+        }
+        print!("Root magnitude estimates (Graeffe):");
+        for magnitude in numeric::graeffe_magnitudes(&coefficients) {
+            print!(" {}", float::shortest(magnitude as f32));
+        }
+        println!();
+        println!("Roots (Durand-Kerner):");
+        for root in numeric::durand_kerner(&coefficients) {
+            println!("{}", root);
+        }
+        println!("Concavity:");
+        print!("{}", sign::describe_curvature(&coefficients));
     }
 
-    pub fn get_degree(&self) -> i32 {
-        let degree: i32 = self.coefficients.len().try_into().unwrap();
-        degree - 1
+    fn print_factors(&self, style: Style) {
+        match factor::over_integers(self.coefficients()) {
+            Ok(factorization) => println!("Factored form: {} = 0", factorization.render(style)),
+            Err(factor::FactorError::Zero) => eprintln!("The zero polynomial has no factorization"),
+            Err(factor::FactorError::Overflow) => {
+                eprintln!("The coefficients are too large to factor over the integers")
+            }
+            Err(factor::FactorError::SearchTooLarge) => {
+                eprintln!("The search for factors over the integers is too large")
+            }
+        }
     }
 
-    pub fn solve(&self) -> Option<Vec<f32>> {
-        match self.get_degree() {
-            0 => {
-                if self.coefficients[0] == 0.0 {
-                    Some(vec![])
-                } else {
-                    None
+    /// The real solutions found by one solver, with the choice of `auto`
+    /// explained on stderr.
+    fn print_method(&self, method: method::Method) {
+        let coefficients = self.coefficients_f64();
+        let method = match method {
+            method::Method::Auto if self.get_degree() >= 1 => {
+                let (chosen, reason) = method::choose(&coefficients);
+                eprintln!("Method: {}, because {}", chosen.name(), reason);
+                chosen
+            }
+            method => method,
+        };
+        match method::solve(method, &coefficients) {
+            Some(roots) if roots.is_empty() => println!("No real solution by {}.", method.name()),
+            Some(roots) => {
+                println!("Real solutions by {}:", method.name());
+                for root in roots {
+                    println!("{}", float::shortest(root as f32));
                 }
             }
-            1 => Some(vec![-self.coefficients[0] / self.coefficients[1]]),
-            2 => self.quadratic_formula(),
-            _ => None,
+            None => eprintln!(
+                "The {} method does not apply to this equation",
+                method.name()
+            ),
+        }
+    }
+
+    fn print_root_count(&self, a: f32, b: f32) {
+        match self.count_roots(a, b) {
+            Some(count) => println!(
+                "Distinct real roots in [{}, {}]: {}",
+                float::shortest(a),
+                float::shortest(b),
+                count
+            ),
+            None => println!(
+                "Each real number in [{}, {}] is a solution",
+                float::shortest(a),
+                float::shortest(b)
+            ),
         }
     }
 
-    fn quadratic_formula(&self) -> Option<Vec<f32>> {
-        let a = self.coefficients[2];
-        let b = self.coefficients[1];
-        let c = self.coefficients[0];
-        let discriminant = b * b - 4.0 * a * c;
-        match discriminant {
-            d if d > 0.0 => Some(vec![
-                (-b + d.sqrt()) / (2.0 * a),
-                (-b - d.sqrt()) / (2.0 * a),
-            ]),
-            d if d == 0.0 => Some(vec![-b / (2.0 * a)]),
-            _ => None,
+    /// The solutions with the precision of the options: those of degree 1
+    /// and 2 rounded correctly from the decimals as written, the others
+    /// from their approximation.
+    fn print_rounded(&self, options: &SolveOptions) {
+        let Some(digits) = options.get_precision() else {
+            return;
+        };
+        let solution = solve::solve(self.coefficients(), options, &AtomicBool::new(false), None);
+        print_rounded(digits, &solution.rounded);
+    }
+
+    /// The expansion of each real solution and its convergents, exact for
+    /// degree 1 and 2, from the numeric approximation above.
+    fn print_continued_fractions(&self, count: usize) {
+        let expansions: Vec<(String, Option<Vec<i128>>)> =
+            match decimal::exact_roots(self.coefficients()) {
+                Some(roots) => roots
+                    .into_iter()
+                    .map(|root| {
+                        let x = float::shortest(root.to_f64() as f32);
+                        (x, fraction::exact_terms(root, count))
+                    })
+                    .collect(),
+                None if self.get_degree() > 2 => numeric::real_roots(&self.coefficients_f64())
+                    .into_iter()
+                    .map(|root| {
+                        let x = float::shortest(root as f32);
+                        (x, Some(fraction::approximate_terms(root, count)))
+                    })
+                    .collect(),
+                None => vec![],
+            };
+        for (x, terms) in expansions {
+            let Some(terms) = terms else {
+                println!("X = {}: the continued fraction overflows", x);
+                continue;
+            };
+            println!("X = {} = {}", x, fraction::format_terms(&terms));
+            let convergents: Vec<String> = fraction::convergents(&terms)
+                .iter()
+                .map(|(p, q)| format!("{}/{}", p, q))
+                .collect();
+            println!("Convergents: {}", convergents.join(", "));
         }
     }
 
-    pub fn print(&self) {
+    fn print(&self, style: Style) {
+        self.print_solved(style, &self.real_roots());
+    }
+
+    /// Like `print`, with the real roots already solved, printed when there
+    /// is no closed form.
+    fn print_solved(&self, style: Style, roots: &[f32]) {
         print!("Reduced form: ");
-        self.print_polinomial();
-        println!(
-            "Polynomial degree: {}",
-            if self.get_degree() > -1 {
-                self.get_degree()
-            } else {
-                0
-            }
-        );
+        self.print_polinomial(style);
+        println!("Polynomial degree: {}", self.degree().unwrap_or(0));
+        self.print_solutions(style, roots);
+    }
+
+    /// Above degree 2, factors out `X^k` first and solves the quotient.
+    fn print_solutions(&self, style: Style, roots: &[f32]) {
         let solutions = self.solve();
+        let (k, rest) = self.factor_x_power();
         match self.get_degree() {
-            0 => {
-                if solutions.is_none() {
-                    println!("There no solution")
-                } else {
-                    println!("Each real number is a solution")
-                }
-            }
-            1 => println!("The solution is:\n{}", solutions.unwrap()[0]),
+            _ if self.is_zero() => println!("Each real number is a solution."),
+            0 => println!("There no solution"),
+            1 => println!(
+                "The solution is:\n{}",
+                float::shortest(solutions.unwrap()[0])
+            ),
             2 => {
                 if let Some(solutions) = solutions {
                     if solutions.len() == 1 {
                         println!(
                             "Discriminant is strictly zero, there is only one solution:\n{}",
-                            solutions[0]
+                            float::shortest(solutions[0])
                         )
                     } else {
                         println!(
                             "Discriminant is strictly positive, the two solutions are:\n{}\n{}",
-                            solutions[0], solutions[1]
+                            float::shortest(solutions[0]),
+                            float::shortest(solutions[1])
                         )
                     }
                 } else {
                     println!("Discriminant is strictly negative, there is no real solutions.")
                 }
             }
-            -1 => println!("Each real number is a solution."),
-            _ => println!("The polynomial degree is strictly greater than 2, I can't solve."),
+            _ if k > 0 => {
+                let power = match k {
+                    1 => String::from("X"),
+                    k => format!("X^{}", k),
+                };
+                println!(
+                    "Factoring out {}: {} * ({}) = 0",
+                    power,
+                    power,
+                    format_polynomial(rest.coefficients(), style)
+                );
+                match k {
+                    1 => println!("X = 0 is a solution"),
+                    k => println!("X = 0 is a solution of multiplicity {}", k),
+                }
+                if rest.get_degree() > 0 {
+                    println!("Remaining factor of degree {}:", rest.get_degree());
+                    // X = 0 found numerically may be off by a rounding error
+                    let roots: Vec<f32> = roots
+                        .iter()
+                        .copied()
+                        .filter(|root| root.abs() > 1e-6)
+                        .collect();
+                    rest.print_solutions(style, &roots);
+                }
+            }
+            _ if cyclotomic::recognize(self.coefficients()).is_some() => {
+                let Some(family) = cyclotomic::recognize(self.coefficients()) else {
+                    return;
+                };
+                let product: Vec<String> =
+                    family.indices().iter().map(|d| format!("Φ{}", d)).collect();
+                match product.len() {
+                    1 => println!("Cyclotomic polynomial {}", family),
+                    _ => println!("Cyclotomic: {} = {}", family, product.join(" * ")),
+                }
+                println!("The solutions are {}", family.roots());
+                let roots = family.real_roots();
+                match roots.len() {
+                    0 => println!("None of them is real."),
+                    _ => println!("The real ones are:"),
+                }
+                for root in roots {
+                    println!("{}", float::shortest(root));
+                }
+            }
+            _ if factor::by_grouping(self.coefficients()).is_some() => {
+                let factors = factor::by_grouping(self.coefficients()).unwrap_or_default();
+                let product: Vec<String> = factors
+                    .iter()
+                    .map(|factor| format!("({})", format_polynomial(factor.coefficients(), style)))
+                    .collect();
+                println!("Factoring by grouping: {} = 0", product.join(" * "));
+                let roots = factor::real_roots(&factors);
+                match roots.len() {
+                    0 => println!("There is no real solution."),
+                    1 => println!("The solution is:"),
+                    _ => println!("The solutions are:"),
+                }
+                for root in roots {
+                    println!("{}", float::shortest(root + 0.0));
+                }
+            }
+            _ if factor::perfect_power(self.coefficients()).is_some() => {
+                let Some(power) = factor::perfect_power(self.coefficients()) else {
+                    return;
+                };
+                let base = format_polynomial(power.base.coefficients(), style);
+                println!(
+                    "Perfect power, same solutions as: ({})^{} = 0",
+                    base, power.exponent
+                );
+                println!(
+                    "Solving the base, the multiplicities times {}:",
+                    power.exponent
+                );
+                power.base.print_solutions(style, roots);
+            }
+            _ => {
+                println!("The polynomial degree is strictly greater than 2, I can't solve.");
+                for hint in hints::hints(self.coefficients()) {
+                    println!("Hint: {}", hint);
+                }
+                if roots.is_empty() {
+                    println!("No real solution found numerically.");
+                } else {
+                    println!("Approximate real solutions found numerically:");
+                    for &root in roots {
+                        println!("{}", float::shortest(root));
+                    }
+                }
+            }
         }
     }
 
-    fn print_polinomial(&self) {
-        let mut degree = 0;
-        while degree < self.coefficients.len() && self.coefficients[degree] == 0.0 {
-            degree += 1
+    fn print_polinomial(&self, style: Style) {
+        println!("{} = 0", format_polynomial(self.coefficients(), style));
+    }
+}
+
+fn print_derivative(line: &str, limits: &expr::Limits, style: Style) -> ExitCode {
+    let expr = match expr::check_expression_with(line, limits) {
+        Ok(expr) => expr,
+        Err(diagnostics) => {
+            eprint!("{}", diagnostics::render(line, &diagnostics));
+            eprintln!("Error parsing the expression");
+            return ExitCode::FAILURE;
         }
-        if degree < self.coefficients.len() {
-            print!("{} * X^{}", self.coefficients[degree], degree);
+    };
+    let derivative = expr.derivative().simplify();
+    match derivative.expand() {
+        Ok(expanded) => {
+            println!("Expression: {}", expr);
+            println!("Derivative: {}", derivative);
+            println!(
+                "Expanded derivative: {}",
+                format_polynomial(&expanded, style)
+            );
+            ExitCode::SUCCESS
         }
-        degree += 1;
-        while degree < self.coefficients.len() {
-            if self.coefficients[degree] == 0.0 {
-                degree += 1;
-                continue;
-            }
-            if self.coefficients[degree] < 0.0 {
-                print!(" - ")
-            } else {
-                print!(" + ")
-            }
-            print!("{} * X^{}", self.coefficients[degree].abs(), degree);
-            degree += 1;
+        Err(_) => {
+            eprintln!("Error parsing the expression");
+            ExitCode::FAILURE
         }
-        if self.coefficients.len() == 0 {
-            print!("0");
+    }
+}
+
+/// The reduced polynomial of an expression, or of an equation moved to one
+/// side.
+fn polynomial_of(line: &str, limits: &expr::Limits) -> Result<Vec<f32>, ExitCode> {
+    let expr = if line.contains('=') {
+        expr::check_equation_with(line, limits)
+            .map(|(left, right)| expr::Expr::Sub(Box::new(left), Box::new(right)))
+    } else {
+        expr::check_expression_with(line, limits)
+    };
+    match expr.map(|expr| expr.expand()) {
+        Ok(Ok(coefficients)) => Ok(coefficients),
+        Ok(Err(_)) => {
+            eprintln!("Error parsing {}", line);
+            Err(ExitCode::FAILURE)
+        }
+        Err(diagnostics) => {
+            eprint!("{}", diagnostics::render(line, &diagnostics));
+            eprintln!("Error parsing {}", line);
+            Err(ExitCode::FAILURE)
         }
-        println!(" = 0");
     }
 }
 
-fn parse(line: &str) -> Result<Vec<f32>, ParseError> {
-    let line: String = line.chars().filter(|c| *c != ' ').collect(); // Remove spaces
-    let equations: Vec<&str> = line.split('=').collect();
-    if equations.len() != 2 {
-        return Err(ParseError::EqualSignError);
+/// Whether the two equations have the same real solutions, up to the
+/// rounding of the numeric ones.
+fn same_solutions(p: &Poly, q: &Poly) -> bool {
+    if p.is_zero() || q.is_zero() {
+        return p.is_zero() && q.is_zero();
     }
-    let left_eq = parse_equation(equations[0])?;
-    let right_eq = parse_equation(equations[1])?;
-    let equation = simplify_equations(left_eq, right_eq);
-    Ok(map2vec(equation))
+    let sorted = |poly: &Poly| {
+        let mut roots = poly.real_roots();
+        roots.sort_by(f32::total_cmp);
+        roots
+    };
+    let (a, b) = (sorted(p), sorted(q));
+    a.len() == b.len()
+        && a.iter()
+            .zip(&b)
+            .all(|(x, y)| (x - y).abs() <= 1e-5 * x.abs().max(1.0))
 }
 
-fn parse_equation(equation: &str) -> Result<HashMap<i32, f32>, ParseError> {
-    let equation = equation.replacen('-', "+-", equation.len());
-    let monomial: Vec<&str> = equation.split('+').collect();
-    let mut equation: HashMap<i32, f32> = HashMap::new();
-    for m in monomial {
-        match parse_monomial(m) {
-            Ok((coef, degree)) => {
-                if equation.contains_key(&degree) {
-                    equation.insert(degree, coef + equation[&degree]);
-                } else {
-                    equation.insert(degree, coef);
-                }
-            },
-            Err(_) => return Err(ParseError::ParseNumError),
-        };
+/// Compares a rearranged equation with the original one: the difference of
+/// the reduced forms and whether the solutions are the same.
+fn print_equation_diff(first: &str, second: &str, limits: &expr::Limits, style: Style) -> ExitCode {
+    let (p, q) = match (polynomial_of(first, limits), polynomial_of(second, limits)) {
+        (Ok(p), Ok(q)) => (Poly::from_coefficients(p), Poly::from_coefficients(q)),
+        (Err(code), _) | (_, Err(code)) => return code,
+    };
+    println!("First: {} = 0", format_polynomial(p.coefficients(), style));
+    println!("Second: {} = 0", format_polynomial(q.coefficients(), style));
+    let scale = p.scale_factor(&q);
+    let equivalent = scale.is_some() || same_solutions(&p, &q);
+    let difference = p - q;
+    println!(
+        "Difference: {}",
+        format_polynomial(difference.coefficients(), style)
+    );
+    match scale {
+        Some((1, 1)) => println!("The reduced forms are identical."),
+        Some((numerator, 1)) => println!("The first equation is the second times {}.", numerator),
+        Some((numerator, denominator)) => println!(
+            "The first equation is the second times {}/{}.",
+            numerator, denominator
+        ),
+        None => (),
+    }
+    if equivalent {
+        println!("The equations are equivalent: they have the same solutions.");
+    } else {
+        println!("The equations are not equivalent.");
     }
-    Ok(equation)
+    ExitCode::SUCCESS
 }
 
-fn parse_monomial(monomial: &str) -> Result<(f32, i32), ParseError> {
-    let elements: Vec<&str> = monomial.split('*').collect();
-    if elements.len() == 2 {
-        let coefficient = elements[0].parse::<f32>();
-        let degree = parse_indeterminate(elements[1]);
-        if let Ok(coefficient) = coefficient {
-            if let Ok(degree) = degree {
-                return Ok((coefficient, degree));
+/// The real roots shared by two polynomials, those of their GCD.
+fn print_common(first: &str, second: &str, limits: &expr::Limits, style: Style) -> ExitCode {
+    let (p, q) = match (polynomial_of(first, limits), polynomial_of(second, limits)) {
+        (Ok(p), Ok(q)) => (p, q),
+        (Err(code), _) | (_, Err(code)) => return code,
+    };
+    println!("First: {} = 0", format_polynomial(&p, style));
+    println!("Second: {} = 0", format_polynomial(&q, style));
+    let to_f64 = |p: &[f32]| -> Vec<f64> { p.iter().map(|&c| c as f64).collect() };
+    let divisor = numeric::gcd(&to_f64(&p), &to_f64(&q));
+    if divisor.is_empty() {
+        println!("Each real number is a common root.");
+        return ExitCode::SUCCESS;
+    }
+    let rounded: Vec<f32> = divisor.iter().map(|&c| c as f32).collect();
+    println!(
+        "Greatest common divisor: {}",
+        format_polynomial(&rounded, style)
+    );
+    match method::solve(method::Method::Auto, &divisor) {
+        Some(roots) if !roots.is_empty() => {
+            println!("Common real roots:");
+            for root in roots {
+                println!("{}", float::shortest(root as f32));
             }
         }
-    } else if elements.len() == 1 && elements[0].contains('X') {
-        let coefficient = 1.0;
-        let degree = parse_indeterminate(elements[0]);
-        if let Ok(degree) = degree {
-            return Ok((coefficient, degree));
+        _ => println!("No common real root."),
+    }
+    ExitCode::SUCCESS
+}
+
+/// The reduced sum, difference or product of two polynomials.
+fn print_arithmetic(options: &cli::Options) -> ExitCode {
+    let (p, q) = match (
+        polynomial_of(&options.equation, &options.limits()),
+        polynomial_of(&options.second, &options.limits()),
+    ) {
+        (Ok(p), Ok(q)) => (Poly::from_coefficients(p), Poly::from_coefficients(q)),
+        (Err(code), _) | (_, Err(code)) => return code,
+    };
+    let result = match options.command {
+        cli::Command::Add => p + q,
+        cli::Command::Sub => p - q,
+        _ => p * q,
+    };
+    println!(
+        "{}",
+        format_polynomial(result.coefficients(), options.style())
+    );
+    ExitCode::SUCCESS
+}
+
+fn print_shift(line: &str, limits: &expr::Limits, by: f32, style: Style) -> ExitCode {
+    let p = match polynomial_of(line, limits) {
+        Ok(p) => p,
+        Err(code) => return code,
+    };
+    let coefficients: Vec<f64> = p.iter().map(|&c| c as f64).collect();
+    let shifted: Vec<f32> = numeric::taylor_shift(&coefficients, by as f64)
+        .iter()
+        .map(|&c| c as f32)
+        .collect();
+    println!("P(X) = {}", format_polynomial(&p, style));
+    let sign = if by < 0.0 { '-' } else { '+' };
+    println!(
+        "P(X {} {}) = {}",
+        sign,
+        float::shortest(by.abs()),
+        format_polynomial(&shifted, style)
+    );
+    ExitCode::SUCCESS
+}
+
+fn print_transform(
+    line: &str,
+    limits: &expr::Limits,
+    transform: transform::Transform,
+    style: Style,
+) -> ExitCode {
+    let p = match polynomial_of(line, limits) {
+        Ok(p) => p,
+        Err(code) => return code,
+    };
+    let coefficients: Vec<f64> = p.iter().map(|&c| c as f64).collect();
+    let transformed: Vec<f32> = transform
+        .apply(&coefficients)
+        .iter()
+        .map(|&c| c as f32)
+        .collect();
+    println!("Roots r of: {} = 0", format_polynomial(&p, style));
+    println!(
+        "Roots {} of: {} = 0",
+        transform.roots(),
+        format_polynomial(&transformed, style)
+    );
+    ExitCode::SUCCESS
+}
+
+/// The discriminant for any degree, from the resultant of the polynomial and
+/// its derivative, without solving.
+fn print_discriminant(line: &str, limits: &expr::Limits, style: Style) -> ExitCode {
+    let p = match polynomial_of(line, limits) {
+        Ok(p) => p,
+        Err(code) => return code,
+    };
+    println!("P(X) = {}", format_polynomial(&p, style));
+    if p.len() < 3 {
+        eprintln!("The discriminant is only defined from degree 2");
+        return ExitCode::FAILURE;
+    }
+    let Some((numerator, denominator)) = resultant::discriminant(&p) else {
+        eprintln!("The exact discriminant overflows");
+        return ExitCode::FAILURE;
+    };
+    if denominator == 1 {
+        println!("Discriminant: {}", numerator);
+    } else {
+        println!("Discriminant: {}/{}", numerator, denominator);
+    }
+    if numerator == 0 {
+        println!("It is zero: there is a repeated root.");
+    } else {
+        println!("It is not zero: the roots are distinct.");
+    }
+    ExitCode::SUCCESS
+}
+
+/// Shows the frames one after the other, redrawing in place when the output
+/// is a terminal.
+fn animate(frames: Vec<String>, terminal: bool) {
+    for frame in frames {
+        if terminal {
+            print!("\x1b[2J\x1b[H");
+        }
+        print!("{}", frame);
+        io::stdout().flush().unwrap_or_default();
+        if terminal {
+            thread::sleep(FRAME_DELAY);
+        }
+    }
+}
+
+/// On X11 and Wayland the text is served by this process, so it only outlives
+/// it when a clipboard manager takes it over.
+#[cfg(feature = "arboard")]
+fn copy_to_clipboard(text: String) -> Result<(), String> {
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.set_text(text))
+        .map_err(|error| error.to_string())
+}
+
+#[cfg(not(feature = "arboard"))]
+fn copy_to_clipboard(_text: String) -> Result<(), String> {
+    Err(String::from("built without the `arboard` feature"))
+}
+
+fn print_check_answer(poly: &Poly, candidates: &[f32], tolerance: f32) -> ExitCode {
+    let (verdicts, missing) = poly.check_answer(candidates, tolerance);
+    for (candidate, correct) in &verdicts {
+        if *correct {
+            println!("X = {}: correct", float::shortest(*candidate));
+        } else {
+            println!("X = {}: not a solution", float::shortest(*candidate));
+        }
+    }
+    for &root in &missing {
+        println!("Missing solution: {}", float::shortest(root));
+    }
+    if missing.is_empty() && verdicts.iter().all(|(_, correct)| *correct) {
+        println!("All solutions are correct");
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn print_grade(binary: &str) -> ExitCode {
+    let results = grade::grade(binary);
+    for (equation, result) in &results {
+        match result {
+            Ok(()) => println!("PASS {}", equation),
+            Err(reason) => println!("FAIL {}: {}", equation, reason),
+        }
+    }
+    let passed = results.iter().filter(|(_, result)| result.is_ok()).count();
+    println!("{}/{} passed", passed, results.len());
+    if passed == results.len() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn repl_session(options: &cli::Options) -> repl::Session {
+    let mut session = repl::Session::new(repl::Settings {
+        style: options.style(),
+        precision: options.precision,
+        rounding: options.rounding,
+    });
+    session.limits = options.limits();
+    session
+}
+
+/// Executes the lines of the file, or of stdin for `-`, as if entered in
+/// the repl.
+fn run_script(options: &cli::Options) -> ExitCode {
+    let mut session = repl_session(options);
+    let path = &options.equation;
+    let result = if path == "-" {
+        repl::run_script(&mut session, io::stdin().lock())
+    } else {
+        fs::File::open(path)
+            .and_then(|file| repl::run_script(&mut session, io::BufReader::new(file)))
+    };
+    match result {
+        Ok(None) => ExitCode::SUCCESS,
+        Ok(Some((number, line, repl::Error::Parse(diagnostics)))) => {
+            eprint!("{}", diagnostics::render(&line, &diagnostics));
+            eprintln!("line {}: Error parsing the polynomial equation", number);
+            ExitCode::FAILURE
+        }
+        Ok(Some((number, _, repl::Error::Message(message)))) => {
+            eprintln!("line {}: {}", number, message);
+            ExitCode::FAILURE
+        }
+        Err(error) => {
+            eprintln!("Could not read {}: {}", path, error);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Streams the equations of the file, or of stdin for `-`, one per line.
+/// A progress bar on stderr when it is a terminal, sized by the equations in
+/// the file, or only counting them for stdin.
+fn batch_progress(options: &cli::Options) -> Option<ProgressBar> {
+    if options.no_progress || options.deterministic || !io::stderr().is_terminal() {
+        return None;
+    }
+    let total = fs::File::open(&options.equation).ok().map(|file| {
+        io::BufReader::new(file)
+            .lines()
+            .map_while(Result::ok)
+            .filter(|line| !line.trim().is_empty())
+            .count()
+    });
+    let progress = match total {
+        Some(total) if options.equation != "-" => ProgressBar::new(total as u64)
+            .with_style(ProgressStyle::with_template("{bar:40} {pos}/{len} equations").ok()?),
+        _ => ProgressBar::new_spinner()
+            .with_style(ProgressStyle::with_template("{spinner} {pos} equations").ok()?),
+    };
+    Some(progress)
+}
+
+/// The exit status of a batch or a solve stopped by Ctrl-C, as shells report a process
+/// killed by SIGINT.
+const INTERRUPTED_EXIT: u8 = 130;
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// The first Ctrl-C lets the batch finish the lines in flight and close its
+/// output, or a single equation stop solving, a second one exits at once.
+fn handle_interrupt() {
+    let handler = ctrlc::set_handler(|| {
+        if INTERRUPTED.swap(true, Ordering::SeqCst) {
+            process::exit(INTERRUPTED_EXIT as i32);
+        }
+    });
+    if let Err(error) = handler {
+        eprintln!("Ctrl-C will not stop cleanly: {}", error);
+    }
+}
+
+fn run_batch(options: &cli::Options) -> ExitCode {
+    handle_interrupt();
+    let solver = match options.deterministic {
+        true => solve::Solver::without_memo(options.solve_options()),
+        false => solve::Solver::new(options.solve_options()),
+    };
+    let artifacts = options.out_dir.as_ref().map(|dir| batch::Artifacts {
+        dir: dir.into(),
+        plot: options.plot,
+    });
+    if let Some(artifacts) = &artifacts {
+        if let Err(error) = fs::create_dir_all(&artifacts.dir) {
+            eprintln!("Could not create {}: {}", artifacts.dir.display(), error);
+            return ExitCode::FAILURE;
+        }
+    }
+    let stats = match options.stats.as_deref().map(stats::Stats::load) {
+        Some(Ok(stats)) => Some(Mutex::new(stats)),
+        Some(Err(message)) => {
+            eprintln!("{}", message);
+            return ExitCode::FAILURE;
+        }
+        None => None,
+    };
+    let renderers = render::Renderers::default();
+    let settings = batch::Settings {
+        jobs: options.jobs.unwrap_or(1),
+        // checked when the arguments were parsed
+        renderer: renderers
+            .get(options.output_name())
+            .unwrap_or(&render::Text),
+        style: options.style(),
+        solver: &solver,
+        artifacts,
+        progress: batch_progress(options),
+        interrupted: Some(&INTERRUPTED),
+        stats: stats.as_ref(),
+    };
+    let mut timings = timing::Timings::default();
+    let result = timings.measure("batch", || {
+        if options.equation == "-" {
+            batch::run(io::stdin().lock(), io::stdout(), &settings)
+        } else {
+            fs::File::open(&options.equation)
+                .and_then(|file| batch::run(io::BufReader::new(file), io::stdout(), &settings))
+        }
+    });
+    if let Some(progress) = &settings.progress {
+        progress.finish_and_clear();
+    }
+    if let (Some(path), Some(Ok(stats))) = (&options.stats, stats.as_ref().map(Mutex::lock)) {
+        if let Err(message) = stats.save(path) {
+            eprintln!("{}", message);
+        }
+    }
+    if options.time {
+        eprint!("{}", timings.report());
+        let (hits, misses) = solver.hits_and_misses();
+        eprintln!("Cache: {} hits, {} misses", hits, misses);
+    }
+    if settings.is_interrupted() {
+        eprintln!("Interrupted, the output is incomplete");
+        return ExitCode::from(INTERRUPTED_EXIT);
+    }
+    match result {
+        Ok(0) => ExitCode::SUCCESS,
+        Ok(failed) => {
+            eprintln!("{} equations could not be parsed", failed);
+            ExitCode::FAILURE
+        }
+        Err(error) => {
+            eprintln!("Could not read {}: {}", options.equation, error);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Adds to the counts of `--stats`, a failure to update them only being
+/// reported.
+fn record_stats(options: &cli::Options, update: impl FnOnce(&mut stats::Stats)) {
+    let Some(path) = &options.stats else {
+        return;
+    };
+    let saved = stats::Stats::load(path).and_then(|mut stats| {
+        update(&mut stats);
+        stats.save(path)
+    });
+    if let Err(message) = saved {
+        eprintln!("{}", message);
+    }
+}
+
+/// The solutions of every equation found in the text, by line number.
+fn print_extract(path: &str, style: Style) -> ExitCode {
+    let found = if path == "-" {
+        extract::scan(io::stdin().lock())
+    } else {
+        fs::File::open(path).and_then(|file| extract::scan(io::BufReader::new(file)))
+    };
+    let found = match found {
+        Ok(found) => found,
+        Err(error) => {
+            eprintln!("Could not read {}: {}", path, error);
+            return ExitCode::FAILURE;
+        }
+    };
+    for (line, equation) in found {
+        match Poly::new(&equation) {
+            Ok(poly) => println!(
+                "line {}: {}: {}",
+                line,
+                equation,
+                poly.report(style).summary()
+            ),
+            Err(_) => eprintln!(
+                "line {}: {}: Error parsing the polynomial equation",
+                line, equation
+            ),
         }
-    } else if elements.len() == 1 && elements[0].len() == 0 {
-        return Ok((0., 0));
+    }
+    ExitCode::SUCCESS
+}
+
+fn read_input(path: &str) -> io::Result<String> {
+    if path == "-" {
+        io::read_to_string(io::stdin())
     } else {
-        let coefficient = elements[0].parse::<f32>();
-        let degree = 0;
-        if let Ok(coefficient) = coefficient {
-            return Ok((coefficient, degree));
+        fs::read_to_string(path)
+    }
+}
+
+/// Fails when any equation no longer gives its saved result.
+fn print_verify(options: &cli::Options) -> ExitCode {
+    let expected = options.expected.as_deref().unwrap_or_default();
+    let files = read_input(&options.equation)
+        .map_err(|error| format!("Could not read {}: {}", options.equation, error))
+        .and_then(|input| {
+            let saved = fs::read_to_string(expected)
+                .map_err(|error| format!("Could not read {}: {}", expected, error))?;
+            Ok((input, saved))
+        });
+    let tolerance = options.tolerance.unwrap_or(cli::DEFAULT_TOLERANCE);
+    let mismatches = files.and_then(|(input, saved)| verify::verify(&input, &saved, tolerance));
+    match mismatches {
+        Ok(mismatches) if mismatches.is_empty() => {
+            println!("Every result matches {}", expected);
+            ExitCode::SUCCESS
+        }
+        Ok(mismatches) => {
+            for (line, mismatch) in &mismatches {
+                println!("line {}: {}", line, mismatch);
+            }
+            eprintln!("{} mismatches with {}", mismatches.len(), expected);
+            ExitCode::FAILURE
+        }
+        Err(error) => {
+            eprintln!("{}", error);
+            ExitCode::FAILURE
         }
     }
-    Err(ParseError::ParseNumError)
 }
 
-fn parse_indeterminate(indeterminate: &str) -> Result<i32, ParseError> {
-    let exponentiation: Vec<&str> = indeterminate.split('^').collect();
-    if exponentiation.len() == 2 && exponentiation[0].eq("X") {
-        match exponentiation[1].parse::<i32>() {
-            Ok(degree) => Ok(degree),
-            _ => Err(ParseError::ParseNumError),
+/// The schema of the JSON output, or whether the output saved in `path`
+/// conforms to it.
+fn print_schema(path: &str) -> ExitCode {
+    if path.is_empty() {
+        print!("{}", schema::SCHEMA);
+        return ExitCode::SUCCESS;
+    }
+    let output = match read_input(path) {
+        Ok(output) => output,
+        Err(error) => {
+            eprintln!("Could not read {}: {}", path, error);
+            return ExitCode::FAILURE;
         }
-    } else if exponentiation.len() == 1 && exponentiation[0].eq("X") {
-        Ok(1)
+    };
+    let errors = schema::check_all(&output);
+    for (line, error) in &errors {
+        println!("line {}: {}", line, error);
+    }
+    if errors.is_empty() {
+        println!("{} conforms to the schema", path);
+        ExitCode::SUCCESS
     } else {
-        Err(ParseError::ParseNumError)
+        ExitCode::FAILURE
     }
 }
 
-fn map2vec(map: HashMap<i32, f32>) -> Vec<f32> {
-    let mut keys: Vec<&i32> = map.keys().collect();
-    keys.sort();
-    let mut vector: Vec<f32> = vec![];
-    let mut i = 0;
-    for k in keys {
-        while i < *k {
-            vector.push(0.0);
-            i += 1;
+/// What the equation being typed reduces to so far, and what may follow.
+fn print_preview(line: &str, style: Style) -> ExitCode {
+    let partial = expr::parse_partial(line);
+    let state = if partial.complete { "" } else { " (so far)" };
+    println!(
+        "Preview{}: {} = 0",
+        state,
+        format_polynomial(&partial.coefficients, style)
+    );
+    println!("Expected next: {}", partial.expected);
+    ExitCode::SUCCESS
+}
+
+fn print_conic(line: &str, limits: &expr::Limits) -> ExitCode {
+    let (left, right) = match expr::check_equation_in_two_variables_with(line, limits) {
+        Ok(sides) => sides,
+        Err(diagnostics) => {
+            eprint!("{}", diagnostics::render(line, &diagnostics));
+            eprintln!("Error parsing the equation");
+            return ExitCode::FAILURE;
+        }
+    };
+    match conic::Conic::new(&left, &right) {
+        Ok(conic) => {
+            print!("{}", conic.describe());
+            ExitCode::SUCCESS
         }
-        vector.push(*map.get(k).unwrap());
-        i += 1;
+        Err(_) => {
+            eprintln!("The equation is not of degree 2 in X and Y");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn allowed_warnings(line: &str, poly: &Poly, options: &cli::Options) -> Vec<warnings::Warning> {
+    warnings::check(line, poly.coefficients(), &options.limits())
+        .into_iter()
+        .filter(|warning| !options.allowed.contains(&warning.category))
+        .collect()
+}
+
+/// `solve::solve_all` with the edits of the options, and what only the
+/// command line has: the warnings allowed, `--explain-discriminant` and
+/// `--domain`.
+fn solve_all(
+    input: &str,
+    options: &cli::Options,
+    cancel: &AtomicBool,
+    observer: Option<Observer>,
+) -> Result<Report, Vec<Diagnostic>> {
+    let solve_options = options.solve_options();
+    let mut report = solve::solve_all(input, &solve_options, &options.edits(), cancel, observer)?;
+    report
+        .warnings
+        .retain(|warning| !options.allowed.contains(&warning.category));
+    if options.explain_discriminant && report.degree == 2 {
+        let explanation = steps::discriminant_explanation(&report.coefficients);
+        report.steps.extend(explanation);
     }
-    while !vector.is_empty() && vector[vector.len() - 1] == 0.0 {
-        vector.pop();
+    if let Some(domain) = options.domain {
+        let (inside, outside) = domain.split(&report.solutions);
+        report.solutions = inside;
+        report
+            .roots
+            .retain(|root| domain.exclusion(root.value).is_none());
+        report.excluded = outside
+            .into_iter()
+            .map(|(value, reason)| report::Excluded { value, reason })
+            .collect();
     }
-    vector
+    Ok(report)
 }
 
-fn simplify_equations(
-    left_eq: HashMap<i32, f32>,
-    right_eq: HashMap<i32, f32>,
-) -> HashMap<i32, f32> {
-    let mut equation = left_eq;
-    for (k, v) in right_eq {
-        let monomial = equation.entry(k).or_insert(0.0);
-        *monomial -= v;
+fn print_parse_errors(line: &str, limits: &expr::Limits) {
+    if let Err(diagnostics) = expr::check_equation_with(line, limits) {
+        eprint!("{}", diagnostics::render(line, &diagnostics));
     }
-    equation
+    eprintln!("Error parsing the polynomial equation");
 }
 
-fn main() {
-    let args: Vec<String> = env::args().skip(1).collect();
-    if args.len() != 1 {
-        println!("Wrong numbers of arguments");
-        println!("Usage: ./computor \"5 * X^0 + 4 * X^1 - 9.3 * X^2 = 1 * X^0\"");
+/// The real solutions restricted to the domain, and the others with the
+/// reason they were excluded.
+fn print_domain(poly: &Poly, roots: &[f32], domain: Domain) {
+    if poly.is_zero() {
+        println!("Each real number in {} is a solution", domain);
         return;
     }
-    let poly = Poly::new(args.first().unwrap());
-    if poly.is_err() {
-        println!("Error parsing the polynomial equation");
+    let (inside, excluded) = domain.split(roots);
+    if inside.is_empty() {
+        println!("No solution in the domain {}", domain);
+    } else {
+        println!("Solutions in the domain {}:", domain);
+        for root in inside {
+            println!("{}", float::shortest(root + 0.0));
+        }
+    }
+    for (root, reason) in excluded {
+        println!("Excluded {}: {}", float::shortest(root + 0.0), reason);
+    }
+}
+
+/// The roots off the real axis, one line per conjugate pair, from the same
+/// solvers as the real ones.
+fn print_complex(pairs: &[report::ComplexPair]) {
+    if pairs.is_empty() {
         return;
     }
-    let poly = poly.unwrap();
-    poly.print();
+    println!("Complex solutions:");
+    for pair in pairs {
+        println!("{}", pair.text);
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+fn print_rounded(digits: usize, rounded: &[String]) {
+    if rounded.is_empty() {
+        return;
+    }
+    println!("Solutions rounded to {} decimal places:", digits);
+    for text in rounded {
+        println!("{}", text);
+    }
+}
 
-    fn equivalent_solution(left: Vec<f32>, right: Vec<f32>) -> bool {
-        if left.len() != right.len() {
-            return false;
+/// Everything printed in text mode besides the file exports, the roots
+/// taken from the report rather than solved again.
+fn print_text(options: &cli::Options, poly: &Poly, report: &Report) {
+    if options.steps {
+        if let Ok((left, right)) = expr::parse_equation_with(&options.equation, &options.limits()) {
+            steps::print_expansion(&left, &right, options.style()).ok();
+        }
+    }
+    poly.print_solved(options.style(), &report.solutions);
+    if options.complex {
+        print_complex(&report.complex_roots);
+    }
+    if options.steps {
+        for line in steps::unsolvability(poly.coefficients()) {
+            println!("{}", line);
+        }
+    }
+    if let Some(domain) = options.domain {
+        print_domain(poly, &report.solutions, domain);
+    }
+    if options.residuals {
+        if let Ok((left, right)) = expr::parse_equation_with(&options.equation, &options.limits()) {
+            steps::print_residuals(&left, &right, &report.solutions);
+        }
+    }
+    if (options.plot || options.plot_sides) && poly.is_zero() {
+        eprintln!(
+            "Nothing to plot: the reduced polynomial is zero, each real number is a solution"
+        );
+    } else if options.plot || options.plot_sides {
+        let size = options.plot_size();
+        let roots: Vec<f64> = report.solutions.iter().map(|&r| r as f64).collect();
+        match expr::parse_equation_with(&options.equation, &options.limits()) {
+            Ok((left, right)) if options.plot_sides => {
+                print!("{}", plot::plot_sides(&left, &right, &roots, size))
+            }
+            _ => print!(
+                "{}",
+                plot::plot_polynomial(&poly.coefficients_f64(), &roots, size)
+            ),
+        }
+    }
+    if options.argand {
+        if poly.get_degree() >= 1 {
+            let size = options.plot_size();
+            let roots = numeric::durand_kerner(&poly.coefficients_f64());
+            print!("{}", plot::argand(&roots, size));
+        } else {
+            eprintln!("The Argand diagram needs an equation of degree 1 or more");
         }
-        let wrong = left
-            .iter()
-            .zip(right)
-            .filter(|&(a, b)| (a - b).abs() > 0.00001)
-            .count();
-        wrong == 0
     }
+    if options.share {
+        println!(
+            "Desmos (https://www.desmos.com/calculator): {}",
+            format::desmos_expression(poly.coefficients())
+        );
+        println!(
+            "GeoGebra (https://www.geogebra.org/graphing): {}",
+            format::geogebra_expression(poly.coefficients())
+        );
+    }
+    if let Some(plot::Animation::Newton) = options.animate {
+        let size = options.plot_size();
+        let roots: Vec<f64> = report.solutions.iter().map(|&r| r as f64).collect();
+        let frames = plot::newton_frames(&poly.coefficients_f64(), &roots, size);
+        animate(frames, !options.deterministic && io::stdout().is_terminal());
+    }
+    if options.formula {
+        if poly.get_degree() == 2 {
+            println!("{}", steps::quadratic_formula(poly.coefficients()));
+        } else {
+            eprintln!("The formula is only shown for degree 2 equations");
+        }
+    }
+    if options.explain_discriminant {
+        if poly.get_degree() == 2 {
+            for line in steps::discriminant_explanation(poly.coefficients()) {
+                println!("{}", line);
+            }
+        } else {
+            eprintln!("The discriminant is only explained for degree 2 equations");
+        }
+    }
+    if let Some(digits) = options.precision {
+        print_rounded(digits, &report.rounded);
+    }
+    if let Some(count) = options.continued_fraction {
+        poly.print_continued_fractions(count);
+    }
+    if options.rearrange {
+        if poly.get_degree() == 1 {
+            steps::print_rearrangement(poly.coefficients());
+        } else {
+            eprintln!("Rearrangement is only shown for degree 1 equations");
+        }
+    }
+    if options.analyze {
+        poly.print_analysis(!options.float);
+    }
+    if options.factor && options.float {
+        eprintln!("Factoring is exact, it is not done with --float");
+    } else if options.factor {
+        poly.print_factors(options.style());
+    }
+    if let Some(method) = options.method {
+        poly.print_method(method);
+    }
+    if options.sign {
+        print!("{}", sign::describe(&poly.coefficients_f64()));
+    }
+    if options.extrema {
+        print!("{}", sign::describe_extrema(&poly.coefficients_f64()));
+    }
+    if options.symmetric {
+        if poly.get_degree() >= 1 {
+            print!("{}", symmetric::describe(&poly.coefficients_f64()));
+        } else {
+            eprintln!("Symmetric functions need an equation of degree 1 or more");
+        }
+    }
+    if options.compare_methods {
+        if poly.get_degree() >= 1 {
+            let runs = method::compare(&poly.coefficients_f64());
+            print!(
+                "{}",
+                method::comparison_table(&runs, !options.deterministic)
+            );
+        } else {
+            eprintln!("Methods are only compared for equations of degree 1 or more");
+        }
+    }
+    if let Some((a, b)) = options.count_roots {
+        poly.print_root_count(a, b);
+    }
+    if let Some((a, b)) = options.has_root {
+        let answer = if poly.has_real_root_in(a, b) {
+            "yes"
+        } else {
+            "no"
+        };
+        println!(
+            "A real root in [{}, {}]: {}",
+            float::shortest(a),
+            float::shortest(b),
+            answer
+        );
+    }
+}
 
-    #[test]
-    fn error_when_no_equal_sign() {
-        let no_equal_sign = "5 * X^0 + 4 * X^1 - 9.3 * X^2";
-        assert!(parse(no_equal_sign).is_err());
+/// Writes the requested files and the clipboard, which receives the
+/// structured record when there is one and the solutions otherwise. Only
+/// status messages are printed, on stderr. Returns whether everything was
+/// written. The plots mark every real root of the report, those outside
+/// `--domain` included.
+fn export(options: &cli::Options, poly: &Poly, report: &Report, record: Option<String>) -> bool {
+    let mut roots: Vec<f64> = report.solutions.iter().map(|&r| r as f64).collect();
+    roots.extend(report.excluded.iter().map(|excluded| excluded.value as f64));
+    roots.sort_by(f64::total_cmp);
+    let coefficients = poly.coefficients_f64();
+    let dpi = options.dpi.unwrap_or(export::DEFAULT_DPI);
+    let written = [
+        options
+            .plot_svg
+            .as_ref()
+            .map(|path| (path, export::write_svg(path, &coefficients, &roots))),
+        options
+            .plot_png
+            .as_ref()
+            .map(|path| (path, export::write_png(path, &coefficients, &roots, dpi))),
+        options.export_gnuplot.as_ref().map(|path| {
+            let script = export::gnuplot_script(&coefficients, &roots);
+            (path, fs::write(path, script).map_err(|e| e.to_string()))
+        }),
+    ];
+    let mut exported = true;
+    for (path, result) in written.into_iter().flatten() {
+        match result {
+            Ok(()) => eprintln!("Written to {}", path),
+            Err(error) => {
+                eprintln!("Could not write {}: {}", path, error);
+                exported = false;
+            }
+        }
     }
+    if options.copy {
+        let text = record.unwrap_or_else(|| {
+            let roots: Vec<String> = report
+                .solutions
+                .iter()
+                .map(|&r| float::shortest(r))
+                .collect();
+            roots.join("\n")
+        });
+        match copy_to_clipboard(text) {
+            Ok(()) => eprintln!("Copied to the clipboard"),
+            Err(error) => {
+                eprintln!("Could not copy to the clipboard: {}", error);
+                exported = false;
+            }
+        }
+    }
+    exported
+}
 
-    #[test]
-    fn parse_basic_monomial() {
-        let basic_monomial = "5*X^0";
-        assert_eq!(parse_monomial(basic_monomial), Ok((5.0, 0)));
+fn main() -> ExitCode {
+    let options = match cli::parse_args(env::args().skip(1).collect()) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}", message);
+            eprintln!("{}", cli::USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+    let limits = options.limits();
+    let mut random = match options.seed {
+        Some(seed) => random::Random::new(seed),
+        None if options.deterministic => random::Random::new(cli::DETERMINISTIC_SEED),
+        None => random::Random::from_time(),
+    };
+    let degree = options.degree.unwrap_or(cli::DEFAULT_DEGREE);
+    let count = options.count.unwrap_or(cli::DEFAULT_COUNT);
+    if options.command == cli::Command::Generate {
+        for _ in 0..count {
+            println!(
+                "{}",
+                generate::equation(&mut random, degree, options.integer_roots)
+            );
+        }
+        return ExitCode::SUCCESS;
+    }
+    if options.command == cli::Command::Quiz {
+        quiz::run(io::stdin().lock(), &mut random, degree, count);
+        return ExitCode::SUCCESS;
+    }
+    if options.command == cli::Command::Repl {
+        if let Err(error) = repl::interactive(&mut repl_session(&options)) {
+            eprintln!("Could not read the input: {}", error);
+            return ExitCode::FAILURE;
+        }
+        return ExitCode::SUCCESS;
+    }
+    if options.command == cli::Command::Run {
+        return run_script(&options);
+    }
+    if options.command == cli::Command::Batch {
+        return run_batch(&options);
+    }
+    if options.command == cli::Command::Verify {
+        return print_verify(&options);
+    }
+    if options.command == cli::Command::Schema {
+        return print_schema(&options.equation);
+    }
+    if options.command == cli::Command::Preview {
+        return print_preview(&options.equation, options.style());
+    }
+    if options.command == cli::Command::Extract {
+        return print_extract(&options.equation, options.style());
+    }
+    if options.command == cli::Command::Grade {
+        return print_grade(&options.equation);
+    }
+    if matches!(
+        options.command,
+        cli::Command::Add | cli::Command::Sub | cli::Command::Mul
+    ) {
+        return print_arithmetic(&options);
+    }
+    if options.command == cli::Command::Common {
+        return print_common(&options.equation, &options.second, &limits, options.style());
     }
+    if options.command == cli::Command::Shift {
+        let by = options.by.unwrap_or_default();
+        return print_shift(&options.equation, &limits, by, options.style());
+    }
+    if let (cli::Command::Transform, Some(transform)) = (&options.command, options.transform) {
+        return print_transform(&options.equation, &limits, transform, options.style());
+    }
+    if options.command == cli::Command::Discriminant {
+        return print_discriminant(&options.equation, &limits, options.style());
+    }
+    if options.command == cli::Command::Conic {
+        return print_conic(&options.equation, &limits);
+    }
+    if options.command == cli::Command::Diff && !options.second.is_empty() {
+        return print_equation_diff(&options.equation, &options.second, &limits, options.style());
+    }
+    if options.command == cli::Command::Diff {
+        return print_derivative(&options.equation, &limits, options.style());
+    }
+    let units = match units::annotate(&options.equation, &limits) {
+        Ok(units) => units,
+        Err(message) => {
+            eprintln!("{}", message);
+            return ExitCode::FAILURE;
+        }
+    };
+    let options = match &units {
+        Some(units) => cli::Options {
+            equation: units.equation.clone(),
+            ..options
+        },
+        None => options,
+    };
+    let unit = units.and_then(|units| units.unknown);
+    if options.strict {
+        let diagnostics = expr::check_strict_with(&options.equation, &options.limits());
+        if !diagnostics.is_empty() {
+            eprint!("{}", diagnostics::render(&options.equation, &diagnostics));
+            eprintln!("The equation does not follow the subject grammar");
+            return ExitCode::FAILURE;
+        }
+    }
+    if options.tree || options.dot {
+        match expr::parse_equation_with(&options.equation, &options.limits()) {
+            Ok((left, right)) if options.dot => print!("{}", expr::equation_dot(&left, &right)),
+            Ok((left, right)) => print!("{}", expr::equation_tree(&left, &right)),
+            Err(_) => {
+                print_parse_errors(&options.equation, &options.limits());
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    let coefficients = expr::parse_equation_with(&options.equation, &options.limits())
+        .and_then(|(left, right)| expr::Expr::Sub(Box::new(left), Box::new(right)).expand());
+    let mut poly = match coefficients {
+        Ok(coefficients) => Poly::from_coefficients(coefficients),
+        Err(error) => {
+            record_stats(&options, |stats| stats.record_error(&error));
+            if let ("json", Err(diagnostics)) = (
+                options.output_name(),
+                expr::check_equation_with(&options.equation, &options.limits()),
+            ) {
+                print!("{}", diagnostics::to_json(&diagnostics));
+            }
+            print_parse_errors(&options.equation, &options.limits());
+            return ExitCode::FAILURE;
+        }
+    };
+    let method = options.method.unwrap_or(method::Method::Auto);
+    record_stats(&options, |stats| stats.record(&poly, method));
+    let warnings = allowed_warnings(&options.equation, &poly, &options);
+    for warning in &warnings {
+        eprintln!("{}", warning.render(options.deny_warnings));
+    }
+    if options.deny_warnings && !warnings.is_empty() {
+        return ExitCode::FAILURE;
+    }
+    if options.command == cli::Command::CheckAnswer {
+        let tolerance = options.tolerance.unwrap_or(cli::DEFAULT_TOLERANCE);
+        return print_check_answer(&poly, &options.roots, tolerance);
+    }
+    poly.edit(&options);
+    // --time follows the stages of the solve below as its events arrive
+    let clock = timing::Clock::new();
+    let observe = |event: &Event| {
+        if options.events {
+            eprintln!("event: {}", event);
+        }
+        clock.observe(event);
+    };
+    let observer: Option<Observer> = match options.events || options.time {
+        true => Some(&observe),
+        false => None,
+    };
+    // a high degree can take a while, Ctrl-C prints what was found
+    handle_interrupt();
+    // solved once, the events told as they happen; the text has its own
+    // --domain and warnings, so takes every root
+    let report = match options.output_name() {
+        "text" => solve::solve_all(
+            &options.equation,
+            &options.solve_options(),
+            &options.edits(),
+            &INTERRUPTED,
+            observer,
+        ),
+        _ => solve_all(&options.equation, &options, &INTERRUPTED, observer),
+    };
+    let mut timings = timing::Timings::default();
+    clock.finish(&mut timings);
+    let mut report = match report {
+        Ok(report) => report,
+        Err(diagnostics) => {
+            eprint!("{}", diagnostics::render(&options.equation, &diagnostics));
+            return ExitCode::FAILURE;
+        }
+    };
+    report.unit = unit.as_ref().map(|unit| unit.to_string());
+    let record = match options.output_name() {
+        "text" => None,
+        output => render::Renderers::default().render(output, &report),
+    };
+    timings.measure("output", || match &record {
+        Some(record) => print!("{}", record),
+        None => print_text(&options, &poly, &report),
+    });
+    if let (None, Some(unit)) = (&record, &unit) {
+        if unit.is_dimensionless() {
+            println!("X is dimensionless");
+        } else {
+            println!("Unit of X: {}", unit);
+            for &root in &report.solutions {
+                println!("X = {} {}", float::shortest(root + 0.0), unit);
+            }
+        }
+    }
+    let interrupted = record.is_none() && report.cancelled;
+    let exported = export(&options, &poly, &report, record);
+    if options.time {
+        eprint!("{}", timings.report());
+    }
+    if interrupted {
+        eprintln!("Interrupted, the solutions are those found by then");
+        return ExitCode::from(INTERRUPTED_EXIT);
+    }
+    match exported {
+        true => ExitCode::SUCCESS,
+        false => ExitCode::FAILURE,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use computor_core::Reduced;
+    use computor_macros::poly;
 
     #[test]
-    fn test_parse_equation() {
-        let line = "8 * X^0 - 6 * X^1 + 0 * X^2 - 5.6 * X^3 = 3 * X^0";
-        let simplified = parse(line);
-        let answer: Vec<f32> = vec![5.0, -6.0, 0.0, -5.6];
-        assert_eq!(simplified, Ok(answer));
+    fn equations_parsed_at_build_time() {
+        const CONSTANT: &[f32] = poly!("5 * X^2 - 3 = 0");
+        assert_eq!(
+            CONSTANT,
+            Poly::new("5 * X^2 - 3 = 0").unwrap().coefficients()
+        );
+        const ZERO: Reduced = Reduced::of(poly!("X = X"));
+        assert_eq!(ZERO, Reduced::ZeroPolynomial);
+        let poly = Poly::from_coefficients(poly!("-0 * X = 1").to_vec());
+        assert_eq!(poly.coefficients(), [-1.0]);
+        assert_eq!(poly!(r"X = 1"), poly!("X = \x31"));
     }
 
     #[test]
-    fn test_parse_bonus() {
-        let line = "5 + 4 * X + X^2= X^2";
-        let simplified = parse(line);
-        let answer: Vec<f32> = vec![5.0, 4.0];
-        assert_eq!(simplified, Ok(answer));
+    fn equivalent_equations_share_solutions() {
+        let poly = |line| Poly::new(line).unwrap();
+        assert!(same_solutions(&poly("2 * X + 1 = 5"), &poly("X = 2")));
+        assert!(same_solutions(
+            &poly("X^2 = 4"),
+            &poly("(X - 2) * (X + 2) = 0")
+        ));
+        assert!(same_solutions(&poly("X^2 = -1"), &poly("X^2 = -4")));
+        assert!(!same_solutions(&poly("X^2 = 4"), &poly("X = 2")));
+        assert!(!same_solutions(&poly("X = X"), &poly("X = 2")));
+        assert!(same_solutions(&poly("X = X"), &poly("0 = 0")));
     }
 
     #[test]
-    fn test_poly() {
-        let line = "5 * X^0 + 4 * X^1 - 9.3 * X^2 = 1 * X^0";
-        let coefficients: Vec<f32> = vec![4.0, 4.0, -9.3];
-        let poly = Poly::new(line).unwrap();
-        assert_eq!(poly.coefficients, coefficients);
-        assert_eq!(poly.get_degree(), 2);
+    fn solve_all_adds_the_command_line_options() {
+        let options = cli::Options {
+            allowed: vec![warnings::Category::ZeroTerm],
+            explain_discriminant: true,
+            domain: Some(Domain::parse("0..").unwrap()),
+            ..Default::default()
+        };
+        let never = AtomicBool::new(false);
+        let report = solve_all("0 * X^3 + 4 = X^2", &options, &never, None).unwrap();
+        assert!(report.warnings.is_empty());
+        assert_eq!(
+            report.steps.last().unwrap(),
+            "Δ > 0: the parabola crosses the X-axis at two points, two real solutions"
+        );
+        assert_eq!(report.solutions, [2.0]);
+        assert_eq!(report.roots.len(), 1);
+        assert_eq!(report.excluded[0].value, -2.0);
+        assert_eq!(report.excluded[0].reason, "below 0");
     }
 
     #[test]
-    fn test_solve() {
-        let line = "5 * X^0 + 4 * X^1 - 9.3 * X^2 = 1 * X^0";
-        let poly = Poly::new(line).unwrap();
-        let solutions = poly.solve().unwrap();
-        assert!(equivalent_solution(solutions, vec![-0.475131, 0.905239]));
-
-        let line = "5 * X^0 + 4 * X^1 = 4 * X^0";
-        let poly = Poly::new(line).unwrap();
-        let solutions = poly.solve().unwrap();
-        assert!(equivalent_solution(solutions, vec![-0.25]));
-
-        let line = "8 * X^0 - 6 * X^1 + 0 * X^2 - 5.6 * X^3 = 3 * X^0";
-        let poly = Poly::new(line).unwrap();
-        let solutions = poly.solve();
-        assert_eq!(solutions, None);
-
-        let line = "5 + 4 * X + X^2= X^2";
-        let poly = Poly::new(line).unwrap();
-        let solutions = poly.solve().unwrap();
-        assert!(equivalent_solution(solutions, vec![-1.25]));
-
-        let line = "42 * X^0= 42 * X^0";
-        let poly = Poly::new(line).unwrap();
-        let solutions = poly.solve();
-        assert_eq!(solutions, None);
-
-        let line = "3 = 0";
-        let poly = Poly::new(line).unwrap();
-        let solutions = poly.solve();
-        assert_eq!(solutions, None);
+    fn full_report_conforms_to_the_schema() {
+        let options = cli::Options {
+            steps: true,
+            complex: true,
+            domain: Some(Domain::parse("0..").unwrap()),
+            ..Default::default()
+        };
+        let never = AtomicBool::new(false);
+        let report = solve_all("0 * X^4 + X^3 = 1", &options, &never, None).unwrap();
+        assert!(!report.complex_roots.is_empty() && !report.warnings.is_empty());
+        let mut report = report;
+        report.unit = Some(String::from("s"));
+        report.cancelled = true;
+        let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+        assert_eq!(schema::check(&json), Ok(()));
     }
 }
@@ -0,0 +1,337 @@
+use crate::complex::Complex;
+use crate::expr::Expr;
+use crate::float;
+use crate::numeric;
+
+use terminal_size::{terminal_size, Height, Width};
+
+/// Used when the output is not a terminal, e.g. when piped into a file.
+const WIDTH: usize = 61;
+const HEIGHT: usize = 21;
+const MIN_WIDTH: usize = 21;
+const MIN_HEIGHT: usize = 7;
+/// Lines printed below the canvas: the ranges and the legend.
+const FOOTER: usize = 2;
+
+/// The size of the canvas in characters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Size {
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Size {
+    /// Fits the canvas to the terminal, keeping a roughly 3:1 ratio since
+    /// characters are about twice as high as they are wide. The given
+    /// dimensions take precedence over the detected ones.
+    pub fn detect(width: Option<usize>, height: Option<usize>) -> Size {
+        let terminal = terminal_size().map(|(Width(w), Height(h))| (w as usize, h as usize));
+        Size::fit(width, height, terminal)
+    }
+
+    /// Ignores the terminal, so that the canvas is the same wherever the
+    /// output goes.
+    pub fn fixed(width: Option<usize>, height: Option<usize>) -> Size {
+        Size::fit(width, height, None)
+    }
+
+    fn fit(width: Option<usize>, height: Option<usize>, terminal: Option<(usize, usize)>) -> Size {
+        let width = width.unwrap_or(match terminal {
+            Some((columns, _)) => columns.saturating_sub(1),
+            None => WIDTH,
+        });
+        let height = height.unwrap_or(match terminal {
+            Some((_, rows)) => (width / 3).min(rows.saturating_sub(FOOTER + 1)),
+            None => HEIGHT,
+        });
+        Size {
+            width: width.max(MIN_WIDTH),
+            height: height.max(MIN_HEIGHT),
+        }
+    }
+}
+
+/// A character grid mapping a rectangle of the plane, with the axes drawn
+/// when they are in view.
+struct Canvas {
+    cells: Vec<Vec<char>>,
+    x_range: (f64, f64),
+    y_range: (f64, f64),
+}
+
+impl Canvas {
+    fn new(width: usize, height: usize, x_range: (f64, f64), y_range: (f64, f64)) -> Canvas {
+        let mut canvas = Canvas {
+            cells: vec![vec![' '; width]; height],
+            x_range,
+            y_range,
+        };
+        if let Some(row) = canvas.row(0.0) {
+            canvas.cells[row].iter_mut().for_each(|cell| *cell = '-');
+        }
+        if let Some(column) = canvas.column(0.0) {
+            for row in canvas.cells.iter_mut() {
+                row[column] = if row[column] == '-' { '+' } else { '|' };
+            }
+        }
+        canvas
+    }
+
+    fn width(&self) -> usize {
+        self.cells[0].len()
+    }
+
+    fn height(&self) -> usize {
+        self.cells.len()
+    }
+
+    fn x(&self, column: usize) -> f64 {
+        let (a, b) = self.x_range;
+        a + (b - a) * column as f64 / (self.width() - 1) as f64
+    }
+
+    fn column(&self, x: f64) -> Option<usize> {
+        let (a, b) = self.x_range;
+        let column = ((x - a) / (b - a) * (self.width() - 1) as f64).round();
+        (0.0..self.width() as f64)
+            .contains(&column)
+            .then_some(column as usize)
+    }
+
+    fn row(&self, y: f64) -> Option<usize> {
+        let (a, b) = self.y_range;
+        let row = ((b - y) / (b - a) * (self.height() - 1) as f64).round();
+        (0.0..self.height() as f64)
+            .contains(&row)
+            .then_some(row as usize)
+    }
+
+    /// Draws the curve column by column, filling the vertical gaps so that
+    /// steep parts stay connected.
+    fn plot(&mut self, f: impl Fn(f64) -> f64, mark: char) {
+        let mut previous: Option<f64> = None;
+        for column in 0..self.width() {
+            let height = (self.height() - 1) as f64;
+            let (a, b) = self.y_range;
+            let row = (b - f(self.x(column))) / (b - a) * height;
+            let (low, high) = match previous {
+                Some(previous) => (previous.min(row).max(0.0), previous.max(row).min(height)),
+                None => (row, row),
+            };
+            let mut r = low.round();
+            while r <= high.round() {
+                if (0.0..=height).contains(&r) {
+                    self.cells[r as usize][column] = mark;
+                }
+                r += 1.0;
+            }
+            previous = Some(row);
+        }
+    }
+
+    fn mark(&mut self, x: f64, y: f64, mark: char) {
+        if let (Some(column), Some(row)) = (self.column(x), self.row(y)) {
+            self.cells[row][column] = mark;
+        }
+    }
+
+    fn render(&self) -> String {
+        let mut output = String::new();
+        for row in &self.cells {
+            output += row.iter().collect::<String>().trim_end();
+            output += "\n";
+        }
+        output += &format!(
+            "X from {} to {}, Y from {} to {}\n",
+            float::shortest(self.x_range.0 as f32),
+            float::shortest(self.x_range.1 as f32),
+            float::shortest(self.y_range.0 as f32),
+            float::shortest(self.y_range.1 as f32)
+        );
+        output
+    }
+}
+
+/// The processes `--animate` can show on the plot.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Animation {
+    Newton,
+}
+
+const ANIMATION_STEPS: usize = 12;
+
+/// Frames the roots with some margin on both sides.
+pub fn x_range(roots: &[f64]) -> (f64, f64) {
+    if roots.is_empty() {
+        return (-5.0, 5.0);
+    }
+    let low = roots.iter().cloned().fold(f64::INFINITY, f64::min);
+    let high = roots.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let margin = (high - low).max(4.0) / 2.0;
+    (low - margin, high + margin)
+}
+
+/// Samples the curves once per column to fit them vertically.
+pub fn y_range(x_range: (f64, f64), width: usize, curves: &[&dyn Fn(f64) -> f64]) -> (f64, f64) {
+    let (mut low, mut high) = (0.0, 0.0);
+    for f in curves {
+        for i in 0..width {
+            let y = f(x_range.0 + (x_range.1 - x_range.0) * i as f64 / (width - 1) as f64);
+            if y.is_finite() {
+                low = f64::min(low, y);
+                high = f64::max(high, y);
+            }
+        }
+    }
+    if high - low < 1e-9 {
+        (low - 1.0, high + 1.0)
+    } else {
+        (low, high)
+    }
+}
+
+/// The reduced polynomial as a curve crossing the X axis at its roots.
+pub fn plot_polynomial(coefficients: &[f64], roots: &[f64], size: Size) -> String {
+    let polynomial = |x| numeric::eval(coefficients, x);
+    let x_range = x_range(roots);
+    let y_range = y_range(x_range, size.width, &[&polynomial]);
+    let mut canvas = Canvas::new(size.width, size.height, x_range, y_range);
+    canvas.plot(polynomial, '*');
+    for &root in roots {
+        canvas.mark(root, 0.0, '@');
+    }
+    canvas.render() + "* reduced polynomial, @ solutions\n"
+}
+
+/// Both sides of the equation as separate curves, the solutions being where
+/// they intersect.
+pub fn plot_sides(left: &Expr, right: &Expr, roots: &[f64], size: Size) -> String {
+    let left_side = |x| left.eval(x);
+    let right_side = |x| right.eval(x);
+    let x_range = x_range(roots);
+    let y_range = y_range(x_range, size.width, &[&left_side, &right_side]);
+    let mut canvas = Canvas::new(size.width, size.height, x_range, y_range);
+    canvas.plot(left_side, '*');
+    canvas.plot(right_side, 'o');
+    for &root in roots {
+        canvas.mark(root, left.eval(root), '@');
+    }
+    canvas.render() + "* left side, o right side, @ intersections\n"
+}
+
+/// The complex roots on the plane with the unit circle for reference. The
+/// ranges keep the circle round, characters being about twice as high as
+/// they are wide.
+pub fn argand(roots: &[Complex], size: Size) -> String {
+    let radius = roots.iter().map(Complex::norm).fold(1.0, f64::max) * 1.2;
+    let aspect = size.width as f64 / (2.0 * size.height as f64);
+    let (half_width, half_height) = if aspect >= 1.0 {
+        (radius * aspect, radius)
+    } else {
+        (radius, radius / aspect)
+    };
+    let mut canvas = Canvas::new(
+        size.width,
+        size.height,
+        (-half_width, half_width),
+        (-half_height, half_height),
+    );
+    let points = 4 * (size.width + size.height);
+    for i in 0..points {
+        let point = Complex::from_polar(1.0, 2.0 * std::f64::consts::PI * i as f64 / points as f64);
+        canvas.mark(point.re, point.im, '.');
+    }
+    for root in roots {
+        canvas.mark(root.re, root.im, '@');
+    }
+    canvas.render() + "X real part, Y imaginary part, . unit circle, @ roots\n"
+}
+
+/// One frame per Newton step, starting from the right edge of the plot: the
+/// tangent at the current iterate crosses the X axis at the next one.
+pub fn newton_frames(coefficients: &[f64], roots: &[f64], size: Size) -> Vec<String> {
+    let polynomial = |x| numeric::eval(coefficients, x);
+    let derivative = numeric::derivative(coefficients);
+    let x_range = x_range(roots);
+    let y_range = y_range(x_range, size.width, &[&polynomial]);
+    let iterates = numeric::newton_iterates(coefficients, x_range.1, ANIMATION_STEPS);
+    let mut frames = vec![];
+    for (step, pair) in iterates.windows(2).enumerate() {
+        let (x, next) = (pair[0], pair[1]);
+        let (y, slope) = (polynomial(x), numeric::eval(&derivative, x));
+        let mut canvas = Canvas::new(size.width, size.height, x_range, y_range);
+        canvas.plot(polynomial, '*');
+        canvas.plot(|t| y + slope * (t - x), '.');
+        canvas.mark(x, y, 'o');
+        canvas.mark(next, 0.0, '@');
+        frames.push(format!(
+            "{}Step {}: X = {} -> {}\n",
+            canvas.render(),
+            step + 1,
+            float::shortest(x as f32),
+            float::shortest(next as f32)
+        ));
+    }
+    frames
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canvas_maps_plane_to_cells() {
+        let canvas = Canvas::new(11, 5, (-5.0, 5.0), (-2.0, 2.0));
+        assert_eq!(canvas.column(0.0), Some(5));
+        assert_eq!(canvas.column(6.0), None);
+        assert_eq!(canvas.row(2.0), Some(0));
+        assert_eq!(canvas.row(0.0), Some(2));
+        assert_eq!(canvas.render().lines().nth(2), Some("-----+-----"));
+    }
+
+    #[test]
+    fn plot_marks_roots_on_axis() {
+        // X^2 - 1
+        let size = Size {
+            width: WIDTH,
+            height: HEIGHT,
+        };
+        let plot = plot_polynomial(&[-1.0, 0.0, 1.0], &[-1.0, 1.0], size);
+        let axis = plot.lines().find(|line| line.contains('@')).unwrap();
+        assert_eq!(axis.matches('@').count(), 2);
+    }
+
+    #[test]
+    fn newton_frames_follow_iterates() {
+        let size = Size::detect(Some(40), Some(10));
+        let frames = newton_frames(&[-1.0, 0.0, 1.0], &[-1.0, 1.0], size);
+        assert!(frames.len() > 2 && frames.len() <= ANIMATION_STEPS);
+        assert!(frames[0].ends_with("Step 1: X = 3 -> 1.6666666\n"));
+        assert!(frames.iter().all(|frame| frame.contains('.')));
+    }
+
+    #[test]
+    fn argand_shows_conjugates() {
+        // X^2 + 4, the roots above and below the center on the Y axis
+        let roots = [Complex::new(0.0, 2.0), Complex::new(0.0, -2.0)];
+        let plot = argand(&roots, Size::detect(Some(41), Some(11)));
+        let lines: Vec<&str> = plot.lines().collect();
+        assert_eq!(plot.matches('@').count(), 2 + 1);
+        assert_eq!(lines[1].find('@'), Some(20));
+        assert_eq!(lines[9].find('@'), Some(20));
+        assert!(lines[5].contains('.'));
+    }
+
+    #[test]
+    fn size_overrides_terminal() {
+        let size = Size::detect(Some(100), Some(30));
+        assert_eq!((size.width, size.height), (100, 30));
+        let size = Size::detect(Some(3), Some(1));
+        assert_eq!((size.width, size.height), (MIN_WIDTH, MIN_HEIGHT));
+        let size = Size::fixed(None, Some(15));
+        assert_eq!((size.width, size.height), (WIDTH, 15));
+        let plot = plot_polynomial(&[-1.0, 0.0, 1.0], &[], Size::detect(Some(40), Some(10)));
+        assert_eq!(plot.lines().count(), 10 + FOOTER);
+        assert!(plot.lines().take(10).all(|line| line.chars().count() <= 40));
+    }
+}
@@ -0,0 +1,117 @@
+use crate::cli;
+use crate::expr;
+use crate::format::Style;
+use crate::generate;
+use crate::random::Random;
+use crate::steps;
+use crate::{Commands, Poly};
+use std::io::{self, BufRead, Write};
+
+const TOLERANCE: f32 = 1e-3;
+
+#[derive(Debug, PartialEq)]
+enum Answer {
+    Roots(Vec<f32>),
+    Steps,
+    Quit,
+    Invalid(String),
+}
+
+fn answer(line: &str) -> Answer {
+    match line.trim() {
+        "steps" => Answer::Steps,
+        "quit" => Answer::Quit,
+        line => match cli::parse_roots("answer", line) {
+            Ok(roots) => Answer::Roots(roots),
+            Err(message) => Answer::Invalid(message),
+        },
+    }
+}
+
+/// The worked solution: the sides expanded, then the reduced form solved.
+fn print_steps(line: &str, poly: &Poly) {
+    if let Ok((left, right)) = expr::parse_equation(line) {
+        steps::print_expansion(&left, &right, Style::default()).ok();
+    }
+    poly.print(Style::default());
+    if poly.get_degree() == 1 {
+        steps::print_rearrangement(poly.coefficients());
+    }
+}
+
+/// Asks `count` questions with integer roots until the input ends or the
+/// user quits, and returns the score with the number of questions asked.
+pub fn run(
+    input: impl BufRead,
+    random: &mut Random,
+    degree: usize,
+    count: usize,
+) -> (usize, usize) {
+    let mut lines = input.lines();
+    let (mut score, mut asked) = (0, 0);
+    'questions: while asked < count {
+        let line = generate::equation(random, degree, true);
+        let poly = match Poly::new(&line) {
+            Ok(poly) => poly,
+            Err(_) => continue,
+        };
+        asked += 1;
+        println!("Question {}: {}", asked, line);
+        loop {
+            print!("Solutions (comma separated), `steps` or `quit`: ");
+            io::stdout().flush().unwrap_or_default();
+            let typed = match lines.next() {
+                Some(Ok(typed)) => answer(&typed),
+                _ => {
+                    println!();
+                    Answer::Quit
+                }
+            };
+            match typed {
+                Answer::Steps => print_steps(&line, &poly),
+                Answer::Quit => {
+                    // the question left unanswered does not count
+                    asked -= 1;
+                    break 'questions;
+                }
+                Answer::Invalid(message) => println!("{}", message),
+                Answer::Roots(roots) => {
+                    let (verdicts, missing) = poly.check_answer(&roots, TOLERANCE);
+                    if missing.is_empty() && verdicts.iter().all(|(_, correct)| *correct) {
+                        println!("Correct!");
+                        score += 1;
+                    } else {
+                        let solutions: Vec<String> =
+                            poly.real_roots().iter().map(|r| r.to_string()).collect();
+                        println!("Not quite, the solutions are: {}", solutions.join(", "));
+                    }
+                    break;
+                }
+            }
+        }
+    }
+    println!("Score: {}/{}", score, asked);
+    (score, asked)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_answers() {
+        assert_eq!(answer(" 2, -3 "), Answer::Roots(vec![2.0, -3.0]));
+        assert_eq!(answer("steps"), Answer::Steps);
+        assert_eq!(answer("quit"), Answer::Quit);
+        assert!(matches!(answer("two"), Answer::Invalid(_)));
+    }
+
+    #[test]
+    fn score_answers() {
+        let mut random = Random::new(1);
+        let line = generate::equation(&mut Random::new(1), 1, true);
+        let root = Poly::new(&line).unwrap().real_roots()[0];
+        let input = format!("steps\n{}\n", root);
+        assert_eq!(run(input.as_bytes(), &mut random, 1, 3), (1, 1));
+    }
+}
@@ -0,0 +1,566 @@
+use crate::cli;
+use crate::decimal::Rounding;
+use crate::diagnostics::{self, Diagnostic};
+use crate::export;
+use crate::expr;
+use crate::float;
+use crate::format::{format_polynomial, Style};
+use crate::numeric;
+use crate::solve::SolveOptions;
+use crate::suggest::{suggest, Suggestion};
+use crate::{Commands, Poly};
+use rustyline::completion::{Completer, Pair};
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, BufRead};
+use std::path::Path;
+
+const PROMPT: &str = "> ";
+
+/// Words that cannot name a polynomial.
+const COMMANDS: [&str; 12] = [
+    "solve", "eval", "plot", "delete", "list", "undo", "redo", "set", "history", "save", "load",
+    "quit",
+];
+
+/// The named polynomials, by ascending degree like `Poly`.
+type Environment = BTreeMap<String, Vec<f32>>;
+
+/// How the solutions are shown, changed with `set`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Settings {
+    pub style: Style,
+    /// Also prints the solutions rounded to this many decimal places.
+    pub precision: Option<usize>,
+    pub rounding: Rounding,
+}
+
+/// What `save` writes and `load` reads back.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Saved {
+    settings: Settings,
+    polynomials: Environment,
+    history: Vec<String>,
+}
+
+/// Why a line could not be executed.
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    /// Located in the line, which can be given back for editing.
+    Parse(Vec<Diagnostic>),
+    Message(String),
+}
+
+/// What a line entered at the prompt asks for.
+#[derive(Debug, PartialEq)]
+enum Statement<'a> {
+    /// `p := X^2 - 4`, the expression starting at the given byte offset
+    Assign(&'a str, usize),
+    Delete(&'a str),
+    Show(&'a str),
+    Solve(&'a str),
+    Eval(&'a str, &'a str),
+    /// The file format follows the extension: `.svg`, `.png` or `.gp`
+    Plot(&'a str, &'a str),
+    List,
+    Undo,
+    Redo,
+    Set(&'a str, &'a str),
+    History,
+    Save(&'a str),
+    Load(&'a str),
+    Equation,
+}
+
+fn is_name(word: &str) -> bool {
+    let mut chars = word.chars();
+    chars.next().is_some_and(|c| c.is_ascii_lowercase())
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        && !COMMANDS.contains(&word)
+}
+
+fn statement(line: &str) -> Statement<'_> {
+    if let Some((name, _)) = line.split_once(":=") {
+        return Statement::Assign(name.trim(), name.len() + 2);
+    }
+    let words: Vec<&str> = line.split_whitespace().collect();
+    match words[..] {
+        ["list"] => Statement::List,
+        ["undo"] => Statement::Undo,
+        ["redo"] => Statement::Redo,
+        ["history"] => Statement::History,
+        ["set", key, value] => Statement::Set(key, value),
+        ["save", path] => Statement::Save(path),
+        ["load", path] => Statement::Load(path),
+        ["delete", name] => Statement::Delete(name),
+        ["solve", name] => Statement::Solve(name),
+        ["eval", name, x] => Statement::Eval(name, x),
+        ["plot", name, path] => Statement::Plot(name, path),
+        [name] if is_name(name) => Statement::Show(name),
+        _ => Statement::Equation,
+    }
+}
+
+/// The state kept between the lines entered at the prompt. Each change to
+/// the named polynomials saves the previous ones, so that it can be undone.
+pub struct Session {
+    pub settings: Settings,
+    pub polynomials: Environment,
+    /// The lines executed without error.
+    pub history: Vec<String>,
+    /// Those every line is parsed within.
+    pub limits: expr::Limits,
+    undo: Vec<Environment>,
+    redo: Vec<Environment>,
+}
+
+impl Session {
+    pub fn new(settings: Settings) -> Session {
+        Session {
+            settings,
+            polynomials: Environment::new(),
+            history: vec![],
+            limits: expr::DEFAULT_LIMITS,
+            undo: vec![],
+            redo: vec![],
+        }
+    }
+
+    fn solve(&self, poly: &Poly) {
+        poly.print(self.settings.style);
+        if let Some(digits) = self.settings.precision {
+            let options = SolveOptions::new()
+                .precision(digits)
+                .rounding(self.settings.rounding);
+            poly.print_rounded(&options);
+        }
+    }
+
+    fn plot(&self, coefficients: &[f32], path: &str) -> Result<(), String> {
+        let poly = Poly::from_coefficients(coefficients.to_vec());
+        let roots: Vec<f64> = poly.real_roots().iter().map(|&r| r as f64).collect();
+        let coefficients = poly.coefficients_f64();
+        let extension = Path::new(path).extension().and_then(|e| e.to_str());
+        let written = match extension {
+            Some("svg") => export::write_svg(path, &coefficients, &roots),
+            Some("png") => export::write_png(path, &coefficients, &roots, export::DEFAULT_DPI),
+            Some("gp") => fs::write(path, export::gnuplot_script(&coefficients, &roots))
+                .map_err(|e| e.to_string()),
+            _ => return Err(format!("Unknown plot format for {}", path)),
+        };
+        written.map_err(|error| format!("Could not write {}: {}", path, error))?;
+        eprintln!("Written to {}", path);
+        Ok(())
+    }
+
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let option = format!("set {}", key);
+        let value = Some(value.to_string());
+        match key {
+            "format" => {
+                self.settings.style.format = cli::parse_choice(&option, value, &cli::FORMATS)?
+            }
+            "order" => self.settings.style.order = cli::parse_choice(&option, value, &cli::ORDERS)?,
+            "precision" if value.as_deref() == Some("off") => self.settings.precision = None,
+            "precision" => {
+                let digits = cli::parse_bounded(&option, value, cli::PRECISIONS)?;
+                self.settings.precision = Some(digits)
+            }
+            "rounding" => {
+                self.settings.rounding = cli::parse_choice(&option, value, &cli::ROUNDINGS)?
+            }
+            _ => return Err(format!("Unknown setting `{}`", key)),
+        }
+        Ok(())
+    }
+
+    fn save(&self, path: &str) -> Result<(), String> {
+        let saved = Saved {
+            settings: self.settings,
+            polynomials: self.polynomials.clone(),
+            history: self.history.clone(),
+        };
+        let json = serde_json::to_string_pretty(&saved).unwrap_or_default() + "\n";
+        fs::write(path, json).map_err(|error| format!("Could not write {}: {}", path, error))
+    }
+
+    /// Restores a saved session. Like other changes to the polynomials it
+    /// can be undone.
+    fn load(&mut self, path: &str) -> Result<(), String> {
+        let json = fs::read_to_string(path)
+            .map_err(|error| format!("Could not read {}: {}", path, error))?;
+        let saved: Saved = serde_json::from_str(&json)
+            .map_err(|error| format!("Invalid session {}: {}", path, error))?;
+        self.settings = saved.settings;
+        self.history = saved.history;
+        self.change(|polynomials| *polynomials = saved.polynomials);
+        Ok(())
+    }
+
+    fn get(&self, name: &str) -> Result<&Vec<f32>, Error> {
+        self.polynomials
+            .get(name)
+            .ok_or_else(|| Error::Message(format!("Unknown polynomial `{}`", name)))
+    }
+
+    fn show(&self, name: &str, coefficients: &[f32]) {
+        println!(
+            "{} = {}",
+            name,
+            format_polynomial(coefficients, self.settings.style)
+        );
+    }
+
+    fn change(&mut self, update: impl FnOnce(&mut Environment)) {
+        self.undo.push(self.polynomials.clone());
+        self.redo.clear();
+        update(&mut self.polynomials);
+    }
+
+    /// Runs the line, an assignment, a command or an equation to solve.
+    pub fn execute(&mut self, line: &str) -> Result<(), Error> {
+        let load = matches!(statement(line), Statement::Load(_));
+        self.run(line)?;
+        if !load {
+            self.history.push(line.to_string());
+        }
+        Ok(())
+    }
+
+    fn run(&mut self, line: &str) -> Result<(), Error> {
+        match statement(line) {
+            Statement::Assign(name, start) => {
+                if !is_name(name) {
+                    return Err(Error::Message(format!("Invalid name `{}`", name)));
+                }
+                let offset = line[..start].chars().count();
+                let expression = expr::check_expression_with(&line[start..], &self.limits)
+                    .map_err(|diagnostics| {
+                        let shifted = diagnostics.into_iter().map(|mut d| {
+                            d.span = d.span.start + offset..d.span.end + offset;
+                            d
+                        });
+                        Error::Parse(shifted.collect())
+                    })?;
+                let coefficients = expression.expand().map_err(|_| {
+                    Error::Message(String::from("The expression cannot be expanded"))
+                })?;
+                self.show(name, &coefficients);
+                self.change(|polynomials| {
+                    polynomials.insert(name.to_string(), coefficients);
+                });
+            }
+            Statement::Delete(name) => {
+                self.get(name)?;
+                self.change(|polynomials| {
+                    polynomials.remove(name);
+                });
+            }
+            Statement::Show(name) => self.show(name, self.get(name)?),
+            Statement::Solve(name) => {
+                let coefficients = self.get(name)?.clone();
+                self.solve(&Poly::from_coefficients(coefficients));
+            }
+            Statement::Eval(name, x) => {
+                let coefficients = self.get(name)?;
+                let value: f32 = x
+                    .parse()
+                    .map_err(|_| Error::Message(format!("Invalid value for eval: {}", x)))?;
+                let coefficients: Vec<f64> = coefficients.iter().map(|&c| c as f64).collect();
+                let y = numeric::eval(&coefficients, value as f64) as f32;
+                println!(
+                    "{}({}) = {}",
+                    name,
+                    float::shortest(value),
+                    float::shortest(y)
+                );
+            }
+            Statement::Plot(name, path) => {
+                let coefficients = self.get(name)?;
+                self.plot(coefficients, path).map_err(Error::Message)?;
+            }
+            Statement::List => {
+                for (name, coefficients) in &self.polynomials {
+                    self.show(name, coefficients);
+                }
+            }
+            Statement::Undo => {
+                let previous = self
+                    .undo
+                    .pop()
+                    .ok_or_else(|| Error::Message(String::from("Nothing to undo")))?;
+                self.redo
+                    .push(std::mem::replace(&mut self.polynomials, previous));
+            }
+            Statement::Redo => {
+                let next = self
+                    .redo
+                    .pop()
+                    .ok_or_else(|| Error::Message(String::from("Nothing to redo")))?;
+                self.undo
+                    .push(std::mem::replace(&mut self.polynomials, next));
+            }
+            Statement::Equation => {
+                let (left, right) =
+                    expr::check_equation_with(line, &self.limits).map_err(Error::Parse)?;
+                let coefficients = expr::Expr::Sub(Box::new(left), Box::new(right))
+                    .expand()
+                    .map_err(|error| {
+                        let end = line.chars().count();
+                        let message = String::from("the equation cannot be reduced");
+                        Error::Parse(vec![Diagnostic::new(error, message, end..end)])
+                    })?;
+                self.solve(&Poly::from_coefficients(coefficients));
+            }
+            Statement::Set(key, value) => self.set(key, value).map_err(Error::Message)?,
+            Statement::History => {
+                for line in &self.history {
+                    println!("{}", line);
+                }
+            }
+            Statement::Save(path) => self.save(path).map_err(Error::Message)?,
+            Statement::Load(path) => self.load(path).map_err(Error::Message)?,
+        }
+        Ok(())
+    }
+}
+
+/// Executes the lines of a script, skipping blank lines and `#` comments,
+/// until `quit` or the end, and stops at the first error with the line and
+/// its number.
+pub fn run_script(
+    session: &mut Session,
+    input: impl BufRead,
+) -> io::Result<Option<(usize, String, Error)>> {
+    for (i, line) in input.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        if line.trim() == "quit" {
+            break;
+        }
+        if let Err(error) = session.execute(&line) {
+            return Ok(Some((i + 1, line, error)));
+        }
+    }
+    Ok(None)
+}
+
+/// The byte offset of the first error, where the cursor goes when the line
+/// is given back for editing.
+pub fn error_cursor(line: &str, diagnostics: &[Diagnostic]) -> usize {
+    let start = diagnostics.first().map_or(0, |d| d.span.start);
+    line.char_indices()
+        .nth(start)
+        .map_or(line.len(), |(offset, _)| offset)
+}
+
+/// The suggestions for the equation or the expression of an assignment
+/// being typed, none for the other statements.
+fn completions(line: &str, cursor: usize) -> Vec<Suggestion> {
+    match statement(&line[..cursor]) {
+        Statement::Assign(_, start) => suggest(&line[start..], cursor - start)
+            .into_iter()
+            .filter(|suggestion| !suggestion.text.contains('='))
+            .collect(),
+        Statement::Equation => suggest(line, cursor),
+        _ => vec![],
+    }
+}
+
+/// Completes the line at the cursor with Tab.
+struct Completion;
+
+impl Completer for Completion {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let candidates = completions(line, pos)
+            .into_iter()
+            .map(|suggestion| Pair {
+                display: format!("{} ({})", suggestion.text.trim(), suggestion.description),
+                replacement: suggestion.text,
+            })
+            .collect();
+        Ok((pos, candidates))
+    }
+}
+
+impl Hinter for Completion {
+    type Hint = String;
+}
+
+impl Highlighter for Completion {}
+
+impl Validator for Completion {}
+
+impl Helper for Completion {}
+
+/// Reads equations until `quit` or the end of the input. After a parse
+/// error the prompt comes back with the same line, the cursor on the error.
+/// Tab completes the equation being typed.
+pub fn interactive(session: &mut Session) -> rustyline::Result<()> {
+    let mut editor = Editor::<Completion, DefaultHistory>::new()?;
+    editor.set_helper(Some(Completion));
+    let mut retry: Option<(String, usize)> = None;
+    loop {
+        let read = match &retry {
+            Some((line, cursor)) => {
+                editor.readline_with_initial(PROMPT, (&line[..*cursor], &line[*cursor..]))
+            }
+            None => editor.readline(PROMPT),
+        };
+        let line = match read {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted | ReadlineError::Eof) => return Ok(()),
+            Err(error) => return Err(error),
+        };
+        retry = None;
+        match line.trim() {
+            "" => continue,
+            "quit" => return Ok(()),
+            _ => (),
+        }
+        editor.add_history_entry(line.as_str())?;
+        match session.execute(&line) {
+            Ok(()) => (),
+            Err(Error::Parse(diagnostics)) => {
+                eprint!("{}", diagnostics::render(&line, &diagnostics));
+                eprintln!("Error parsing the polynomial equation");
+                let cursor = error_cursor(&line, &diagnostics);
+                retry = Some((line, cursor));
+            }
+            Err(Error::Message(message)) => eprintln!("{}", message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ParseError;
+
+    #[test]
+    fn parse_statements() {
+        assert_eq!(statement("p := X^2"), Statement::Assign("p", 4));
+        assert_eq!(statement(" delete  q1 "), Statement::Delete("q1"));
+        assert_eq!(statement("solve p"), Statement::Solve("p"));
+        assert_eq!(statement("p"), Statement::Show("p"));
+        assert_eq!(statement("X = 1"), Statement::Equation);
+        assert_eq!(statement("undo"), Statement::Undo);
+        assert!(!is_name("list") && !is_name("P") && !is_name("2p"));
+    }
+
+    #[test]
+    fn script_stops_at_error() {
+        let mut session = Session::new(Settings::default());
+        let script = "# roots of p\np := X^2 - 4\n\neval p 3\nsolve q\nlist\n";
+        let result = run_script(&mut session, script.as_bytes()).unwrap();
+        let Some((number, line, Error::Message(message))) = result else {
+            panic!("the script ran to the end");
+        };
+        assert_eq!((number, line.as_str()), (5, "solve q"));
+        assert_eq!(message, "Unknown polynomial `q`");
+        assert_eq!(session.history, ["p := X^2 - 4", "eval p 3"]);
+        assert!(session.execute("plot p out.pdf").is_err());
+        assert!(session.execute("eval p two").is_err());
+        let mut session = Session::new(Settings::default());
+        let script = "p := X\n quit \nsolve q\n";
+        assert!(run_script(&mut session, script.as_bytes())
+            .unwrap()
+            .is_none());
+        assert_eq!(session.history, ["p := X"]);
+    }
+
+    #[test]
+    fn save_and_load_session() {
+        let path =
+            std::env::temp_dir().join(format!("computor-session-{}.json", std::process::id()));
+        let path = path.to_str().unwrap();
+        let mut session = Session::new(Settings::default());
+        session.execute("p := X^2 - 2").unwrap();
+        session.execute("set precision 3").unwrap();
+        session.execute("set format pretty").unwrap();
+        session.execute(&format!("save {}", path)).unwrap();
+        let mut restored = Session::new(Settings::default());
+        restored.execute(&format!("load {}", path)).unwrap();
+        fs::remove_file(path).ok();
+        assert_eq!(restored.settings, session.settings);
+        assert_eq!(restored.polynomials, session.polynomials);
+        // saved before the `save` line itself was added
+        assert_eq!(restored.history, session.history[..3]);
+        restored.execute("undo").unwrap();
+        assert!(restored.polynomials.is_empty());
+        assert!(restored.execute("set precision many").is_err());
+        assert!(restored.execute("load /nonexistent/session.json").is_err());
+    }
+
+    #[test]
+    fn undo_and_redo_changes() {
+        let mut session = Session::new(Settings::default());
+        session.execute("p := X^2 - 4").unwrap();
+        session.execute("p := (X - 1)^2").unwrap();
+        session.execute("q := X").unwrap();
+        session.execute("delete p").unwrap();
+        assert_eq!(session.polynomials.len(), 1);
+        session.execute("undo").unwrap();
+        session.execute("undo").unwrap();
+        assert_eq!(session.polynomials["p"], vec![1.0, -2.0, 1.0]);
+        session.execute("undo").unwrap();
+        assert_eq!(session.polynomials["p"], vec![-4.0, 0.0, 1.0]);
+        session.execute("redo").unwrap();
+        assert_eq!(session.polynomials["p"], vec![1.0, -2.0, 1.0]);
+        session.execute("p := X").unwrap();
+        assert!(session.execute("redo").is_err());
+        assert!(session.execute("solve r").is_err());
+        assert!(session.execute("list := X").is_err());
+    }
+
+    #[test]
+    fn cursor_on_first_error() {
+        let mut session = Session::new(Settings::default());
+        let line = "X² + 3 = 0";
+        let Err(Error::Parse(diagnostics)) = session.execute(line) else {
+            panic!("{} parsed", line);
+        };
+        assert_eq!(error_cursor(line, &diagnostics), 1);
+        let line = "p := X^2 + * 3";
+        let Err(Error::Parse(diagnostics)) = session.execute(line) else {
+            panic!("{} parsed", line);
+        };
+        assert_eq!(&line[error_cursor(line, &diagnostics)..], "* 3");
+        assert!(session.execute("X^2 = 4").is_ok());
+        assert_eq!(error_cursor("X", &[]), 0);
+        let line = "X² + 3x = 0";
+        let diagnostic = Diagnostic::new(ParseError::SyntaxError, String::new(), 6..7);
+        assert_eq!(&line[error_cursor(line, &[diagnostic])..], "x = 0");
+    }
+
+    #[test]
+    fn tab_completions() {
+        let texts = |line: &str| -> Vec<String> {
+            completions(line, line.len())
+                .into_iter()
+                .map(|suggestion| suggestion.text)
+                .collect()
+        };
+        assert_eq!(texts("3 * X^0 + 2"), [" * X^1", " = 0"]);
+        assert_eq!(texts("p := 3 * X^0 + 2"), [" * X^1"]);
+        assert_eq!(texts("p := "), ["X^0", "1 * X^0"]);
+        assert!(texts("solve p").is_empty());
+        assert!(texts("eval p 2").is_empty());
+    }
+}
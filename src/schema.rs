@@ -0,0 +1,283 @@
+use serde_json::{Map, Value};
+
+/// The contract of every JSON document printed, versioned by its `$id`.
+pub const SCHEMA: &str = include_str!("schema.json");
+
+/// Whether `text` matches a pattern of the schema, made of literal
+/// characters and `[...]` classes of characters and ranges, each optionally
+/// repeated `{n}` times, between `^` and `$`. That is all the schema uses.
+fn matches_pattern(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern
+        .trim_start_matches('^')
+        .trim_end_matches('$')
+        .chars()
+        .collect();
+    let mut text = text.chars();
+    let mut i = 0;
+    while i < pattern.len() {
+        let mut class = vec![];
+        if pattern[i] == '[' {
+            i += 1;
+            while i < pattern.len() && pattern[i] != ']' {
+                match pattern.get(i + 1..i + 3) {
+                    Some(&['-', end]) if end != ']' => {
+                        class.push((pattern[i], end));
+                        i += 3;
+                    }
+                    _ => {
+                        class.push((pattern[i], pattern[i]));
+                        i += 1;
+                    }
+                }
+            }
+        } else {
+            class.push((pattern[i], pattern[i]));
+        }
+        i += 1;
+        let mut count = 1;
+        if pattern.get(i) == Some(&'{') {
+            let end = (i..pattern.len())
+                .find(|&j| pattern[j] == '}')
+                .unwrap_or(pattern.len());
+            count = pattern[i + 1..end]
+                .iter()
+                .collect::<String>()
+                .parse()
+                .unwrap_or(1);
+            i = end + 1;
+        }
+        for _ in 0..count {
+            match text.next() {
+                Some(c) if class.iter().any(|&(low, high)| (low..=high).contains(&c)) => {}
+                _ => return false,
+            }
+        }
+    }
+    text.next().is_none()
+}
+
+fn has_type(value: &Value, name: &str) -> bool {
+    match name {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        _ => false,
+    }
+}
+
+/// The first way `value` breaks `schema`, at the JSON path `at`. Only the
+/// keywords the schema uses are checked.
+fn violation(value: &Value, schema: &Value, root: &Value, at: &str) -> Option<String> {
+    let schema = schema.as_object()?;
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        let name = reference.trim_start_matches("#/$defs/");
+        return violation(value, &root["$defs"][name], root, at);
+    }
+    if let Some(choices) = schema.get("oneOf").and_then(Value::as_array) {
+        let matching = choices
+            .iter()
+            .filter(|choice| violation(value, choice, root, at).is_none())
+            .count();
+        if matching != 1 {
+            return Some(format!(
+                "{}: matches {} of the alternatives, not 1",
+                at, matching
+            ));
+        }
+    }
+    let types = match schema.get("type") {
+        Some(Value::String(name)) => vec![name.as_str()],
+        Some(Value::Array(names)) => names.iter().filter_map(Value::as_str).collect(),
+        _ => vec![],
+    };
+    if !types.is_empty() && !types.iter().any(|name| has_type(value, name)) {
+        return Some(format!(
+            "{}: {} is not of type {}",
+            at,
+            value,
+            types.join(" or ")
+        ));
+    }
+    if let Some(expected) = schema.get("const") {
+        if value != expected {
+            return Some(format!("{}: {} instead of {}", at, value, expected));
+        }
+    }
+    if let Some(Value::Array(values)) = schema.get("enum") {
+        if !values.contains(value) {
+            return Some(format!(
+                "{}: {} is not one of the values allowed",
+                at, value
+            ));
+        }
+    }
+    if let (Some(minimum), Some(number)) = (
+        schema.get("minimum").and_then(Value::as_f64),
+        value.as_f64(),
+    ) {
+        if number < minimum {
+            return Some(format!("{}: {} is below {}", at, number, minimum));
+        }
+    }
+    if let (Some(pattern), Some(text)) = (
+        schema.get("pattern").and_then(Value::as_str),
+        value.as_str(),
+    ) {
+        if !matches_pattern(pattern, text) {
+            return Some(format!("{}: {:?} does not match {}", at, text, pattern));
+        }
+    }
+    if let (Some(items), Some(values)) = (schema.get("items"), value.as_array()) {
+        for (i, item) in values.iter().enumerate() {
+            if let Some(violation) = violation(item, items, root, &format!("{}[{}]", at, i)) {
+                return Some(violation);
+            }
+        }
+    }
+    if let Some(object) = value.as_object() {
+        return object_violation(object, schema, root, at);
+    }
+    None
+}
+
+fn object_violation(
+    object: &Map<String, Value>,
+    schema: &Map<String, Value>,
+    root: &Value,
+    at: &str,
+) -> Option<String> {
+    let properties = schema.get("properties").and_then(Value::as_object);
+    for required in schema
+        .get("required")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let required = required.as_str().unwrap_or_default();
+        if !object.contains_key(required) {
+            return Some(format!("{}: missing {}", at, required));
+        }
+    }
+    for (key, value) in object {
+        match properties.and_then(|properties| properties.get(key)) {
+            Some(property) => {
+                if let Some(violation) =
+                    violation(value, property, root, &format!("{}.{}", at, key))
+                {
+                    return Some(violation);
+                }
+            }
+            None if schema.get("additionalProperties") == Some(&Value::Bool(false)) => {
+                return Some(format!("{}: unexpected {}", at, key));
+            }
+            None => {}
+        }
+    }
+    None
+}
+
+/// Checks one document against `SCHEMA`.
+pub fn check(document: &Value) -> Result<(), String> {
+    let schema: Value = serde_json::from_str(SCHEMA).map_err(|error| error.to_string())?;
+    match violation(document, &schema, &schema, "$") {
+        Some(violation) => Err(violation),
+        None => Ok(()),
+    }
+}
+
+/// Checks a saved output: one pretty-printed document, or one document per
+/// line as in batch mode. Returns the errors by line number, counting from
+/// 1, the whole document being line 1.
+pub fn check_all(text: &str) -> Vec<(usize, String)> {
+    if let Ok(document) = serde_json::from_str::<Value>(text) {
+        return check(&document)
+            .err()
+            .map(|error| (1, error))
+            .into_iter()
+            .collect();
+    }
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .filter_map(|(i, line)| {
+            let result = serde_json::from_str(line)
+                .map_err(|error| format!("not JSON: {}", error))
+                .and_then(|document| check(&document));
+            result.err().map(|error| (i + 1, error))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostics;
+    use crate::expr;
+    use crate::Poly;
+
+    fn conforms(json: &str) -> Result<(), String> {
+        check(&serde_json::from_str(json).unwrap())
+    }
+
+    #[test]
+    fn schema_is_versioned() {
+        let schema: Value = serde_json::from_str(SCHEMA).unwrap();
+        assert_eq!(schema["version"], 1);
+        assert_eq!(schema["$id"], "urn:computor_v1:schema:1");
+    }
+
+    #[test]
+    fn patterns() {
+        assert!(matches_pattern("^W[0-9]{3}$", "W004"));
+        assert!(!matches_pattern("^W[0-9]{3}$", "W04"));
+        assert!(!matches_pattern("^W[0-9]{3}$", "W0041"));
+        assert!(matches_pattern("^[0-9a-f]{16}$", "d32b34c50004f1d1"));
+        assert!(!matches_pattern("^[0-9a-f]{16}$", "D32B34C50004F1D1"));
+    }
+
+    #[test]
+    fn outputs_conform() {
+        for line in [
+            "X^2 = 4",
+            "0 = 0",
+            "5 = 0",
+            "X^3 + X = 1",
+            "100000000000000000000000 * X^2 = 1",
+        ] {
+            let report = Poly::new(line).unwrap().report(Default::default());
+            assert_eq!(conforms(&report.to_json()), Ok(()), "{}", line);
+            assert_eq!(conforms(&report.to_json_line()), Ok(()), "{}", line);
+        }
+        let errors = expr::check_equation("X^2 + = 4").unwrap_err();
+        assert_eq!(conforms(&diagnostics::to_json(&errors)), Ok(()));
+        assert_eq!(conforms(r#"{"truncated":true}"#), Ok(()));
+    }
+
+    #[test]
+    fn violations_are_located() {
+        let report = Poly::new("X = 1").unwrap().report(Default::default());
+        let mut json: Value = serde_json::from_str(&report.to_json()).unwrap();
+        json["roots"] = serde_json::json!([{"value": 1.0, "residual": 0.0}]);
+        assert_eq!(
+            check(&json),
+            Err(String::from("$: matches 0 of the alternatives, not 1"))
+        );
+        let schema: Value = serde_json::from_str(SCHEMA).unwrap();
+        assert_eq!(
+            violation(&json, &schema["$defs"]["report"], &schema, "$"),
+            Some(String::from("$.roots[0]: missing exact"))
+        );
+        assert!(conforms(r#"{"truncated":false}"#).is_err());
+        assert_eq!(
+            check_all("{\"truncated\":true}\n\n{\"errors\":[]}\nnot json\n")
+                .iter()
+                .map(|(line, _)| *line)
+                .collect::<Vec<usize>>(),
+            [4]
+        );
+    }
+}
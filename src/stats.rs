@@ -0,0 +1,96 @@
+use crate::method::{self, Method};
+use crate::{ParseError, Poly};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+
+/// Counts over every equation solved with `--stats file.json`, added to
+/// those already in the file, to see what a dataset is made of. The file
+/// stays local, nothing is sent anywhere.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Stats {
+    /// By degree, `undefined` for `0 = 0`.
+    pub degrees: BTreeMap<String, u64>,
+    /// By error code, for the equations that could not be parsed.
+    pub errors: BTreeMap<String, u64>,
+    /// By the name of the solver used.
+    pub methods: BTreeMap<String, u64>,
+}
+
+impl Stats {
+    /// The counts recorded so far, none when the file does not exist yet.
+    pub fn load(path: &str) -> Result<Stats, String> {
+        match fs::read_to_string(path) {
+            Ok(json) => serde_json::from_str(&json)
+                .map_err(|error| format!("Invalid statistics {}: {}", path, error)),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Stats::default()),
+            Err(error) => Err(format!("Could not read {}: {}", path, error)),
+        }
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).unwrap_or_default() + "\n";
+        fs::write(path, json).map_err(|error| format!("Could not write {}: {}", path, error))
+    }
+
+    /// Counts the degree of the equation and the solver `method` resolves
+    /// to, `auto` being counted as the solver it routes to.
+    pub fn record(&mut self, poly: &Poly, method: Method) {
+        let degree = match poly.degree() {
+            Some(degree) => degree.to_string(),
+            None => String::from("undefined"),
+        };
+        *self.degrees.entry(degree).or_default() += 1;
+        let method = match method {
+            Method::Auto => method::choose(&poly.coefficients_f64()).0,
+            method => method,
+        };
+        *self.methods.entry(method.name().to_string()).or_default() += 1;
+    }
+
+    pub fn record_error(&mut self, error: &ParseError) {
+        *self.errors.entry(error.code().to_string()).or_default() += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn counts_are_added_to_the_file() {
+        let path = env::temp_dir().join(format!("computor-stats-{}.json", std::process::id()));
+        let path = path.to_str().unwrap();
+        fs::remove_file(path).ok();
+        let mut stats = Stats::load(path).unwrap();
+        assert_eq!(stats, Stats::default());
+        stats.record(&Poly::new("X^2 = 4").unwrap(), Method::Auto);
+        stats.record(&Poly::new("X^3 = 8").unwrap(), Method::DurandKerner);
+        stats.record(&Poly::new("X = X").unwrap(), Method::Auto);
+        if let Err(error) = Poly::new("X / 0 = 1") {
+            stats.record_error(&error);
+        }
+        stats.save(path).unwrap();
+        let mut stats = Stats::load(path).unwrap();
+        stats.record(&Poly::new("X^2 + 1 = 0").unwrap(), Method::Auto);
+        fs::remove_file(path).unwrap();
+        assert_eq!(stats.degrees["2"], 2);
+        assert_eq!(stats.degrees["3"], 1);
+        assert_eq!(stats.degrees["undefined"], 1);
+        assert_eq!(stats.methods["closed-form"], 3);
+        assert_eq!(stats.methods["durand-kerner"], 1);
+        assert_eq!(stats.errors.len(), 1);
+    }
+
+    #[test]
+    fn invalid_file_is_reported() {
+        let path = env::temp_dir().join(format!("computor-bad-stats-{}.json", std::process::id()));
+        let path = path.to_str().unwrap();
+        fs::write(path, "not json").unwrap();
+        let loaded = Stats::load(path);
+        fs::remove_file(path).unwrap();
+        assert!(loaded.unwrap_err().starts_with("Invalid statistics"));
+    }
+}
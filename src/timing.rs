@@ -0,0 +1,111 @@
+use crate::event::Event;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Wall-clock time spent in each stage, in the order they ran.
+#[derive(Debug, Default)]
+pub struct Timings {
+    stages: Vec<(String, Duration)>,
+}
+
+impl Timings {
+    pub fn measure<T>(&mut self, stage: &str, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = f();
+        self.stages.push((stage.to_string(), start.elapsed()));
+        result
+    }
+
+    pub fn record(&mut self, stage: &str, duration: Duration) {
+        self.stages.push((stage.to_string(), duration));
+    }
+
+    pub fn report(&self) -> String {
+        let mut output = String::from("Time spent:\n");
+        for (stage, duration) in &self.stages {
+            output += &format!("  {}: {} µs\n", stage, duration.as_micros());
+        }
+        output
+    }
+}
+
+/// Times the stages of a solve from its events: the parsing ends with
+/// `Parsed`, the reduction with the last `Reduced` and the solving when the
+/// report is back.
+#[derive(Debug)]
+pub struct Clock {
+    start: Instant,
+    marks: Mutex<Vec<(&'static str, Instant)>>,
+}
+
+impl Clock {
+    pub fn new() -> Clock {
+        Clock {
+            start: Instant::now(),
+            marks: Mutex::new(vec![]),
+        }
+    }
+
+    pub fn observe(&self, event: &Event) {
+        let stage = match event {
+            Event::Parsed { .. } => "parsing",
+            Event::Reduced { .. } => "reduction",
+            _ => return,
+        };
+        let mut marks = self.marks.lock().unwrap();
+        if marks.last().is_some_and(|&(last, _)| last == stage) {
+            marks.pop();
+        }
+        marks.push((stage, Instant::now()));
+    }
+
+    /// Ends the solving, adding each stage seen to `timings`.
+    pub fn finish(self, timings: &mut Timings) {
+        let end = Instant::now();
+        let marks = self.marks.into_inner().unwrap();
+        let mut previous = self.start;
+        for (stage, instant) in marks.into_iter().chain([("solving", end)]) {
+            timings.record(stage, instant - previous);
+            previous = instant;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stages_in_order() {
+        let mut timings = Timings::default();
+        assert_eq!(timings.measure("first", || 1 + 1), 2);
+        timings.measure("second", || ());
+        let report = timings.report();
+        assert!(report.starts_with("Time spent:\n  first: "));
+        assert!(report.contains("\n  second: "));
+        assert!(report.ends_with(" µs\n"));
+    }
+
+    #[test]
+    fn stages_from_events() {
+        let clock = Clock::new();
+        clock.observe(&Event::Parsed {
+            equation: String::from("X = 1"),
+        });
+        clock.observe(&Event::Reduced {
+            coefficients: vec![-1.0, 1.0],
+        });
+        clock.observe(&Event::Iteration { iterations: 100 });
+        clock.observe(&Event::Reduced {
+            coefficients: vec![1.0, -1.0],
+        });
+        let mut timings = Timings::default();
+        clock.finish(&mut timings);
+        let stages: Vec<&str> = timings
+            .stages
+            .iter()
+            .map(|(stage, _)| stage.as_str())
+            .collect();
+        assert_eq!(stages, ["parsing", "reduction", "solving"]);
+    }
+}
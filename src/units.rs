@@ -0,0 +1,292 @@
+use crate::expr::{self, Limits};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// A product of base units with integer exponents, like `m/s^2`. Empty is
+/// dimensionless.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Dimension(BTreeMap<String, i32>);
+
+impl Dimension {
+    /// Factors separated by `*`, `·` or spaces, each `name` or `name^n`;
+    /// those after a `/` divide. `1` is dimensionless.
+    pub fn parse(text: &str) -> Result<Dimension, String> {
+        let invalid = || format!("Invalid unit: {}", text);
+        let (numerator, denominator) = match text.split_once('/') {
+            Some((numerator, denominator)) => (numerator, denominator),
+            None => (text, ""),
+        };
+        if denominator.contains('/') {
+            return Err(invalid());
+        }
+        let mut dimension = Dimension::default();
+        for (part, sign) in [(numerator, 1), (denominator, -1)] {
+            for factor in part.split(['*', '·', ' ']).filter(|f| !f.is_empty()) {
+                let (name, exponent) = match factor.split_once('^') {
+                    Some((name, exponent)) => (name, exponent.parse().map_err(|_| invalid())?),
+                    None => (factor, 1),
+                };
+                if name == "1" {
+                    continue;
+                }
+                if name.is_empty() || !name.chars().all(char::is_alphabetic) {
+                    return Err(invalid());
+                }
+                dimension.multiply(name, sign * exponent);
+            }
+        }
+        Ok(dimension)
+    }
+
+    fn multiply(&mut self, name: &str, exponent: i32) {
+        let total = self.0.entry(name.to_string()).or_default();
+        *total += exponent;
+        if *total == 0 {
+            self.0.remove(name);
+        }
+    }
+
+    pub fn times(&self, other: &Dimension) -> Dimension {
+        let mut product = self.clone();
+        for (name, &exponent) in &other.0 {
+            product.multiply(name, exponent);
+        }
+        product
+    }
+
+    pub fn pow(&self, n: i32) -> Dimension {
+        Dimension(
+            self.0
+                .iter()
+                .map(|(name, e)| (name.clone(), e * n))
+                .collect(),
+        )
+    }
+
+    pub fn is_dimensionless(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The dimension whose `n`th power this is, if the exponents divide.
+    fn root(&self, n: i32) -> Option<Dimension> {
+        self.0
+            .iter()
+            .map(|(name, &e)| (e % n == 0).then(|| (name.clone(), e / n)))
+            .collect::<Option<_>>()
+            .map(Dimension)
+    }
+}
+
+/// `kg*m/s^2`, or `1` when dimensionless.
+impl fmt::Display for Dimension {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let factor = |(name, e): (&String, i32)| match e {
+            1 => name.clone(),
+            e => format!("{}^{}", name, e),
+        };
+        let numerator: Vec<String> = self
+            .0
+            .iter()
+            .filter(|(_, &e)| e > 0)
+            .map(|(name, &e)| factor((name, e)))
+            .collect();
+        let denominator: Vec<String> = self
+            .0
+            .iter()
+            .filter(|(_, &e)| e < 0)
+            .map(|(name, &e)| factor((name, -e)))
+            .collect();
+        match (numerator.is_empty(), denominator.is_empty()) {
+            (true, true) => write!(f, "1"),
+            (_, true) => write!(f, "{}", numerator.join("*")),
+            (true, _) => write!(f, "1/{}", denominator.join("*")),
+            _ => write!(f, "{}/{}", numerator.join("*"), denominator.join("*")),
+        }
+    }
+}
+
+/// An equation with its unit annotations taken out, and the unit of `X`
+/// they imply when the terms tell it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Units {
+    pub equation: String,
+    pub unknown: Option<Dimension>,
+}
+
+/// The terms of one side, split at the `+` and `-` outside parentheses that
+/// are not signs, e.g. after `*` or `^`.
+fn terms(side: &str) -> Vec<std::ops::Range<usize>> {
+    let mut terms = vec![];
+    let (mut start, mut depth, mut previous) = (0, 0, None);
+    for (i, c) in side.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            '+' | '-' if depth == 0 => {
+                let sign = matches!(previous, None | Some('*' | '/' | '^' | '(' | '+' | '-'));
+                if !sign {
+                    terms.push(start..i);
+                    start = i;
+                }
+            }
+            _ => {}
+        }
+        if !c.is_whitespace() {
+            previous = Some(c);
+        }
+    }
+    terms.push(start..side.len());
+    terms
+}
+
+/// Takes the `[unit]` annotations out of the equation, e.g.
+/// `9.81[m/s^2] * X^2 = 100[m]`, and checks that every term has the same
+/// dimension once `X` is given the unit the terms of different degrees
+/// imply. A term without annotation is dimensionless. `None` when nothing
+/// is annotated. The units of an equation that does not parse are left
+/// unchecked, its errors being reported when it is solved. The equation is
+/// parsed within `limits`.
+pub fn annotate(line: &str, limits: &Limits) -> Result<Option<Units>, String> {
+    if !line.contains('[') {
+        return Ok(None);
+    }
+    // the annotations by their offset in the equation without them
+    let mut equation = String::new();
+    let mut annotations = vec![];
+    let mut rest = line;
+    while let Some(open) = rest.find('[') {
+        equation.push_str(&rest[..open]);
+        let close = rest[open..]
+            .find(']')
+            .ok_or_else(|| String::from("Unclosed unit annotation"))?;
+        annotations.push((
+            equation.len(),
+            Dimension::parse(&rest[open + 1..open + close])?,
+        ));
+        rest = &rest[open + close + 1..];
+    }
+    equation.push_str(rest);
+    if equation.contains(']') {
+        return Err(String::from("Unopened unit annotation"));
+    }
+    if expr::check_equation_with(&equation, limits).is_err() {
+        return Ok(Some(Units {
+            equation,
+            unknown: None,
+        }));
+    }
+    // each power of X with its dimension, term by term
+    let mut powers = vec![];
+    let middle = equation.find('=').unwrap_or(equation.len());
+    for (offset, side) in [
+        (0, &equation[..middle]),
+        (middle + 1, equation.get(middle + 1..).unwrap_or("")),
+    ] {
+        for range in terms(side) {
+            let text = side[range.clone()].trim().trim_start_matches('+');
+            let coefficients = expr::check_expression_with(text, limits)
+                .ok()
+                .and_then(|term| term.expand().ok())
+                .ok_or_else(|| format!("Cannot tell the unit of {}", text))?;
+            let dimension = annotations
+                .iter()
+                .filter(|(at, _)| *at > offset + range.start && *at <= offset + range.end)
+                .fold(Dimension::default(), |product, (_, unit)| {
+                    product.times(unit)
+                });
+            for (degree, &c) in coefficients.iter().enumerate() {
+                if c != 0.0 {
+                    powers.push((text.to_string(), degree as i32, dimension.clone()));
+                }
+            }
+        }
+    }
+    let unknown = powers.iter().find_map(|(_, k, u)| {
+        let (_, first_k, first_u) = &powers[0];
+        (k != first_k).then(|| (first_u.times(&u.pow(-1)), k - first_k))
+    });
+    let unknown =
+        match unknown {
+            Some((ratio, n)) => Some(ratio.root(n).ok_or_else(|| {
+                format!("Inconsistent units: no unit of X makes {} a power", ratio)
+            })?),
+            None => None,
+        };
+    let in_terms = |k: i32, u: &Dimension| match &unknown {
+        Some(x) => u.times(&x.pow(k)),
+        None => u.clone(),
+    };
+    if let Some((_, k, u)) = powers.first() {
+        let expected = in_terms(*k, u);
+        for (text, k, u) in &powers {
+            if in_terms(*k, u) != expected {
+                return Err(format!(
+                    "Inconsistent units: {} is in {}, the first term in {}",
+                    text,
+                    in_terms(*k, u),
+                    expected
+                ));
+            }
+        }
+    }
+    Ok(Some(Units { equation, unknown }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr::DEFAULT_LIMITS;
+
+    #[test]
+    fn dimension_algebra() {
+        let acceleration = Dimension::parse("m/s^2").unwrap();
+        assert_eq!(acceleration.to_string(), "m/s^2");
+        let force = Dimension::parse("kg").unwrap().times(&acceleration);
+        assert_eq!(force.to_string(), "kg*m/s^2");
+        assert_eq!(Dimension::parse("m s^-1").unwrap().to_string(), "m/s");
+        assert_eq!(Dimension::parse("1/s").unwrap().to_string(), "1/s");
+        assert_eq!(acceleration.times(&acceleration.pow(-1)).to_string(), "1");
+        assert!(Dimension::parse("m/s/s").is_err());
+        assert!(Dimension::parse("m^x").is_err());
+    }
+
+    #[test]
+    fn unit_of_x_from_the_terms() {
+        let units = annotate(
+            "4.9[m/s^2] * X^2 + 2[m/s] * X - 100[m] = 0",
+            &DEFAULT_LIMITS,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(units.equation, "4.9 * X^2 + 2 * X - 100 = 0");
+        assert_eq!(units.unknown.unwrap().to_string(), "s");
+        // the same unit on both sides leaves X undetermined
+        let units = annotate("3[kg] * X = 6[kg] * X^1", &DEFAULT_LIMITS)
+            .unwrap()
+            .unwrap();
+        assert_eq!(units.unknown, None);
+        assert_eq!(annotate("X^2 = 4", &DEFAULT_LIMITS).unwrap(), None);
+        let units = annotate("2[m] * (X + 1) = 4[m]", &DEFAULT_LIMITS)
+            .unwrap()
+            .unwrap();
+        assert!(units.unknown.unwrap().is_dimensionless());
+        // left to the parser, which tells what is wrong
+        let units = annotate("2[m] * X + = 4[m]", &DEFAULT_LIMITS)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            (units.equation.as_str(), units.unknown),
+            ("2 * X + = 4", None)
+        );
+    }
+
+    #[test]
+    fn inconsistent_units() {
+        assert_eq!(
+            annotate("2[m] * X^2 + 3[s] * X = 1[m]", &DEFAULT_LIMITS).unwrap_err(),
+            "Inconsistent units: 1 is in m, the first term in s^2/m"
+        );
+        assert!(annotate("2[m] * X^2 = 1[s]", &DEFAULT_LIMITS).is_err());
+        assert!(annotate("2[m * X = 1", &DEFAULT_LIMITS).is_err());
+    }
+}
@@ -0,0 +1,144 @@
+use crate::float;
+use crate::report::Report;
+use crate::Poly;
+
+use serde_json::Value;
+
+/// The saved results, either the lines of `batch --output json` or a JSON
+/// array of the same records.
+fn records(expected: &str) -> Result<Vec<Value>, String> {
+    if let Ok(Value::Array(records)) = serde_json::from_str(expected) {
+        return Ok(records);
+    }
+    expected
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line)
+                .map_err(|error| format!("Invalid record on line {}: {}", i + 1, error))
+        })
+        .collect()
+}
+
+fn close(a: f32, b: f32, tolerance: f32) -> bool {
+    (a - b).abs() <= tolerance * b.abs().max(1.0)
+}
+
+fn list(solutions: &[f32]) -> String {
+    let solutions: Vec<String> = solutions.iter().map(|&s| float::shortest(s)).collect();
+    format!("[{}]", solutions.join(", "))
+}
+
+/// What differs between the new report and the saved record: the degree,
+/// whether every real is a solution, and the solutions in any order, each
+/// within the tolerance relative to its magnitude.
+fn differences(report: &Report, expected: &Value, tolerance: f32) -> Vec<String> {
+    let mut differences = vec![];
+    if expected["degree"].as_u64() != Some(report.degree as u64) {
+        differences.push(format!(
+            "degree {} instead of {}",
+            report.degree, expected["degree"]
+        ));
+    }
+    if expected["every_real_is_solution"].as_bool() != Some(report.every_real_is_solution) {
+        differences.push(format!(
+            "every_real_is_solution {} instead of {}",
+            report.every_real_is_solution, expected["every_real_is_solution"]
+        ));
+    }
+    let mut saved: Vec<f32> = expected["solutions"]
+        .as_array()
+        .map(|solutions| {
+            solutions
+                .iter()
+                .filter_map(|s| s.as_f64().map(|s| s as f32))
+                .collect()
+        })
+        .unwrap_or_default();
+    let mut solutions = report.solutions.clone();
+    saved.sort_by(f32::total_cmp);
+    solutions.sort_by(f32::total_cmp);
+    let same = saved.len() == solutions.len()
+        && saved
+            .iter()
+            .zip(&solutions)
+            .all(|(&a, &b)| close(b, a, tolerance));
+    if !same {
+        differences.push(format!(
+            "solutions {} instead of {}",
+            list(&solutions),
+            list(&saved)
+        ));
+    }
+    differences
+}
+
+/// Solves each line of the input again and compares it with the record in
+/// the same position, skipping blank lines and lines that do not parse like
+/// `batch` does. Returns the mismatches by line number, counting from 1.
+pub fn verify(input: &str, expected: &str, tolerance: f32) -> Result<Vec<(usize, String)>, String> {
+    let records = records(expected)?;
+    let mut records = records.iter();
+    let mut mismatches = vec![];
+    for (i, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Ok(poly) = Poly::new(line) else {
+            continue;
+        };
+        let Some(expected) = records.next() else {
+            mismatches.push((i + 1, String::from("no saved result left")));
+            continue;
+        };
+        let differences = differences(&poly.report(Default::default()), expected, tolerance);
+        if !differences.is_empty() {
+            mismatches.push((i + 1, differences.join(", ")));
+        }
+    }
+    let left = records.count();
+    if left > 0 {
+        mismatches.push((
+            input.lines().count(),
+            format!("{} saved results left over", left),
+        ));
+    }
+    Ok(mismatches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mismatches_by_line() {
+        let input = "X^2 = 4\n\nX = 1\nnot an equation\n2 * X = 1\n";
+        let expected = concat!(
+            r#"{"degree":2,"every_real_is_solution":false,"solutions":[-2.0,2.0000001]}"#,
+            "\n",
+            r#"{"degree":1,"every_real_is_solution":false,"solutions":[1.5]}"#,
+            "\n",
+            r#"{"degree":1,"every_real_is_solution":false,"solutions":[0.5]}"#,
+        );
+        assert_eq!(
+            verify(input, expected, 1e-6).unwrap(),
+            [(3, String::from("solutions [1] instead of [1.5]"))]
+        );
+        let array = format!("[{}]", expected.replace('\n', ","));
+        assert_eq!(verify(input, &array, 1e-6).unwrap().len(), 1);
+        assert_eq!(
+            verify(
+                "X = 1\n",
+                r#"{"degree":2,"every_real_is_solution":false,"solutions":[2.0,-2.0]}"#,
+                1e-6
+            )
+            .unwrap(),
+            [(
+                1,
+                String::from("degree 1 instead of 2, solutions [1] instead of [-2, 2]")
+            )]
+        );
+        assert!(verify(input, "{", 1e-6).is_err());
+    }
+}
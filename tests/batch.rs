@@ -0,0 +1,48 @@
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Command, Stdio};
+use std::thread;
+
+const LINES: usize = 30_000;
+/// Padding makes the input much larger than the memory the batch may use.
+const PADDING: usize = 1000;
+const MAX_MEMORY_KB: usize = 16_000;
+
+/// Peak resident memory of the process, from /proc.
+fn peak_memory_kb(pid: u32) -> Option<usize> {
+    let status = std::fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmHWM:"))?;
+    line.split_whitespace().nth(1)?.parse().ok()
+}
+
+#[test]
+fn batch_streams_large_input_in_bounded_memory() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_computor_v1"))
+        .args(["batch", "-", "--jobs", "4"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdin = child.stdin.take().unwrap();
+    let stdout = child.stdout.take().unwrap();
+    let reader = thread::spawn(move || {
+        let mut lines = 0;
+        for line in BufReader::new(stdout).lines() {
+            assert_eq!(line.unwrap(), "X^2 - 4 = 0: -2, 2");
+            lines += 1;
+        }
+        lines
+    });
+    let line = format!("X^2 - 4 = 0{}\n", " ".repeat(PADDING));
+    for _ in 0..LINES {
+        stdin.write_all(line.as_bytes()).unwrap();
+    }
+    stdin.flush().unwrap();
+    // still running since stdin is open, after reading about 30 MB
+    let peak = peak_memory_kb(child.id());
+    drop(stdin);
+    assert!(child.wait().unwrap().success());
+    assert_eq!(reader.join().unwrap(), LINES);
+    if let Some(peak) = peak {
+        assert!(peak < MAX_MEMORY_KB, "peak memory {} kB", peak);
+    }
+}
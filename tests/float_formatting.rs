@@ -0,0 +1,291 @@
+//! Floats are printed through `computor_parse::float`, whose text is the
+//! same on every platform, rather than with the standard formatting. This
+//! scans the sources for the forms a float is formatted with directly, the
+//! floats being told from the `: f32` and `: f64` declarations of the same
+//! function, so that a new `{}` of a float fails here rather than in review.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+/// The directories scanned, each file up to its `#[cfg(test)]`.
+const SOURCES: [&str; 4] = ["src", "core/src", "parse/src", "macros/src"];
+
+/// The formatter itself.
+const FORMATTER: &str = "parse/src/float.rs";
+
+const MACROS: [&str; 7] = [
+    "format!(",
+    "print!(",
+    "println!(",
+    "eprint!(",
+    "eprintln!(",
+    "write!(",
+    "writeln!(",
+];
+
+fn is_identifier(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// The names declared as `name: f32` or `name: f64`, references included.
+fn floats(source: &str) -> HashSet<String> {
+    let mut names = HashSet::new();
+    for ty in [": f32", ": f64", ": &f32", ": &f64"] {
+        for (at, _) in source.match_indices(ty) {
+            let end = &source[at + ty.len()..];
+            if end.starts_with(is_identifier) {
+                continue;
+            }
+            let name: String = source[..at]
+                .chars()
+                .rev()
+                .take_while(|&c| is_identifier(c))
+                .collect::<Vec<char>>()
+                .into_iter()
+                .rev()
+                .collect();
+            if !name.is_empty() {
+                names.insert(name);
+            }
+        }
+    }
+    names
+}
+
+/// A numeric literal with a fraction or an exponent, like `1.5` or `1e8`.
+fn is_float_literal(text: &str) -> bool {
+    let text = text.trim_end_matches("f32").trim_end_matches("f64");
+    text.starts_with(|c: char| c.is_ascii_digit())
+        && text.contains(['.', 'e'])
+        && text.parse::<f64>().is_ok()
+}
+
+/// Whether the expression is a float formatted as it is.
+fn is_float(expression: &str, floats: &HashSet<String>) -> bool {
+    let expression = expression.trim().trim_start_matches(['&', '*']);
+    expression.ends_with(" as f32")
+        || expression.ends_with(" as f64")
+        || is_float_literal(expression)
+        || floats.contains(expression)
+}
+
+/// The arguments of the macro call starting at `open`, its `(`, split at
+/// the top-level commas.
+fn arguments(source: &str, open: usize) -> Vec<String> {
+    let mut arguments = vec![String::new()];
+    let mut depth = 0;
+    let mut chars = source[open..].char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let current = arguments.last_mut().unwrap();
+        match c {
+            '"' => {
+                current.push(c);
+                while let Some((_, c)) = chars.next() {
+                    current.push(c);
+                    match c {
+                        '\\' => current.extend(chars.next().map(|(_, c)| c)),
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+            }
+            '\'' => {
+                // a character literal, or a lifetime left as it is
+                let rest: String = source[open + i + 1..].chars().take(3).collect();
+                let length = match rest.chars().next() {
+                    Some('\\') => rest[1..].find('\'').map(|end| end + 2),
+                    Some(_) if rest.chars().nth(1) == Some('\'') => Some(2),
+                    _ => None,
+                };
+                current.push(c);
+                for _ in 0..length.unwrap_or(0) {
+                    current.extend(chars.next().map(|(_, c)| c));
+                }
+            }
+            '(' | '[' | '{' => {
+                depth += 1;
+                if depth > 1 {
+                    current.push(c);
+                }
+            }
+            ')' | ']' | '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return arguments;
+                }
+                current.push(c);
+            }
+            ',' if depth == 1 => arguments.push(String::new()),
+            c => current.push(c),
+        }
+    }
+    arguments
+}
+
+/// The placeholders of a format string literal: their argument, given by
+/// its position or its name, and their format spec.
+fn placeholders(literal: &str) -> Vec<(Result<usize, String>, String)> {
+    let mut placeholders = vec![];
+    let mut next = 0;
+    let mut chars = literal.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '{' {
+            continue;
+        }
+        if chars.peek() == Some(&'{') {
+            chars.next();
+            continue;
+        }
+        let inside: String = chars.by_ref().take_while(|&c| c != '}').collect();
+        let (name, spec) = inside.split_once(':').unwrap_or((&inside, ""));
+        let argument = match name.parse::<usize>() {
+            _ if name.is_empty() => {
+                next += 1;
+                Ok(next - 1)
+            }
+            Ok(position) => Ok(position),
+            Err(_) => Err(String::from(name)),
+        };
+        placeholders.push((argument, String::from(spec)));
+    }
+    placeholders
+}
+
+/// The direct uses of the standard float formatting in a file, as the
+/// lines they are on.
+fn violations(source: &str) -> Vec<usize> {
+    let source = source.split("#[cfg(test)]").next().unwrap_or_default();
+    // the floats declared in each function, from its `fn` to the next one
+    let mut starts: Vec<usize> = source
+        .match_indices("fn ")
+        .map(|(at, _)| at)
+        .filter(|&at| !source[..at].ends_with(is_identifier))
+        .collect();
+    starts.insert(0, 0);
+    let scopes: Vec<(usize, HashSet<String>)> = starts
+        .iter()
+        .enumerate()
+        .map(|(i, &start)| {
+            let end = starts.get(i + 1).copied().unwrap_or(source.len());
+            (start, floats(&source[start..end]))
+        })
+        .collect();
+    let floats = |at: usize| {
+        let (_, floats) = scopes.iter().rev().find(|(start, _)| *start <= at).unwrap();
+        floats
+    };
+    let line_of = |at: usize| source[..at].matches('\n').count() + 1;
+    let mut lines = vec![];
+    for name in MACROS {
+        for (at, _) in source.match_indices(name) {
+            if source[..at].ends_with(is_identifier) {
+                continue;
+            }
+            let mut arguments = arguments(source, at + name.len() - 1);
+            if name.starts_with("write") {
+                arguments.remove(0);
+            }
+            let Some(literal) = arguments.first().map(|literal| literal.trim()) else {
+                continue;
+            };
+            let Some(literal) = literal.strip_prefix('"').and_then(|l| l.strip_suffix('"')) else {
+                continue;
+            };
+            let named = |name: &str| {
+                arguments[1..].iter().find_map(|argument| {
+                    let (left, right) = argument.split_once('=')?;
+                    (left.trim() == name).then_some(right)
+                })
+            };
+            for (argument, spec) in placeholders(literal) {
+                let expression = match &argument {
+                    Ok(position) => arguments.get(position + 1).map(String::as_str),
+                    Err(name) => named(name).or(Some(name.as_str())),
+                };
+                let scientific = spec.ends_with(['e', 'E']);
+                let precision = spec.contains('.');
+                // `{:?}` keeps the decimal point that gnuplot and the
+                // generated literals need
+                let display = !spec.contains('?');
+                if scientific
+                    || precision
+                    || (display && expression.is_some_and(|e| is_float(e, floats(at))))
+                {
+                    lines.push(line_of(at));
+                }
+            }
+        }
+    }
+    for (at, _) in source.match_indices(".to_string()") {
+        let before = &source[..at];
+        let receiver = match before.strip_suffix(')') {
+            Some(inner) => {
+                let open = inner.rfind('(').unwrap_or(0);
+                &inner[open + 1..]
+            }
+            None => {
+                let start = before.trim_end_matches(is_identifier).len();
+                let start = before[..start].trim_end_matches('.').len();
+                &before[start..]
+            }
+        };
+        if is_float(receiver, floats(at)) {
+            lines.push(line_of(at));
+        }
+    }
+    lines.sort();
+    lines.dedup();
+    lines
+}
+
+#[test]
+fn floats_are_not_formatted_directly() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let mut found = vec![];
+    for directory in SOURCES {
+        let mut paths: Vec<_> = fs::read_dir(root.join(directory))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().is_some_and(|e| e == "rs"))
+            .collect();
+        paths.sort();
+        for path in paths {
+            let relative = path.strip_prefix(root).unwrap();
+            if relative == Path::new(FORMATTER) {
+                continue;
+            }
+            let source = fs::read_to_string(&path).unwrap();
+            for line in violations(&source) {
+                found.push(format!("{}:{}", relative.display(), line));
+            }
+        }
+    }
+    assert!(
+        found.is_empty(),
+        "floats formatted without `float::shortest` or `float::scientific`:\n{}",
+        found.join("\n")
+    );
+}
+
+#[test]
+fn direct_float_formatting_is_found() {
+    let source = r#"
+fn print(x: f32, n: usize, name: &str) {
+    println!("{} {}", n, name);
+    println!("{}", float::shortest(x));
+    println!("{:?}", x);
+    println!("{} {}", n, x);
+    println!("{x}");
+    let text = x.to_string();
+    println!("{:e}", n as f64);
+    println!("{}", (n as f32).to_string());
+    write!(f, "{:.2}", 1.5)
+}
+
+fn shadowed(x: &str) {
+    println!("{}", x);
+}
+"#;
+    assert_eq!(violations(source), [6, 7, 8, 9, 10, 11]);
+}
@@ -0,0 +1,288 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+
+/// The library crates whose public items downstream users depend on.
+const CRATES: [&str; 2] = ["parse", "core"];
+
+/// Removes the string and character literals and the comments, so that the
+/// braces left are those of the code.
+fn code_of(line: &str) -> String {
+    let mut code = String::new();
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\\' => {
+                            chars.next();
+                        }
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+                code.push_str("\"\"");
+            }
+            '\'' => {
+                // a character literal, or a lifetime left as it is
+                let rest: String = chars.clone().take(3).collect();
+                let end = match rest.chars().next() {
+                    Some('\\') => rest.find('\'').filter(|&i| i > 1),
+                    Some(_) => rest.find('\'').filter(|&i| i == 1),
+                    None => None,
+                };
+                match end {
+                    Some(end) => {
+                        for _ in 0..=end {
+                            chars.next();
+                        }
+                        code.push_str("' '");
+                    }
+                    None => code.push(c),
+                }
+            }
+            '/' if chars.peek() == Some(&'/') => break,
+            c => code.push(c),
+        }
+    }
+    code
+}
+
+fn depth_change(code: &str) -> i32 {
+    code.chars()
+        .map(|c| match c {
+            '{' => 1,
+            '}' => -1,
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Joins the lines from `*i` until one ends the signature, the first line
+/// ending with `{` or `;`, and moves past them.
+fn signature(lines: &[String], i: &mut usize, depth: &mut i32) -> String {
+    let mut text = String::new();
+    while *i < lines.len() {
+        let part = lines[*i].trim();
+        *depth += depth_change(part);
+        *i += 1;
+        text.push_str(part);
+        text.push(' ');
+        if part.ends_with('{') || part.ends_with(';') || part.ends_with('}') {
+            break;
+        }
+    }
+    let text = match text.find(" = ") {
+        Some(value) if text.starts_with("pub const ") => &text[..value],
+        _ => text.split(" {").next().unwrap_or_default(),
+    };
+    text.trim().trim_end_matches(';').to_string()
+}
+
+/// A public enum with its variants or a public struct with its public
+/// fields, from `*i` to the closing brace.
+fn definition(lines: &[String], i: &mut usize) -> String {
+    let enumeration = lines[*i].trim().starts_with("pub enum ");
+    let mut text = String::new();
+    let mut depth = 0;
+    loop {
+        let part = lines[*i].trim();
+        let closing = depth + depth_change(part) == 0;
+        let kept = depth == 0 || closing || enumeration || part.starts_with("pub ");
+        if kept && !part.is_empty() && !part.starts_with('#') {
+            text.push_str(part);
+            text.push(' ');
+        }
+        depth += depth_change(part);
+        *i += 1;
+        if depth == 0 || *i == lines.len() {
+            return text.trim().to_string();
+        }
+    }
+}
+
+/// The public items of one module, one per line prefixed with its path:
+/// signatures without bodies, whole enums, structs with their public
+/// fields, and the trait implementations.
+fn module_items(path: &str, source: &str) -> Vec<String> {
+    let lines: Vec<String> = source
+        .lines()
+        .take_while(|line| *line != "#[cfg(test)]")
+        .map(code_of)
+        .collect();
+    let mut items = vec![];
+    let mut depth = 0;
+    let mut within = String::new();
+    let mut implementations = vec![];
+    let mut non_exhaustive = false;
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i].trim();
+        if depth == 0 && line.starts_with("impl") {
+            within = signature(&lines, &mut i, &mut depth);
+            if within.contains(" for ") {
+                implementations.push(within.clone());
+            }
+        } else if depth <= 1 && line.starts_with("pub ") && !line.starts_with("pub mod ") {
+            let nested = depth > 0;
+            let whole = line.starts_with("pub enum ") || line.starts_with("pub struct ");
+            let item = match whole && line.ends_with('{') {
+                true => definition(&lines, &mut i),
+                false => signature(&lines, &mut i, &mut depth),
+            };
+            let item = match non_exhaustive {
+                true => format!("#[non_exhaustive] {}", item),
+                false => item,
+            };
+            non_exhaustive = false;
+            match nested {
+                true => items.push(format!("{}: {} {{ {} }}", path, within, item)),
+                false => items.push(format!("{}: {}", path, item)),
+            }
+        } else {
+            non_exhaustive |= line == "#[non_exhaustive]";
+            depth += depth_change(line);
+            i += 1;
+        }
+    }
+    // the trait implementations of the public types only
+    let public: Vec<String> = items
+        .iter()
+        .filter_map(|item| {
+            let (_, rest) = item.split_once("pub ")?;
+            let name = rest
+                .strip_prefix("enum ")
+                .or_else(|| rest.strip_prefix("struct "))?;
+            Some(name.split(['<', ' ', '(']).next()?.to_string())
+        })
+        .collect();
+    for implementation in implementations {
+        let target = implementation.rsplit(" for ").next().unwrap_or_default();
+        if public
+            .iter()
+            .any(|name| target.split('<').next() == Some(name))
+        {
+            items.push(format!("{}: {}", path, implementation));
+        }
+    }
+    items
+}
+
+/// Every public item of a crate, the modules in the order of their files,
+/// including the private ones whose items are re-exported.
+fn crate_items(name: &str) -> Vec<String> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join(name).join("src");
+    let library = fs::read_to_string(root.join("lib.rs")).unwrap();
+    let mut items = module_items("crate", &library);
+    for line in library.lines() {
+        let Some(module) = line
+            .trim_start_matches("pub ")
+            .strip_prefix("mod ")
+            .and_then(|rest| rest.strip_suffix(';'))
+        else {
+            continue;
+        };
+        let source = fs::read_to_string(root.join(format!("{}.rs", module))).unwrap();
+        items.extend(module_items(module, &source));
+    }
+    items
+}
+
+/// Fails when a public item of a library crate changes, so that API changes
+/// are deliberate: review the difference, then rerun with `UPDATE_API=1` to
+/// record the new snapshot, and bump the version when an item changed or
+/// disappeared.
+#[test]
+fn public_api_matches_the_snapshot() {
+    for name in CRATES {
+        let snapshot = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("api")
+            .join(format!("{}.txt", name));
+        let current = crate_items(name).join("\n") + "\n";
+        if env::var_os("UPDATE_API").is_some() {
+            fs::write(&snapshot, &current).unwrap();
+            continue;
+        }
+        let recorded = fs::read_to_string(&snapshot).unwrap_or_default();
+        let removed: Vec<&str> = recorded
+            .lines()
+            .filter(|line| !current.lines().any(|item| item == *line))
+            .collect();
+        let added: Vec<&str> = current
+            .lines()
+            .filter(|line| !recorded.lines().any(|item| item == *line))
+            .collect();
+        assert!(
+            removed.is_empty() && added.is_empty(),
+            "the public API of computor-{} changed, rerun with UPDATE_API=1 once reviewed\nremoved:\n{}\nadded:\n{}",
+            name,
+            removed.join("\n"),
+            added.join("\n")
+        );
+    }
+}
+
+#[test]
+fn items_are_read_from_the_signatures() {
+    let source = "use std::fmt;
+
+/// A doc comment with {
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Kind {
+    /// first
+    Plain,
+    Named { value: f32 },
+}
+
+pub struct Pair {
+    pub left: f32,
+    right: f32,
+}
+
+impl Pair {
+    pub fn new(left: f32) -> Pair {
+        let text = \"}\";
+        Pair { left, right: 0.0 }
+    }
+
+    fn private(&self) {}
+
+    pub fn wrapped(
+        &self,
+        c: char,
+    ) -> char {
+        '{'
+    }
+}
+
+impl fmt::Display for Hidden {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, \"hidden\")
+    }
+}
+
+impl fmt::Display for Pair {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, \"{{\")
+    }
+}
+
+fn private() {}
+
+pub const LIMIT: usize = 3;
+";
+    assert_eq!(
+        module_items("pair", source),
+        [
+            "pair: #[non_exhaustive] pub enum Kind { Plain, Named { value: f32 }, }",
+            "pair: pub struct Pair { pub left: f32, }",
+            "pair: impl Pair { pub fn new(left: f32) -> Pair }",
+            "pair: impl Pair { pub fn wrapped( &self, c: char, ) -> char }",
+            "pair: pub const LIMIT: usize",
+            "pair: impl fmt::Display for Pair",
+        ]
+    );
+}